@@ -0,0 +1,51 @@
+// Quote a mint from a basket and build the invocations a wallet would simulate against a sandbox:
+//   cargo run --example quote_mint -- <minter-burner> <ctoken> <account> <amount> <token>:<units>...
+// A wallet would simulate get_basket() and decode the result with types::decode_basket(); this
+// example takes the basket on the command line so it runs without an RPC endpoint.
+use constellation_client::minter_burner::{quote_mint, MinterBurnerClient};
+use constellation_client::token::TokenClient;
+use constellation_client::types::Component;
+use constellation_client::xdr::{HostFunction, ScAddress};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() < 5 {
+        return Err(
+            "usage: quote_mint <minter-burner> <ctoken> <account> <amount> <component>:<units>..."
+                .into(),
+        );
+    }
+    let minter_burner: ScAddress = args[0].parse()?;
+    let ctoken: ScAddress = args[1].parse()?;
+    let account: ScAddress = args[2].parse()?;
+    let amount: i128 = args[3].parse()?;
+
+    let get_basket = TokenClient::new(ctoken.clone()).get_basket()?;
+    println!("{:#?}", HostFunction::InvokeContract(get_basket));
+
+    let mut basket = Vec::new();
+    for arg in &args[4..] {
+        let (address, units) = arg.split_once(':').ok_or("expected <component>:<units>")?;
+        basket.push(Component {
+            address: address.parse()?,
+            units: units.parse()?,
+            paused: false,
+        });
+    }
+    let quote = quote_mint(&basket, amount);
+    for (component, required) in &quote {
+        println!("{component}: {required}");
+    }
+
+    let max_amounts_in: Vec<i128> = quote.iter().map(|(_, required)| *required).collect();
+    let mint = MinterBurnerClient::new(minter_burner).mint_with_limits(
+        &account,
+        &account,
+        &ctoken,
+        amount,
+        &max_amounts_in,
+        0,
+    )?;
+    println!("{:#?}", HostFunction::InvokeContract(mint));
+    Ok(())
+}
@@ -0,0 +1,94 @@
+// Decoding of the issuance events published through constellation_shared::events
+// Topic names must match the symbols defined there.
+use constellation_shared::events::EVENT_VERSION;
+
+use crate::xdr::{ScAddress, ScVal};
+use crate::{as_address, as_bool, as_i128, as_map, as_u32, as_u64, as_vec, field, Error, Result};
+
+const ISSUANCE: &str = "issuance";
+const ISSUE: &str = "issue";
+const REDEEM: &str = "redeem";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IssuanceEvent {
+    Issue {
+        // Position in the MinterBurner's event sequence
+        seq: u64,
+        ctoken: ScAddress,
+        from: ScAddress,
+        to: ScAddress,
+        amount: i128,
+        via_allowance: bool,
+    },
+    Redeem {
+        seq: u64,
+        ctoken: ScAddress,
+        from: ScAddress,
+        to: ScAddress,
+        amount: i128,
+        // Each component released and its amount
+        released: Vec<(ScAddress, i128)>,
+    },
+}
+
+// None for events that aren't ("issuance", "issue" | "redeem", ctoken, from)
+pub fn decode_issuance_event(topics: &[ScVal], data: &ScVal) -> Result<Option<IssuanceEvent>> {
+    if topics.len() != 4 || !is_symbol(&topics[0], ISSUANCE) {
+        return Ok(None);
+    }
+    let is_issue = is_symbol(&topics[1], ISSUE);
+    if !is_issue && !is_symbol(&topics[1], REDEEM) {
+        return Ok(None);
+    }
+
+    let ctoken = as_address(&topics[2], "ctoken topic")?;
+    let from = as_address(&topics[3], "from topic")?;
+    let map = as_map(data, "issuance event")?;
+    let version = as_u32(field(map, "version", "event version")?, "event version")?;
+    if version != EVENT_VERSION {
+        return Err(Error::UnsupportedEventVersion(version));
+    }
+    let seq = as_u64(field(map, "seq", "event seq")?, "event seq")?;
+    let to = as_address(field(map, "to", "event.to")?, "event.to")?;
+    let amount = as_i128(field(map, "amount", "event.amount")?, "event.amount")?;
+
+    if is_issue {
+        let via_allowance = as_bool(field(map, "via_allowance", "IssueEvent")?, "IssueEvent")?;
+        Ok(Some(IssuanceEvent::Issue {
+            seq,
+            ctoken,
+            from,
+            to,
+            amount,
+            via_allowance,
+        }))
+    } else {
+        let components = as_vec(field(map, "components", "RedeemEvent")?, "RedeemEvent")?;
+        let amounts = as_vec(field(map, "released", "RedeemEvent")?, "RedeemEvent")?;
+        if components.len() != amounts.len() {
+            return Err(Error::UnexpectedValue("RedeemEvent"));
+        }
+        let released = components
+            .iter()
+            .zip(amounts.iter())
+            .map(|(component, amount)| {
+                Ok((
+                    as_address(component, "RedeemEvent.components")?,
+                    as_i128(amount, "RedeemEvent.released")?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Some(IssuanceEvent::Redeem {
+            seq,
+            ctoken,
+            from,
+            to,
+            amount,
+            released,
+        }))
+    }
+}
+
+fn is_symbol(value: &ScVal, name: &str) -> bool {
+    matches!(value, ScVal::Symbol(symbol) if symbol.0.as_slice() == name.as_bytes())
+}
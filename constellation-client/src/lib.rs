@@ -0,0 +1,132 @@
+// Off-chain client for the Constellation contracts.
+// Builders return the InvokeContractArgs for one contract call, ready to be wrapped in a
+// HostFunction and simulated or submitted through any Soroban RPC client.
+// Decoders turn the ScVal results and event payloads back into plain Rust types.
+pub mod events;
+pub mod minter_burner;
+mod test;
+pub mod token;
+pub mod types;
+
+pub use stellar_xdr::curr as xdr;
+
+use xdr::{InvokeContractArgs, ScAddress, ScMap, ScSymbol, ScVal, VecM};
+
+#[derive(Debug)]
+pub enum Error {
+    // An argument didn't fit its XDR type, e.g. a function name longer than 32 bytes
+    Xdr(xdr::Error),
+    // A returned value didn't have the shape of the expected contract type
+    UnexpectedValue(&'static str),
+    // An event payload carries a version this crate doesn't know how to decode
+    UnsupportedEventVersion(u32),
+}
+
+impl From<xdr::Error> for Error {
+    fn from(error: xdr::Error) -> Self {
+        Error::Xdr(error)
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Xdr(error) => write!(f, "xdr error: {error}"),
+            Error::UnexpectedValue(expected) => write!(f, "unexpected value, expected {expected}"),
+            Error::UnsupportedEventVersion(version) => {
+                write!(f, "unsupported event version {version}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+pub(crate) fn invoke(
+    contract: &ScAddress,
+    function: &str,
+    args: Vec<ScVal>,
+) -> Result<InvokeContractArgs> {
+    Ok(InvokeContractArgs {
+        contract_address: contract.clone(),
+        function_name: ScSymbol(function.try_into()?),
+        args: args.try_into()?,
+    })
+}
+
+pub(crate) fn address(address: &ScAddress) -> ScVal {
+    ScVal::Address(address.clone())
+}
+
+pub(crate) fn vec<T: Clone + Into<ScVal>>(items: &[T]) -> Result<ScVal> {
+    let items: Vec<ScVal> = items.iter().cloned().map(Into::into).collect();
+    Ok(ScVal::Vec(Some(xdr::ScVec(items.try_into()?))))
+}
+
+pub(crate) fn address_vec(addresses: &[ScAddress]) -> Result<ScVal> {
+    let items: Vec<ScVal> = addresses.iter().map(address).collect();
+    Ok(ScVal::Vec(Some(xdr::ScVec(items.try_into()?))))
+}
+
+// Items of an ScVal::Vec result
+pub(crate) fn as_vec<'a>(value: &'a ScVal, expected: &'static str) -> Result<&'a VecM<ScVal>> {
+    match value {
+        ScVal::Vec(Some(items)) => Ok(&items.0),
+        _ => Err(Error::UnexpectedValue(expected)),
+    }
+}
+
+// contracttype structs are encoded as maps keyed by field name
+pub(crate) fn as_map<'a>(value: &'a ScVal, expected: &'static str) -> Result<&'a ScMap> {
+    match value {
+        ScVal::Map(Some(map)) => Ok(map),
+        _ => Err(Error::UnexpectedValue(expected)),
+    }
+}
+
+pub(crate) fn field<'a>(map: &'a ScMap, name: &str, expected: &'static str) -> Result<&'a ScVal> {
+    map.0
+        .iter()
+        .find(
+            |entry| matches!(&entry.key, ScVal::Symbol(key) if key.0.as_slice() == name.as_bytes()),
+        )
+        .map(|entry| &entry.val)
+        .ok_or(Error::UnexpectedValue(expected))
+}
+
+pub(crate) fn as_address(value: &ScVal, expected: &'static str) -> Result<ScAddress> {
+    match value {
+        ScVal::Address(address) => Ok(address.clone()),
+        _ => Err(Error::UnexpectedValue(expected)),
+    }
+}
+
+pub(crate) fn as_i128(value: &ScVal, expected: &'static str) -> Result<i128> {
+    match value {
+        ScVal::I128(parts) => Ok(parts.into()),
+        _ => Err(Error::UnexpectedValue(expected)),
+    }
+}
+
+pub(crate) fn as_u64(value: &ScVal, expected: &'static str) -> Result<u64> {
+    match value {
+        ScVal::U64(value) => Ok(*value),
+        _ => Err(Error::UnexpectedValue(expected)),
+    }
+}
+
+pub(crate) fn as_u32(value: &ScVal, expected: &'static str) -> Result<u32> {
+    match value {
+        ScVal::U32(value) => Ok(*value),
+        _ => Err(Error::UnexpectedValue(expected)),
+    }
+}
+
+pub(crate) fn as_bool(value: &ScVal, expected: &'static str) -> Result<bool> {
+    match value {
+        ScVal::Bool(value) => Ok(*value),
+        _ => Err(Error::UnexpectedValue(expected)),
+    }
+}
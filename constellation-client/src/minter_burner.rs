@@ -0,0 +1,151 @@
+// Invocations of the MinterBurner contract
+use crate::types::Component;
+use crate::xdr::{InvokeContractArgs, ScAddress};
+use crate::{address, invoke, vec, Result};
+
+#[derive(Clone, Debug)]
+pub struct MinterBurnerClient {
+    pub contract: ScAddress,
+}
+
+impl MinterBurnerClient {
+    pub fn new(contract: ScAddress) -> Self {
+        MinterBurnerClient { contract }
+    }
+
+    pub fn mint(
+        &self,
+        from: &ScAddress,
+        to: &ScAddress,
+        ctoken: &ScAddress,
+        ctoken_amount: i128,
+    ) -> Result<InvokeContractArgs> {
+        invoke(
+            &self.contract,
+            "mint",
+            vec![
+                address(from),
+                address(to),
+                address(ctoken),
+                ctoken_amount.into(),
+            ],
+        )
+    }
+
+    // 'max_amounts_in' in basket order, see quote_mint()
+    pub fn mint_with_limits(
+        &self,
+        from: &ScAddress,
+        to: &ScAddress,
+        ctoken: &ScAddress,
+        ctoken_amount: i128,
+        max_amounts_in: &[i128],
+        deadline_ledger: u32,
+    ) -> Result<InvokeContractArgs> {
+        invoke(
+            &self.contract,
+            "mint_with_limits",
+            vec![
+                address(from),
+                address(to),
+                address(ctoken),
+                ctoken_amount.into(),
+                vec(max_amounts_in)?,
+                deadline_ledger.into(),
+            ],
+        )
+    }
+
+    // Decode the result with types::decode_shortfalls()
+    pub fn diagnose_mint(
+        &self,
+        from: &ScAddress,
+        ctoken: &ScAddress,
+        ctoken_amount: i128,
+    ) -> Result<InvokeContractArgs> {
+        invoke(
+            &self.contract,
+            "diagnose_mint",
+            vec![address(from), address(ctoken), ctoken_amount.into()],
+        )
+    }
+
+    pub fn burn(
+        &self,
+        from: &ScAddress,
+        ctoken: &ScAddress,
+        ctoken_amount: i128,
+    ) -> Result<InvokeContractArgs> {
+        invoke(
+            &self.contract,
+            "burn",
+            vec![address(from), address(ctoken), ctoken_amount.into()],
+        )
+    }
+
+    pub fn burn_with_limits(
+        &self,
+        from: &ScAddress,
+        ctoken: &ScAddress,
+        ctoken_amount: i128,
+        min_amounts_out: &[i128],
+        deadline_ledger: u32,
+    ) -> Result<InvokeContractArgs> {
+        invoke(
+            &self.contract,
+            "burn_with_limits",
+            vec![
+                address(from),
+                address(ctoken),
+                ctoken_amount.into(),
+                vec(min_amounts_out)?,
+                deadline_ledger.into(),
+            ],
+        )
+    }
+
+    pub fn burn_to(
+        &self,
+        from: &ScAddress,
+        to: &ScAddress,
+        ctoken: &ScAddress,
+        ctoken_amount: i128,
+    ) -> Result<InvokeContractArgs> {
+        invoke(
+            &self.contract,
+            "burn_to",
+            vec![
+                address(from),
+                address(to),
+                address(ctoken),
+                ctoken_amount.into(),
+            ],
+        )
+    }
+
+    pub fn get_issuance_cap(&self, ctoken: &ScAddress) -> Result<InvokeContractArgs> {
+        invoke(&self.contract, "get_issuance_cap", vec![address(ctoken)])
+    }
+
+    pub fn set_issuance_cap(&self, ctoken: &ScAddress, cap: i128) -> Result<InvokeContractArgs> {
+        invoke(
+            &self.contract,
+            "set_issuance_cap",
+            vec![address(ctoken), cap.into()],
+        )
+    }
+}
+
+// Component amounts mint() pulls for 'ctoken_amount', in basket order
+// Mirrors the contract: amount * units per component
+pub fn quote_mint(basket: &[Component], ctoken_amount: i128) -> Vec<(ScAddress, i128)> {
+    basket
+        .iter()
+        .map(|component| {
+            (
+                component.address.clone(),
+                ctoken_amount * component.units as i128,
+            )
+        })
+        .collect()
+}
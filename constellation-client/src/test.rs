@@ -0,0 +1,81 @@
+#![cfg(test)]
+
+use crate::types::{decode_basket, AuctionParams, Component};
+use crate::xdr::{Hash, ScAddress, ScMap, ScVal, ScVec};
+use crate::Error;
+
+fn contract(byte: u8) -> ScAddress {
+    ScAddress::Contract(Hash([byte; 32]))
+}
+
+#[test]
+fn test_component_round_trip() {
+    let component = Component {
+        address: contract(1),
+        units: 3,
+        paused: true,
+    };
+    let value = component.to_scval().unwrap();
+    assert_eq!(Component::from_scval(&value).unwrap(), component);
+
+    // Keys are sorted, as the host requires of contracttype maps
+    let ScVal::Map(Some(map)) = &value else {
+        panic!("not a map")
+    };
+    let keys: Vec<&ScVal> = map.0.iter().map(|entry| &entry.key).collect();
+    let mut sorted = keys.clone();
+    sorted.sort();
+    assert_eq!(keys, sorted);
+
+    let other = Component {
+        address: contract(2),
+        units: 1,
+        paused: false,
+    };
+    let basket = ScVal::Vec(Some(ScVec(
+        vec![value, other.to_scval().unwrap()].try_into().unwrap(),
+    )));
+    assert_eq!(decode_basket(&basket).unwrap(), vec![component, other]);
+}
+
+#[test]
+fn test_auction_params_round_trip() {
+    let params = AuctionParams {
+        start_price: 10_000_000,
+        min_price: -1,
+        duration_ledgers: 100,
+        min_fill: i128::MAX,
+    };
+    let value = params.to_scval().unwrap();
+    assert_eq!(AuctionParams::from_scval(&value).unwrap(), params);
+}
+
+#[test]
+fn test_decoding_rejects_other_shapes() {
+    assert!(matches!(
+        Component::from_scval(&ScVal::U32(1)),
+        Err(Error::UnexpectedValue("Component"))
+    ));
+    assert!(matches!(
+        decode_basket(&ScVal::Void),
+        Err(Error::UnexpectedValue("Vec<Component>"))
+    ));
+
+    // A field with the wrong type, and a missing field
+    let params = AuctionParams {
+        start_price: 1,
+        min_price: 1,
+        duration_ledgers: 1,
+        min_fill: 1,
+    };
+    let ScVal::Map(Some(map)) = params.to_scval().unwrap() else {
+        panic!("not a map")
+    };
+    let mut entries = map.0.to_vec();
+    entries[0].val = 7_i128.into();
+    let wrong_type = ScVal::Map(Some(ScMap(entries.clone().try_into().unwrap())));
+    assert!(AuctionParams::from_scval(&wrong_type).is_err());
+    entries.remove(0);
+    let missing = ScVal::Map(Some(ScMap(entries.try_into().unwrap())));
+    assert!(AuctionParams::from_scval(&missing).is_err());
+}
@@ -0,0 +1,101 @@
+// Invocations of a Constellation Token contract
+use crate::types::AuctionParams;
+use crate::xdr::{InvokeContractArgs, ScAddress, ScVal};
+use crate::{address, address_vec, invoke, vec, Result};
+
+#[derive(Clone, Debug)]
+pub struct TokenClient {
+    pub contract: ScAddress,
+}
+
+impl TokenClient {
+    pub fn new(contract: ScAddress) -> Self {
+        TokenClient { contract }
+    }
+
+    // Decode the result with types::decode_basket()
+    pub fn get_basket(&self) -> Result<InvokeContractArgs> {
+        invoke(&self.contract, "get_basket", vec![])
+    }
+
+    pub fn get_components_page(&self, start: u32, limit: u32) -> Result<InvokeContractArgs> {
+        invoke(
+            &self.contract,
+            "get_components_page",
+            vec![start.into(), limit.into()],
+        )
+    }
+
+    pub fn total_supply(&self) -> Result<InvokeContractArgs> {
+        invoke(&self.contract, "total_supply", vec![])
+    }
+
+    pub fn balance(&self, id: &ScAddress) -> Result<InvokeContractArgs> {
+        invoke(&self.contract, "balance", vec![address(id)])
+    }
+
+    pub fn nav_per_token(&self) -> Result<InvokeContractArgs> {
+        invoke(&self.contract, "nav_per_token", vec![])
+    }
+
+    pub fn implied_price(&self, quote: &ScAddress, oracle: &ScAddress) -> Result<InvokeContractArgs> {
+        invoke(
+            &self.contract,
+            "implied_price",
+            vec![address(quote), address(oracle)],
+        )
+    }
+
+    pub fn propose_rebalance(
+        &self,
+        target_components: &[ScAddress],
+        target_amounts: &[u32],
+        auction_params: &[AuctionParams],
+        intermediate_token: &ScAddress,
+    ) -> Result<InvokeContractArgs> {
+        let params = auction_params
+            .iter()
+            .map(AuctionParams::to_scval)
+            .collect::<Result<Vec<ScVal>>>()?;
+        invoke(
+            &self.contract,
+            "propose_rebalance",
+            vec![
+                address_vec(target_components)?,
+                vec(target_amounts)?,
+                vec(&params)?,
+                address(intermediate_token),
+            ],
+        )
+    }
+
+    pub fn start_rebalance(&self, caller: &ScAddress) -> Result<InvokeContractArgs> {
+        invoke(&self.contract, "start_rebalance", vec![address(caller)])
+    }
+
+    pub fn bid(
+        &self,
+        bidder: &ScAddress,
+        component: &ScAddress,
+        amount: i128,
+    ) -> Result<InvokeContractArgs> {
+        invoke(
+            &self.contract,
+            "bid",
+            vec![address(bidder), address(component), amount.into()],
+        )
+    }
+
+    // Decode the result with types::AuctionStatus::from_scval()
+    pub fn auction_status(&self, component: &ScAddress) -> Result<InvokeContractArgs> {
+        invoke(&self.contract, "auction_status", vec![address(component)])
+    }
+
+    pub fn finalize_rebalance(&self) -> Result<InvokeContractArgs> {
+        invoke(&self.contract, "finalize_rebalance", vec![])
+    }
+
+    pub fn cancel_rebalance(&self) -> Result<InvokeContractArgs> {
+        invoke(&self.contract, "cancel_rebalance", vec![])
+    }
+}
@@ -0,0 +1,155 @@
+// Plain Rust mirrors of the contracttype structs returned by the Constellation contracts.
+// Field names must match the contract definitions, they are the map keys on the wire.
+use crate::xdr::{ScAddress, ScMap, ScMapEntry, ScSymbol, ScVal};
+use crate::{address, as_address, as_bool, as_i128, as_map, as_u32, as_vec, field, Result};
+
+// constellation-token component::Component
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Component {
+    pub address: ScAddress,
+    pub units: u32,
+    pub paused: bool,
+}
+
+impl Component {
+    pub fn from_scval(value: &ScVal) -> Result<Self> {
+        let map = as_map(value, "Component")?;
+        Ok(Component {
+            address: as_address(
+                field(map, "address", "Component.address")?,
+                "Component.address",
+            )?,
+            units: as_u32(field(map, "units", "Component.units")?, "Component.units")?,
+            paused: as_bool(
+                field(map, "paused", "Component.paused")?,
+                "Component.paused",
+            )?,
+        })
+    }
+
+    pub fn to_scval(&self) -> Result<ScVal> {
+        // Entries sorted by key, as the host requires
+        let entries = vec![
+            entry("address", address(&self.address))?,
+            entry("paused", self.paused.into())?,
+            entry("units", self.units.into())?,
+        ];
+        Ok(ScVal::Map(Some(ScMap(entries.try_into()?))))
+    }
+}
+
+// Decodes the result of get_basket() and get_components_page()
+pub fn decode_basket(value: &ScVal) -> Result<Vec<Component>> {
+    as_vec(value, "Vec<Component>")?
+        .iter()
+        .map(Component::from_scval)
+        .collect()
+}
+
+// constellation-token auction::AuctionParams
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionParams {
+    pub start_price: i128,
+    pub min_price: i128,
+    pub duration_ledgers: u32,
+    // 0 lets the contract derive it from the component's decimals
+    pub min_fill: i128,
+}
+
+impl AuctionParams {
+    pub fn to_scval(&self) -> Result<ScVal> {
+        // Entries sorted by key, as the host requires
+        let entries = vec![
+            entry("duration_ledgers", self.duration_ledgers.into())?,
+            entry("min_fill", self.min_fill.into())?,
+            entry("min_price", self.min_price.into())?,
+            entry("start_price", self.start_price.into())?,
+        ];
+        Ok(ScVal::Map(Some(ScMap(entries.try_into()?))))
+    }
+
+    pub fn from_scval(value: &ScVal) -> Result<Self> {
+        let map = as_map(value, "AuctionParams")?;
+        Ok(AuctionParams {
+            start_price: as_i128(field(map, "start_price", "AuctionParams")?, "AuctionParams")?,
+            min_price: as_i128(field(map, "min_price", "AuctionParams")?, "AuctionParams")?,
+            duration_ledgers: as_u32(
+                field(map, "duration_ledgers", "AuctionParams")?,
+                "AuctionParams",
+            )?,
+            min_fill: as_i128(field(map, "min_fill", "AuctionParams")?, "AuctionParams")?,
+        })
+    }
+}
+
+// constellation-token auction::AuctionStatus
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionStatus {
+    pub component: ScAddress,
+    pub current_amount: u32,
+    pub target_amount: u32,
+    pub filled: i128,
+    pub remaining: i128,
+    pub current_price: i128,
+    pub reached_min: bool,
+    pub min_fill: i128,
+}
+
+impl AuctionStatus {
+    pub fn from_scval(value: &ScVal) -> Result<Self> {
+        let map = as_map(value, "AuctionStatus")?;
+        Ok(AuctionStatus {
+            component: as_address(field(map, "component", "AuctionStatus")?, "AuctionStatus")?,
+            current_amount: as_u32(
+                field(map, "current_amount", "AuctionStatus")?,
+                "AuctionStatus",
+            )?,
+            target_amount: as_u32(
+                field(map, "target_amount", "AuctionStatus")?,
+                "AuctionStatus",
+            )?,
+            filled: as_i128(field(map, "filled", "AuctionStatus")?, "AuctionStatus")?,
+            remaining: as_i128(field(map, "remaining", "AuctionStatus")?, "AuctionStatus")?,
+            current_price: as_i128(
+                field(map, "current_price", "AuctionStatus")?,
+                "AuctionStatus",
+            )?,
+            reached_min: as_bool(field(map, "reached_min", "AuctionStatus")?, "AuctionStatus")?,
+            min_fill: as_i128(field(map, "min_fill", "AuctionStatus")?, "AuctionStatus")?,
+        })
+    }
+}
+
+// constellation-minter-burner diagnostics::Shortfall
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Shortfall {
+    pub component: ScAddress,
+    pub required: i128,
+    pub available: i128,
+}
+
+impl Shortfall {
+    pub fn from_scval(value: &ScVal) -> Result<Self> {
+        let map = as_map(value, "Shortfall")?;
+        Ok(Shortfall {
+            component: as_address(field(map, "component", "Shortfall")?, "Shortfall")?,
+            required: as_i128(field(map, "required", "Shortfall")?, "Shortfall")?,
+            available: as_i128(field(map, "available", "Shortfall")?, "Shortfall")?,
+        })
+    }
+}
+
+// Decodes the result of diagnose_mint()
+pub fn decode_shortfalls(value: &ScVal) -> Result<Vec<Shortfall>> {
+    as_vec(value, "Vec<Shortfall>")?
+        .iter()
+        .map(Shortfall::from_scval)
+        .collect()
+}
+
+fn entry(key: &str, val: ScVal) -> Result<ScMapEntry> {
+    Ok(ScMapEntry {
+        key: ScVal::Symbol(ScSymbol(key.try_into()?)),
+        val,
+    })
+}
@@ -0,0 +1,26 @@
+// Same as admin.rs in the Constellation Token contract
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::error::Error;
+use crate::storage_types::DataKey;
+
+pub fn has_administrator(e: &Env) -> bool {
+    let key = DataKey::Admin;
+    e.storage().instance().has(&key)
+}
+
+pub fn require_initialized(e: &Env) {
+    if !has_administrator(e) {
+        panic_with_error!(e, Error::NotInitialized);
+    }
+}
+
+pub fn read_administrator(e: &Env) -> Address {
+    let key = DataKey::Admin;
+    e.storage().instance().get(&key).unwrap()
+}
+
+pub fn write_administrator(e: &Env, id: &Address) {
+    let key = DataKey::Admin;
+    e.storage().instance().set(&key, id);
+}
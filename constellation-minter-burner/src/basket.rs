@@ -0,0 +1,87 @@
+// A ctoken's components and units, read with one get_basket() call per invocation.
+// The mint and redeem paths pass the Basket to the amount calculation, the transfer loop and the event
+// instead of calling getComponents() and getAmounts() again, each of which is a cross-contract call.
+use constellation_shared::events::emit_component_error;
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+use crate::error::Error;
+use crate::{component_amount, constellation_token};
+
+pub struct Basket {
+    pub components: Vec<Address>,
+    pub amounts: Vec<u32>,
+    // getComponents() indices of the components whose issuance is paused
+    paused: Vec<u32>,
+}
+
+impl Basket {
+    // Component amounts backing 'ctoken_amount', in getComponents() order
+    pub fn required(&self, env: &Env, ctoken_amount: i128) -> Vec<i128> {
+        let mut required = Vec::new(env);
+        for units in self.amounts.iter() {
+            required.push_back(component_amount(ctoken_amount, units));
+        }
+        required
+    }
+
+    // The most ctokens 'component_amounts' (in getComponents() order) can back
+    pub fn max_mintable(&self, component_amounts: &Vec<i128>) -> i128 {
+        if component_amounts.len() != self.amounts.len() {
+            panic!("component_amounts must have one amount per component")
+        }
+        let mut ctoken_amount: Option<i128> = None;
+        for i in 0..self.amounts.len() {
+            let units = self.amounts.get(i).unwrap();
+            // Components entering in a rebalance have no units yet and don't limit the mint
+            if units == 0 {
+                continue;
+            }
+            let backed = component_amounts.get(i).unwrap() / units as i128;
+            ctoken_amount = Some(ctoken_amount.map_or(backed, |amount| amount.min(backed)));
+        }
+        match ctoken_amount {
+            Some(amount) if amount > 0 => amount,
+            _ => panic!("component amounts are too small to mint"),
+        }
+    }
+}
+
+pub fn read_basket(env: &Env, ctoken_client: &constellation_token::Client) -> Basket {
+    let mut components = Vec::new(env);
+    let mut amounts = Vec::new(env);
+    let mut paused = Vec::new(env);
+    for component in ctoken_client.get_basket().iter() {
+        if component.paused {
+            paused.push_back(components.len());
+        }
+        components.push_back(component.address);
+        amounts.push_back(component.units);
+    }
+    Basket {
+        components,
+        amounts,
+        paused,
+    }
+}
+
+// Traps with WindDown once the ctoken's wind-down has taken effect, and with ComponentPaused if any
+// component's issuance is paused, after an ("error", code) event naming each paused component and its
+// index. Redemption is unaffected.
+pub fn read_mintable_basket(env: &Env, ctoken_client: &constellation_token::Client) -> Basket {
+    if ctoken_client.is_winding_down() {
+        panic_with_error!(env, Error::WindDown);
+    }
+    let basket = read_basket(env, ctoken_client);
+    if !basket.paused.is_empty() {
+        for index in basket.paused.iter() {
+            emit_component_error(
+                env,
+                Error::ComponentPaused as u32,
+                basket.components.get(index).unwrap(),
+                index,
+            );
+        }
+        panic_with_error!(env, Error::ComponentPaused);
+    }
+    basket
+}
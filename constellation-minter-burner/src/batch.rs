@@ -0,0 +1,16 @@
+// Redemption of several ctokens for one holder in one transaction with burn_ctoken_batch().
+// Every request is a full burn_with_limits() redemption, so any failing request reverts the whole batch.
+// Transfers of a component shared by several baskets aren't merged. Each ctoken contract holds its own
+// reserve and is the only account that can release it, so every ctoken has to make its own transfer to
+// 'from'. Routing them through the MinterBurner to send one merged transfer would add a call per ctoken,
+// not save one.
+use soroban_sdk::{contracttype, Address, Vec};
+
+#[derive(Clone)]
+#[contracttype]
+pub struct BurnRequest {
+    pub ctoken: Address,
+    pub amount: i128,
+    // In the ctoken's getComponents() order, see limits.rs
+    pub min_amounts_out: Vec<i128>,
+}
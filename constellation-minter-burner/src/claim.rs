@@ -0,0 +1,66 @@
+// Components owed to a user whose transfer failed during burn_with_queue.
+// They stay in the ConstellationToken contract until the user claims them.
+// The total queued per (ctoken, component) is tracked too, so the ctoken can tell owed components
+// apart from dust, see queued_claims().
+use crate::storage_types::{ClaimDataKey, DataKey, CLAIM_BUMP_AMOUNT, CLAIM_LIFETIME_THRESHOLD};
+use soroban_sdk::{Address, Env};
+
+pub fn read_claim(e: &Env, owner: Address, ctoken: Address, component: Address) -> i128 {
+    let key = DataKey::PendingClaim(ClaimDataKey {
+        owner,
+        ctoken,
+        component,
+    });
+    if let Some(claim) = e.storage().persistent().get::<DataKey, i128>(&key) {
+        e.storage()
+            .persistent()
+            .bump(&key, CLAIM_LIFETIME_THRESHOLD, CLAIM_BUMP_AMOUNT);
+        claim
+    } else {
+        0
+    }
+}
+
+pub fn receive_claim(e: &Env, owner: Address, ctoken: Address, component: Address, amount: i128) {
+    let claim = read_claim(e, owner.clone(), ctoken.clone(), component.clone());
+    let key = DataKey::PendingClaim(ClaimDataKey {
+        owner,
+        ctoken: ctoken.clone(),
+        component: component.clone(),
+    });
+    e.storage().persistent().set(&key, &(claim + amount));
+    e.storage()
+        .persistent()
+        .bump(&key, CLAIM_LIFETIME_THRESHOLD, CLAIM_BUMP_AMOUNT);
+    write_queued_total(e, ctoken, component, amount);
+}
+
+pub fn remove_claim(e: &Env, owner: Address, ctoken: Address, component: Address) {
+    let claim = read_claim(e, owner.clone(), ctoken.clone(), component.clone());
+    let key = DataKey::PendingClaim(ClaimDataKey {
+        owner,
+        ctoken: ctoken.clone(),
+        component: component.clone(),
+    });
+    e.storage().persistent().remove(&key);
+    write_queued_total(e, ctoken, component, -claim);
+}
+
+// Sum of every pending claim on 'component' held by 'ctoken'
+pub fn read_queued_total(e: &Env, ctoken: Address, component: Address) -> i128 {
+    let key = DataKey::QueuedClaims(ctoken, component);
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+fn write_queued_total(e: &Env, ctoken: Address, component: Address, change: i128) {
+    let total = read_queued_total(e, ctoken.clone(), component.clone()) + change;
+    let key = DataKey::QueuedClaims(ctoken, component);
+    if total == 0 {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, &total);
+        e.storage()
+            .persistent()
+            .bump(&key, CLAIM_LIFETIME_THRESHOLD, CLAIM_BUMP_AMOUNT);
+    }
+}
@@ -0,0 +1,60 @@
+// Every configuration value of this MinterBurner in one read, for auditors checking a deployed instance.
+// Settings kept per ctoken are read with read_ctoken_config(), since registered ctokens can't be listed.
+// Escrows, claims, issuance and sessions are state rather than configuration and are left out.
+// An unset rate limit is an empty Vec and a set one a Vec of one: a contracttype struct can't hold an
+// Option of another contracttype, since it then has no ScVal conversion under testutils.
+use constellation_shared::storage::STORAGE_VERSION;
+use soroban_sdk::{contracttype, vec, Address, Env, Vec};
+
+use crate::admin::read_administrator;
+use crate::issuance::read_issuance_cap;
+use crate::pause::{is_paused, read_allow_redeem_while_paused};
+use crate::rate_limit::{read_rate_limit, RateLimit};
+use crate::registry::{read_bootstrap_config, read_ctoken_decimals, BootstrapConfig};
+use crate::session::read_session_ledgers;
+use crate::ttl::{read_ttl_config, TtlConfig};
+
+#[derive(Clone)]
+#[contracttype]
+pub struct ConfigDump {
+    // constellation_shared::storage::STORAGE_VERSION this contract was built with
+    pub storage_version: u32,
+    pub admin: Address,
+    pub paused: bool,
+    pub allow_redeem_while_paused: bool,
+    pub session_ledgers: u32,
+    pub ttl: TtlConfig,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct CtokenConfigDump {
+    pub decimals: u32,
+    pub bootstrap: BootstrapConfig,
+    pub issuance_cap: Option<i128>,
+    pub rate_limit: Vec<RateLimit>,
+}
+
+pub fn read_config(e: &Env) -> ConfigDump {
+    ConfigDump {
+        storage_version: STORAGE_VERSION,
+        admin: read_administrator(e),
+        paused: is_paused(e),
+        allow_redeem_while_paused: read_allow_redeem_while_paused(e),
+        session_ledgers: read_session_ledgers(e),
+        ttl: read_ttl_config(e),
+    }
+}
+
+// Traps with UnknownCtoken if 'ctoken' isn't registered
+pub fn read_ctoken_config(e: &Env, ctoken: Address) -> CtokenConfigDump {
+    CtokenConfigDump {
+        decimals: read_ctoken_decimals(e, ctoken.clone()),
+        bootstrap: read_bootstrap_config(e, ctoken.clone()),
+        issuance_cap: read_issuance_cap(e, ctoken.clone()),
+        rate_limit: match read_rate_limit(e, ctoken) {
+            Some(rate_limit) => vec![e, rate_limit],
+            None => Vec::new(e),
+        },
+    }
+}
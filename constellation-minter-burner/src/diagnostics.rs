@@ -0,0 +1,41 @@
+// Pre-flight checks for minting. A failed mint only reports an error code, so issue() publishes an
+// ("error", code) event per missing component before trapping, and diagnose_mint() returns the same
+// information to wallets that simulate instead.
+use soroban_sdk::{contracttype, token, Address, Env, Vec};
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Shortfall {
+    pub component: Address,
+    pub required: i128,
+    // What 'from' can provide: its balance, or the smaller of balance and allowance when minting by allowance
+    pub available: i128,
+}
+
+// Components 'from' can't provide 'required' of, in basket order
+pub fn find_shortfalls(
+    e: &Env,
+    from: &Address,
+    components: &Vec<Address>,
+    required: &Vec<i128>,
+    via_allowance: bool,
+) -> Vec<Shortfall> {
+    let mut shortfalls = Vec::new(e);
+    for i in 0..components.len() {
+        let component = components.get(i).unwrap();
+        let amount = required.get(i).unwrap();
+        let client = token::Client::new(e, &component);
+        let mut available = client.balance(from);
+        if via_allowance {
+            available = available.min(client.allowance(from, &e.current_contract_address()));
+        }
+        if available < amount {
+            shortfalls.push_back(Shortfall {
+                component,
+                required: amount,
+                available,
+            });
+        }
+    }
+    shortfalls
+}
@@ -0,0 +1,28 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    IssuanceCapExceeded = 1,
+    InvalidFraction = 2,
+    Reentrancy = 3,
+    NothingToClaim = 4,
+    ComponentPaused = 5,
+    Paused = 6,
+    FeeOnTransferNotSupported = 7,
+    AdminMismatch = 8,
+    UnknownCtoken = 9,
+    InsufficientComponent = 10,
+    DeadlineExpired = 11,
+    SlippageExceeded = 12,
+    NotInitialized = 13,
+    RateLimited = 14,
+    InvalidDecimals = 15,
+    NoSession = 16,
+    SessionExpired = 17,
+    SessionIncomplete = 18,
+    BasketChanged = 19,
+    InitialMintTooSmall = 20,
+    WindDown = 21,
+}
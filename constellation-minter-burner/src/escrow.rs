@@ -0,0 +1,77 @@
+// Component tokens deposited by a user ahead of a mint.
+// Modelled on balance.rs in the Constellation Token contract.
+use crate::storage_types::{
+    DataKey, EscrowDataKey, EscrowValue, ESCROW_BUMP_AMOUNT, ESCROW_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{Address, Env};
+
+pub fn read_escrow(e: &Env, owner: Address, component: Address) -> EscrowValue {
+    let key = DataKey::Escrow(EscrowDataKey { owner, component });
+    if let Some(escrow) = e.storage().persistent().get::<DataKey, EscrowValue>(&key) {
+        e.storage()
+            .persistent()
+            .bump(&key, ESCROW_LIFETIME_THRESHOLD, ESCROW_BUMP_AMOUNT);
+        escrow
+    } else {
+        EscrowValue {
+            amount: 0,
+            expiration_ledger: 0,
+        }
+    }
+}
+
+fn write_escrow(e: &Env, owner: Address, component: Address, escrow: EscrowValue) {
+    let key = DataKey::Escrow(EscrowDataKey { owner, component });
+    if escrow.amount == 0 {
+        e.storage().persistent().remove(&key);
+        return;
+    }
+    e.storage().persistent().set(&key, &escrow);
+    e.storage()
+        .persistent()
+        .bump(&key, ESCROW_LIFETIME_THRESHOLD, ESCROW_BUMP_AMOUNT);
+}
+
+// An expiration_ledger of 0 means the escrow never expires.
+// Topping up an existing escrow replaces its expiration_ledger.
+pub fn receive_escrow(
+    e: &Env,
+    owner: Address,
+    component: Address,
+    amount: i128,
+    expiration_ledger: u32,
+) {
+    if expiration_ledger != 0 && expiration_ledger < e.ledger().sequence() {
+        panic!("expiration_ledger is less than ledger seq")
+    }
+    let escrow = read_escrow(e, owner.clone(), component.clone());
+    write_escrow(
+        e,
+        owner,
+        component,
+        EscrowValue {
+            amount: escrow.amount + amount,
+            expiration_ledger,
+        },
+    );
+}
+
+pub fn spend_escrow(e: &Env, owner: Address, component: Address, amount: i128) {
+    let escrow = read_escrow(e, owner.clone(), component.clone());
+    if escrow.amount < amount {
+        panic!("insufficient escrow");
+    }
+    write_escrow(
+        e,
+        owner,
+        component,
+        EscrowValue {
+            amount: escrow.amount - amount,
+            expiration_ledger: escrow.expiration_ledger,
+        },
+    );
+}
+
+pub fn is_escrow_expired(e: &Env, escrow: &EscrowValue) -> bool {
+    escrow.expiration_ledger != 0 && escrow.expiration_ledger < e.ledger().sequence()
+}
@@ -0,0 +1,58 @@
+// Amount of each ctoken issued through this MinterBurner, and the optional cap on it.
+// Changes also count towards the ctoken's rate limit, see rate_limit.rs.
+use crate::error::Error;
+use crate::rate_limit::{consume_rate_limit, release_rate_limit};
+use crate::storage_types::{DataKey, ISSUANCE_BUMP_AMOUNT, ISSUANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::{panic_with_error, Address, Env};
+
+pub fn read_issuance(e: &Env, ctoken: Address) -> i128 {
+    let key = DataKey::Issuance(ctoken);
+    if let Some(issuance) = e.storage().persistent().get::<DataKey, i128>(&key) {
+        e.storage()
+            .persistent()
+            .bump(&key, ISSUANCE_LIFETIME_THRESHOLD, ISSUANCE_BUMP_AMOUNT);
+        issuance
+    } else {
+        0
+    }
+}
+
+fn write_issuance(e: &Env, ctoken: Address, amount: i128) {
+    let key = DataKey::Issuance(ctoken);
+    e.storage().persistent().set(&key, &amount);
+    e.storage()
+        .persistent()
+        .bump(&key, ISSUANCE_LIFETIME_THRESHOLD, ISSUANCE_BUMP_AMOUNT);
+}
+
+// None means issuance is uncapped
+pub fn read_issuance_cap(e: &Env, ctoken: Address) -> Option<i128> {
+    let key = DataKey::IssuanceCap(ctoken);
+    e.storage().persistent().get::<DataKey, i128>(&key)
+}
+
+pub fn write_issuance_cap(e: &Env, ctoken: Address, cap: i128) {
+    let key = DataKey::IssuanceCap(ctoken);
+    e.storage().persistent().set(&key, &cap);
+    e.storage()
+        .persistent()
+        .bump(&key, ISSUANCE_LIFETIME_THRESHOLD, ISSUANCE_BUMP_AMOUNT);
+}
+
+pub fn increase_issuance(e: &Env, ctoken: Address, amount: i128) {
+    let issuance = read_issuance(e, ctoken.clone()) + amount;
+    if let Some(cap) = read_issuance_cap(e, ctoken.clone()) {
+        if issuance > cap {
+            panic_with_error!(e, Error::IssuanceCapExceeded);
+        }
+    }
+    consume_rate_limit(e, ctoken.clone(), amount);
+    write_issuance(e, ctoken, issuance);
+}
+
+// ctokens minted before tracking started may be redeemed here too, so never go below zero
+pub fn decrease_issuance(e: &Env, ctoken: Address, amount: i128) {
+    let issuance = read_issuance(e, ctoken.clone());
+    release_rate_limit(e, ctoken.clone(), amount);
+    write_issuance(e, ctoken, (issuance - amount).max(0));
+}
@@ -4,22 +4,348 @@
 
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, token, Address, Env, IntoVal};
+mod admin;
+mod basket;
+mod batch;
+mod claim;
+mod config;
+mod diagnostics;
+mod error;
+mod escrow;
+mod issuance;
+mod limits;
+mod pause;
+mod preview;
+mod rate_limit;
+mod reentrancy;
+mod registry;
+mod session;
+mod stats;
+mod storage_types;
+mod test;
+mod ttl;
+
+use crate::admin::{
+    has_administrator, read_administrator, require_initialized, write_administrator,
+};
+use crate::basket::{read_basket, read_mintable_basket};
+use crate::batch::BurnRequest;
+use crate::claim::{read_claim, read_queued_total, receive_claim, remove_claim};
+use crate::config::{read_config, read_ctoken_config, ConfigDump, CtokenConfigDump};
+use crate::diagnostics::{find_shortfalls, Shortfall};
+use crate::error::Error;
+use crate::escrow::{is_escrow_expired, read_escrow, receive_escrow, spend_escrow};
+use crate::issuance::{
+    decrease_issuance, increase_issuance, read_issuance, read_issuance_cap, write_issuance_cap,
+};
+use crate::limits::{check_deadline, check_max_amounts, check_min_amounts};
+use crate::pause::{
+    check_mint_allowed, check_redeem_allowed, is_paused, read_allow_redeem_while_paused,
+    write_allow_redeem_while_paused, write_paused,
+};
+use crate::preview::{BurnPreview, MintPreview};
+use crate::rate_limit::{
+    read_rate_limit_status, remove_rate_limit, write_rate_limit, RateLimit, RateLimitStatus,
+};
+use crate::reentrancy::{enter, exit};
+use crate::registry::{
+    check_registered, default_initial_mint_minimum, is_registered, read_bootstrap_config,
+    read_ctoken_decimals, write_bootstrap_config, write_registered, BootstrapConfig,
+};
+use crate::session::{
+    create_session, load_session, read_session, read_session_ledgers, remove_session,
+    write_session, write_session_ledgers, MintSession,
+};
+use crate::stats::{read_user_stats, record_burn, record_mint, UserStats};
+use crate::storage_types::EscrowValue;
+use crate::ttl::{bump_instance, read_ttl_config, write_ttl_config, TtlConfig};
+use constellation_shared::events::{
+    emit_claim_queued, emit_claimed, emit_error, emit_escrow_refund, emit_issue, emit_redeem,
+    read_event_seq,
+};
+use constellation_shared::oracle::PRICE_SCALE;
+use soroban_sdk::{contract, contractimpl, panic_with_error, token, Address, Env, Vec};
 
 mod constellation_token {
     soroban_sdk::contractimport!(
-        file = "../constellation-token/target/wasm32-unknown-unknown/release/constellation_token_contract.wasm"
+        file = "../target/wasm32-unknown-unknown/release/constellation_token_contract.wasm"
     );
 }
 
+fn check_nonnegative_amount(amount: i128) {
+    if amount < 0 {
+        panic!("negative amount is not allowed: {}", amount)
+    }
+}
+
+// Amount of a component backing `ctoken_amount` Constellation tokens
+// Units are per ctoken base unit, so this is exact and there is no rounding direction to choose
+fn component_amount(ctoken_amount: i128, units: u32) -> i128 {
+    ctoken_amount * units as i128
+}
+
+// Fee-on-transfer components are not supported in v1: if 'to' received less than 'amount' the ctoken
+// would be under-collateralized, so the transfer is rejected rather than crediting the smaller amount.
+fn check_received(env: &Env, component: &token::Client, to: &Address, before: i128, amount: i128) {
+    let received = component.balance(to) - before;
+    if received != amount {
+        emit_error(
+            env,
+            Error::FeeOnTransferNotSupported as u32,
+            component.address.clone(),
+            amount - received,
+        );
+        panic_with_error!(env, Error::FeeOnTransferNotSupported);
+    }
+}
+
+// Dead shares to withhold from a mint of 'ctoken_amount', see registry.rs
+// 0 unless the ctoken has no supply yet; traps with InitialMintTooSmall if that first mint is below the minimum
+fn check_first_mint(
+    env: &Env,
+    ctoken_client: &constellation_token::Client,
+    ctoken: Address,
+    ctoken_amount: i128,
+) -> i128 {
+    if ctoken_client.total_supply() != 0 {
+        return 0;
+    }
+    let config = read_bootstrap_config(env, ctoken);
+    if ctoken_amount < config.initial_mint_minimum {
+        panic_with_error!(env, Error::InitialMintTooSmall);
+    }
+    config.dead_shares
+}
+
+// Mint 'ctoken_amount' with 'dead_shares' of it going to the ctoken contract instead of 'to'
+fn mint_ctoken(
+    ctoken_client: &constellation_token::Client,
+    to: &Address,
+    ctoken_amount: i128,
+    dead_shares: i128,
+) {
+    if dead_shares > 0 {
+        ctoken_client.mint(&ctoken_client.address, &dead_shares);
+    }
+    ctoken_client.mint(to, &(ctoken_amount - dead_shares));
+}
+
+// Transfers the components backing 'ctoken_amount' from 'from' to the ConstellationToken contract,
+// then mints 'ctoken_amount' to 'to'. Returns the amount minted.
+// A 'ctoken_amount' of None mints the most 'max_amounts_in' can back, from the same basket read.
+// The "mint" event carries a flag recording whether the allowance path was used.
+fn issue(
+    env: &Env,
+    from: Address,
+    to: Address,
+    ctoken: Address,
+    ctoken_amount: Option<i128>,
+    via_allowance: bool,
+    max_amounts_in: Option<Vec<i128>>,
+) -> i128 {
+    if let Some(ctoken_amount) = ctoken_amount {
+        check_nonnegative_amount(ctoken_amount);
+    }
+    check_mint_allowed(env);
+    check_registered(env, ctoken.clone());
+    enter(env);
+
+    bump_instance(env);
+
+    let ctoken_client = constellation_token::Client::new(env, &ctoken);
+    let basket = read_mintable_basket(env, &ctoken_client);
+    let ctoken_amount = match (ctoken_amount, &max_amounts_in) {
+        (Some(ctoken_amount), _) => ctoken_amount,
+        (None, Some(max_amounts_in)) => basket.max_mintable(max_amounts_in),
+        (None, None) => panic!("nothing to mint from"),
+    };
+    let required = basket.required(env, ctoken_amount);
+    if let Some(max_amounts_in) = max_amounts_in {
+        check_max_amounts(env, &basket.components, &required, &max_amounts_in);
+    }
+    let dead_shares = check_first_mint(env, &ctoken_client, ctoken.clone(), ctoken_amount);
+
+    let shortfalls = find_shortfalls(env, &from, &basket.components, &required, via_allowance);
+    if !shortfalls.is_empty() {
+        for shortfall in shortfalls.iter() {
+            emit_error(
+                env,
+                Error::InsufficientComponent as u32,
+                shortfall.component,
+                shortfall.required - shortfall.available,
+            );
+        }
+        panic_with_error!(env, Error::InsufficientComponent);
+    }
+
+    // Update MinterBurner state before calling into any component token
+    increase_issuance(env, ctoken.clone(), ctoken_amount);
+    record_mint(env, from.clone(), ctoken.clone(), ctoken_amount);
+
+    for i in 0..basket.components.len() {
+        let component = token::Client::new(env, &basket.components.get(i).unwrap());
+        let amount = required.get(i).unwrap();
+        let before = component.balance(&ctoken);
+        if via_allowance {
+            component.transfer_from(&env.current_contract_address(), &from, &ctoken, &amount);
+        } else {
+            component.transfer(&from, &ctoken, &amount);
+        }
+        check_received(env, &component, &ctoken, before, amount);
+    }
+
+    mint_ctoken(&ctoken_client, &to, ctoken_amount, dead_shares);
+    emit_issue(env, ctoken, from, to, ctoken_amount, via_allowance);
+    exit(env);
+    ctoken_amount
+}
+
+// Burns 'ctoken_amount' from 'from', spending the ctoken allowance 'from' gave 'spender',
+// and releases the backing components to 'to'.
+// Follows checks-effects-interactions: the user's allowance and balance are spent and the
+// issuance counter decremented before any component token is called, so a malicious
+// component re-entering during its transfer can't redeem the same ctokens twice.
+fn redeem(
+    env: &Env,
+    spender: Address,
+    from: Address,
+    to: Address,
+    ctoken: Address,
+    ctoken_amount: i128,
+    min_amounts_out: Option<Vec<i128>>,
+) {
+    check_nonnegative_amount(ctoken_amount);
+    check_registered(env, ctoken.clone());
+    let ctoken_client = constellation_token::Client::new(env, &ctoken);
+    check_redeem_allowed(env, &ctoken_client);
+    enter(env);
+
+    bump_instance(env);
+
+    // Checks
+    let basket = read_basket(env, &ctoken_client);
+    let released = basket.required(env, ctoken_amount);
+    if let Some(min_amounts_out) = min_amounts_out {
+        check_min_amounts(env, &basket.components, &released, &min_amounts_out);
+    }
+
+    // Effects: 'from' must have approved 'spender' for ctoken_amount
+    decrease_issuance(env, ctoken.clone(), ctoken_amount);
+    record_burn(env, from.clone(), ctoken.clone(), ctoken_amount);
+    ctoken_client.burn_from(&spender, &from, &ctoken_amount);
+
+    // Interactions
+    ctoken_client.release(&to, &released);
+    emit_redeem(
+        env,
+        ctoken,
+        from,
+        to,
+        ctoken_amount,
+        basket.components,
+        released,
+    );
+    exit(env);
+}
 
 #[contract]
 pub struct ConstellationMinterBurner;
 
 #[contractimpl]
 impl ConstellationMinterBurner {
+    pub fn initialize(env: Env, admin: Address) {
+        if has_administrator(&env) {
+            panic!("already initialized")
+        }
+        write_administrator(&env, &admin);
+        // Start from the default instance TTL, see ttl.rs
+        write_ttl_config(&env, &read_ttl_config(&env));
+    }
+
+    // Allow minting of 'ctoken' through this contract
+    // Traps with AdminMismatch unless the ctoken's admin is this contract, e.g. before its set_admin handover
+    // The first mint must be at least 'initial_mint_minimum', one whole ctoken if None, and 'dead_shares'
+    // of it are locked in the ctoken contract; see registry.rs
+    pub fn add_ctoken(
+        env: Env,
+        ctoken: Address,
+        initial_mint_minimum: Option<i128>,
+        dead_shares: i128,
+    ) {
+        require_initialized(&env);
+        let admin = read_administrator(&env);
+        admin.require_auth();
+
+        bump_instance(&env);
+
+        let ctoken_client = constellation_token::Client::new(&env, &ctoken);
+        if ctoken_client.get_admin() != env.current_contract_address() {
+            panic_with_error!(&env, Error::AdminMismatch);
+        }
+        let decimals = ctoken_client.decimals();
+        write_registered(&env, ctoken.clone(), decimals);
+        write_bootstrap_config(
+            &env,
+            ctoken,
+            &BootstrapConfig {
+                initial_mint_minimum: initial_mint_minimum
+                    .unwrap_or_else(|| default_initial_mint_minimum(decimals)),
+                dead_shares,
+            },
+        );
+    }
+
+    // Re-read the decimals of a registered ctoken, e.g. after the token was upgraded
+    pub fn refresh_ctoken_metadata(env: Env, ctoken: Address) {
+        require_initialized(&env);
+        let admin = read_administrator(&env);
+        admin.require_auth();
+        check_registered(&env, ctoken.clone());
+
+        bump_instance(&env);
+
+        let decimals = constellation_token::Client::new(&env, &ctoken).decimals();
+        write_registered(&env, ctoken, decimals);
+    }
+
+    // Every configuration value of this contract, see config.rs
+    pub fn dump_config(env: Env) -> ConfigDump {
+        require_initialized(&env);
+        bump_instance(&env);
+        read_config(&env)
+    }
+
+    // Configuration this contract keeps for 'ctoken'; traps with UnknownCtoken if it isn't registered
+    pub fn dump_ctoken_config(env: Env, ctoken: Address) -> CtokenConfigDump {
+        require_initialized(&env);
+        bump_instance(&env);
+        read_ctoken_config(&env, ctoken)
+    }
+
+    // Decimals of 'ctoken' as cached at registration; traps with UnknownCtoken if it isn't registered
+    pub fn ctoken_decimals(env: Env, ctoken: Address) -> u32 {
+        require_initialized(&env);
+        bump_instance(&env);
+        read_ctoken_decimals(&env, ctoken)
+    }
+
+    // Sequence number of the last Constellation event this contract published; ctokens keep their own
+    pub fn get_event_seq(env: Env) -> u64 {
+        require_initialized(&env);
+        bump_instance(&env);
+        read_event_seq(&env)
+    }
+
+    pub fn is_ctoken(env: Env, ctoken: Address) -> bool {
+        require_initialized(&env);
+        bump_instance(&env);
+        is_registered(&env, ctoken)
+    }
+
     // Swap component tokens for newly minted Constellation tokens
     // Function could also be called "issue()", but that might cause confusion with Stellar asset issuance
+    // The component transfers are sub-invocations of this call, so a single signature from 'from'
+    // authorizes the whole mint; no separate approve step is required.
     pub fn mint(
         env: Env,
         from: Address,
@@ -27,13 +353,410 @@ impl ConstellationMinterBurner {
         ctoken: Address,
         ctoken_amount: i128,
     ) {
-        // Verify 'from' has enough of each component token for ctoken_amount
-        // Verify 'from' has approved allowances for each component token 
+        require_initialized(&env);
         from.require_auth();
-        // Transfer component tokens from 'from' to the ConstellationToken contract
-        // Mint ctoken_amount of Constellation tokens to 'to' address
-        let ctoken = constellation_token::Client::new(&env, &ctoken);
-        ctoken.mint(&to, &ctoken_amount);
+        issue(&env, from, to, ctoken, Some(ctoken_amount), false, None);
+    }
+
+    // Fallback for smart-wallet callers that can't sign sub-invocations:
+    // 'from' must have approved this contract to spend each component token beforehand.
+    pub fn mint_with_allowance(
+        env: Env,
+        from: Address,
+        to: Address,
+        ctoken: Address,
+        ctoken_amount: i128,
+    ) {
+        require_initialized(&env);
+        from.require_auth();
+        issue(&env, from, to, ctoken, Some(ctoken_amount), true, None);
+    }
+
+    // mint() that traps with SlippageExceeded if any component amount required is above 'max_amounts_in'
+    // (in getComponents() order), and with DeadlineExpired after ledger 'deadline_ledger' (0 for none)
+    pub fn mint_with_limits(
+        env: Env,
+        from: Address,
+        to: Address,
+        ctoken: Address,
+        ctoken_amount: i128,
+        max_amounts_in: Vec<i128>,
+        deadline_ledger: u32,
+    ) {
+        require_initialized(&env);
+        from.require_auth();
+        check_deadline(&env, deadline_ledger);
+        issue(
+            &env,
+            from,
+            to,
+            ctoken,
+            Some(ctoken_amount),
+            false,
+            Some(max_amounts_in),
+        );
+    }
+
+    // Mint as many Constellation tokens as 'value' of the oracle's quote asset buys at the current NAV, rounded down
+    // The ctoken traps if its oracle quotes are stale. Returns the amount minted.
+    pub fn mint_by_value(
+        env: Env,
+        from: Address,
+        to: Address,
+        ctoken: Address,
+        value: i128,
+    ) -> i128 {
+        require_initialized(&env);
+        from.require_auth();
+        check_nonnegative_amount(value);
+
+        let nav = constellation_token::Client::new(&env, &ctoken).nav_per_token();
+        if nav <= 0 {
+            panic!("ctoken has no value")
+        }
+        let ctoken_amount = value * PRICE_SCALE / nav;
+        issue(&env, from, to, ctoken, Some(ctoken_amount), false, None);
+        ctoken_amount
+    }
+
+    // Mint the most ctokens 'component_amounts' (in getComponents() order) can back, e.g. "deposit what I have"
+    // Only the amounts backing the minted ctokens are transferred, so the remainder never leaves 'from'.
+    // Returns the amount minted.
+    pub fn mint_exact_in(
+        env: Env,
+        from: Address,
+        to: Address,
+        ctoken: Address,
+        component_amounts: Vec<i128>,
+    ) -> i128 {
+        require_initialized(&env);
+        from.require_auth();
+        // The amount is sized inside issue(), from the basket it reads anyway, see Basket::max_mintable
+        issue(&env, from, to, ctoken, None, false, Some(component_amounts))
+    }
+
+    // Read-only: what mint() of 'ctoken_amount' would take and deliver, see preview.rs
+    // Traps like mint() would if a component is paused or 'ctoken' isn't registered
+    pub fn preview_mint(env: Env, ctoken: Address, ctoken_amount: i128) -> MintPreview {
+        require_initialized(&env);
+        check_nonnegative_amount(ctoken_amount);
+        check_registered(&env, ctoken.clone());
+        let ctoken_client = constellation_token::Client::new(&env, &ctoken);
+        let basket = read_mintable_basket(&env, &ctoken_client);
+        MintPreview {
+            gross_amount: ctoken_amount,
+            fees: Vec::new(&env),
+            net_amount: ctoken_amount,
+            amounts_in: basket.required(&env, ctoken_amount),
+            components: basket.components,
+        }
+    }
+
+    // Read-only: what burn() of 'ctoken_amount' would burn and release
+    pub fn preview_burn(env: Env, ctoken: Address, ctoken_amount: i128) -> BurnPreview {
+        require_initialized(&env);
+        check_nonnegative_amount(ctoken_amount);
+        let ctoken_client = constellation_token::Client::new(&env, &ctoken);
+        let basket = read_basket(&env, &ctoken_client);
+        BurnPreview {
+            gross_amount: ctoken_amount,
+            fees: Vec::new(&env),
+            net_amount: ctoken_amount,
+            amounts_out: basket.required(&env, ctoken_amount),
+            components: basket.components,
+        }
+    }
+
+    // Read-only: the components 'from' lacks to mint 'ctoken_amount' with mint(), empty if it would succeed
+    pub fn diagnose_mint(
+        env: Env,
+        from: Address,
+        ctoken: Address,
+        ctoken_amount: i128,
+    ) -> Vec<Shortfall> {
+        require_initialized(&env);
+        let ctoken_client = constellation_token::Client::new(&env, &ctoken);
+        let basket = read_basket(&env, &ctoken_client);
+        let required = basket.required(&env, ctoken_amount);
+        find_shortfalls(&env, &from, &basket.components, &required, false)
+    }
+
+    // First step of the deposit-then-mint flow, for wallets that can't batch approvals.
+    // Moves 'amount' of a component from 'from' into escrow held by this contract.
+    // After 'expiration_ledger' (0 for never) anyone may refund the escrow back to 'from'.
+    pub fn deposit(
+        env: Env,
+        from: Address,
+        component: Address,
+        amount: i128,
+        expiration_ledger: u32,
+    ) {
+        require_initialized(&env);
+        from.require_auth();
+        check_nonnegative_amount(amount);
+
+        bump_instance(&env);
+
+        // Escrow is credited with 'amount', so it must arrive in full
+        let component_client = token::Client::new(&env, &component);
+        let contract = env.current_contract_address();
+        let before = component_client.balance(&contract);
+        component_client.transfer(&from, &contract, &amount);
+        check_received(&env, &component_client, &contract, before, amount);
+        receive_escrow(&env, from, component, amount, expiration_ledger);
+    }
+
+    // Return the whole escrowed balance of 'component' to 'owner'.
+    // Only the owner may do so until the escrow expires; afterwards anyone can trigger it
+    // so abandoned partial deposits don't stay stuck in this contract.
+    pub fn refund_deposit(env: Env, owner: Address, component: Address) {
+        require_initialized(&env);
+        bump_instance(&env);
+
+        let escrow = read_escrow(&env, owner.clone(), component.clone());
+        if !is_escrow_expired(&env, &escrow) {
+            owner.require_auth();
+        }
+
+        spend_escrow(&env, owner.clone(), component.clone(), escrow.amount);
+        token::Client::new(&env, &component).transfer(
+            &env.current_contract_address(),
+            &owner,
+            &escrow.amount,
+        );
+        emit_escrow_refund(&env, owner, component, escrow.amount);
+    }
+
+    // Second step of the deposit-then-mint flow.
+    // Same as mint(), but the components are taken from 'from's escrow instead of allowances.
+    pub fn mint_from_escrow(
+        env: Env,
+        from: Address,
+        to: Address,
+        ctoken: Address,
+        ctoken_amount: i128,
+    ) {
+        require_initialized(&env);
+        from.require_auth();
+        check_nonnegative_amount(ctoken_amount);
+        check_mint_allowed(&env);
+        check_registered(&env, ctoken.clone());
+        enter(&env);
+
+        bump_instance(&env);
+
+        let ctoken_client = constellation_token::Client::new(&env, &ctoken);
+        let basket = read_mintable_basket(&env, &ctoken_client);
+        let dead_shares = check_first_mint(&env, &ctoken_client, ctoken.clone(), ctoken_amount);
+
+        // Update MinterBurner state before calling into any component token
+        increase_issuance(&env, ctoken.clone(), ctoken_amount);
+        record_mint(&env, from.clone(), ctoken.clone(), ctoken_amount);
+        let required = basket.required(&env, ctoken_amount);
+        for i in 0..basket.components.len() {
+            spend_escrow(
+                &env,
+                from.clone(),
+                basket.components.get(i).unwrap(),
+                required.get(i).unwrap(),
+            );
+        }
+
+        for i in 0..basket.components.len() {
+            let component = token::Client::new(&env, &basket.components.get(i).unwrap());
+            let amount = required.get(i).unwrap();
+            let before = component.balance(&ctoken);
+            component.transfer(&env.current_contract_address(), &ctoken, &amount);
+            check_received(&env, &component, &ctoken, before, amount);
+        }
+
+        mint_ctoken(&ctoken_client, &to, ctoken_amount, dead_shares);
+        emit_issue(&env, ctoken, from, to, ctoken_amount, false);
+        exit(&env);
+    }
+
+    pub fn escrow(env: Env, owner: Address, component: Address) -> EscrowValue {
+        require_initialized(&env);
+        bump_instance(&env);
+        read_escrow(&env, owner, component)
+    }
+
+    // Start a split mint of 'ctoken_amount', see session.rs
+    // Fixes the component amounts owed at the current units and returns the session id
+    pub fn mint_prepare(env: Env, from: Address, ctoken: Address, ctoken_amount: i128) -> u32 {
+        require_initialized(&env);
+        from.require_auth();
+        check_nonnegative_amount(ctoken_amount);
+        check_mint_allowed(&env);
+        check_registered(&env, ctoken.clone());
+
+        bump_instance(&env);
+
+        let ctoken_client = constellation_token::Client::new(&env, &ctoken);
+        let basket = read_mintable_basket(&env, &ctoken_client);
+        let mut deposited = Vec::new(&env);
+        for _ in basket.components.iter() {
+            deposited.push_back(false);
+        }
+        create_session(
+            &env,
+            &MintSession {
+                owner: from,
+                ctoken,
+                ctoken_amount,
+                required: basket.required(&env, ctoken_amount),
+                components: basket.components,
+                deposited,
+                expiration_ledger: env.ledger().sequence() + read_session_ledgers(&env),
+            },
+        )
+    }
+
+    // Deposit the components at indices start..end of the session's basket into this contract
+    // Components already deposited are skipped; 'end' past the basket is clamped
+    pub fn mint_execute(env: Env, session_id: u32, start: u32, end: u32) {
+        require_initialized(&env);
+        let mut session = load_session(&env, session_id);
+        session.owner.require_auth();
+        if session.is_expired(&env) {
+            panic_with_error!(&env, Error::SessionExpired);
+        }
+        enter(&env);
+
+        bump_instance(&env);
+
+        let end = end.min(session.components.len());
+        let mut pending = Vec::new(&env);
+        for i in start..end {
+            if !session.deposited.get(i).unwrap() {
+                session.deposited.set(i, true);
+                pending.push_back(i);
+            }
+        }
+        write_session(&env, session_id, &session);
+
+        let contract = env.current_contract_address();
+        for i in pending.iter() {
+            let component = token::Client::new(&env, &session.components.get(i).unwrap());
+            let amount = session.required.get(i).unwrap();
+            let before = component.balance(&contract);
+            component.transfer(&session.owner, &contract, &amount);
+            check_received(&env, &component, &contract, before, amount);
+        }
+        exit(&env);
+    }
+
+    // Mint the session's ctokens to 'to' once every component is deposited
+    // Traps with BasketChanged if the ctoken's units moved since mint_prepare(); abort and prepare again
+    pub fn mint_finalize(env: Env, session_id: u32, to: Address) {
+        require_initialized(&env);
+        let session = load_session(&env, session_id);
+        session.owner.require_auth();
+        if session.is_expired(&env) {
+            panic_with_error!(&env, Error::SessionExpired);
+        }
+        if !session.is_complete() {
+            panic_with_error!(&env, Error::SessionIncomplete);
+        }
+        check_mint_allowed(&env);
+        check_registered(&env, session.ctoken.clone());
+        enter(&env);
+
+        bump_instance(&env);
+
+        let ctoken_client = constellation_token::Client::new(&env, &session.ctoken);
+        let basket = read_mintable_basket(&env, &ctoken_client);
+        if basket.components != session.components
+            || basket.required(&env, session.ctoken_amount) != session.required
+        {
+            panic_with_error!(&env, Error::BasketChanged);
+        }
+        let dead_shares = check_first_mint(
+            &env,
+            &ctoken_client,
+            session.ctoken.clone(),
+            session.ctoken_amount,
+        );
+
+        // Update MinterBurner state before calling into any component token
+        remove_session(&env, session_id);
+        increase_issuance(&env, session.ctoken.clone(), session.ctoken_amount);
+        record_mint(&env, session.owner.clone(), session.ctoken.clone(), session.ctoken_amount);
+
+        for i in 0..basket.components.len() {
+            let component = token::Client::new(&env, &basket.components.get(i).unwrap());
+            let amount = session.required.get(i).unwrap();
+            let before = component.balance(&session.ctoken);
+            component.transfer(&env.current_contract_address(), &session.ctoken, &amount);
+            check_received(&env, &component, &session.ctoken, before, amount);
+        }
+
+        mint_ctoken(&ctoken_client, &to, session.ctoken_amount, dead_shares);
+        emit_issue(&env, session.ctoken, session.owner, to, session.ctoken_amount, false);
+        exit(&env);
+    }
+
+    // Refund whatever the session has deposited and close it
+    // Only the owner may abort until the session expires; afterwards anyone can
+    pub fn mint_abort(env: Env, session_id: u32) {
+        require_initialized(&env);
+        let session = load_session(&env, session_id);
+        if !session.is_expired(&env) {
+            session.owner.require_auth();
+        }
+        enter(&env);
+
+        bump_instance(&env);
+
+        remove_session(&env, session_id);
+        for i in 0..session.components.len() {
+            if session.deposited.get(i).unwrap() {
+                token::Client::new(&env, &session.components.get(i).unwrap()).transfer(
+                    &env.current_contract_address(),
+                    &session.owner,
+                    &session.required.get(i).unwrap(),
+                );
+            }
+        }
+        exit(&env);
+    }
+
+    pub fn get_mint_session(env: Env, session_id: u32) -> Option<MintSession> {
+        require_initialized(&env);
+        bump_instance(&env);
+        read_session(&env, session_id)
+    }
+
+    // Ledgers a new split-mint session stays open for, one day by default
+    pub fn set_session_ledgers(env: Env, ledgers: u32) {
+        require_initialized(&env);
+        let admin = read_administrator(&env);
+        admin.require_auth();
+
+        bump_instance(&env);
+
+        write_session_ledgers(&env, ledgers);
+    }
+
+    // Change how far every call extends the instance TTL
+    pub fn set_ttl_config(env: Env, threshold: u32, bump_amount: u32) {
+        require_initialized(&env);
+        let admin = read_administrator(&env);
+        admin.require_auth();
+
+        write_ttl_config(
+            &env,
+            &TtlConfig {
+                threshold,
+                bump_amount,
+            },
+        );
+        bump_instance(&env);
+    }
+
+    pub fn ttl_config(env: Env) -> TtlConfig {
+        require_initialized(&env);
+        read_ttl_config(&env)
     }
 
     // Swap user's Constellation tokens for components, and burn Constellation tokens
@@ -44,10 +767,371 @@ impl ConstellationMinterBurner {
         ctoken: Address,
         ctoken_amount: i128,
     ) {
-        // Verify 'from' user has approved ctoken_amount
-        // Transfer component tokens from ConstellationToken contract to 'from' address
-        // Burn ctoken_amount of Constellation tokens from user
-        let ctoken = constellation_token::Client::new(&env, &ctoken);
-        ctoken.burn(&from, &ctoken_amount);
+        require_initialized(&env);
+        from.require_auth();
+        redeem(
+            &env,
+            env.current_contract_address(),
+            from.clone(),
+            from,
+            ctoken,
+            ctoken_amount,
+            None,
+        );
+    }
+
+    // burn() that traps with SlippageExceeded if any component amount released is below 'min_amounts_out'
+    // (in getComponents() order), and with DeadlineExpired after ledger 'deadline_ledger' (0 for none)
+    pub fn burn_with_limits(
+        env: Env,
+        from: Address,
+        ctoken: Address,
+        ctoken_amount: i128,
+        min_amounts_out: Vec<i128>,
+        deadline_ledger: u32,
+    ) {
+        require_initialized(&env);
+        from.require_auth();
+        check_deadline(&env, deadline_ledger);
+        redeem(
+            &env,
+            env.current_contract_address(),
+            from.clone(),
+            from,
+            ctoken,
+            ctoken_amount,
+            Some(min_amounts_out),
+        );
+    }
+
+    // Same as burn(), but the components are delivered to 'to', e.g. a settlement contract.
+    // 'from' still authorizes the redemption.
+    pub fn burn_to(env: Env, from: Address, to: Address, ctoken: Address, ctoken_amount: i128) {
+        require_initialized(&env);
+        from.require_auth();
+        redeem(
+            &env,
+            env.current_contract_address(),
+            from,
+            to,
+            ctoken,
+            ctoken_amount,
+            None,
+        );
+    }
+
+    // Redeem for several holders at once, e.g. a custodian closing out client positions.
+    // Each holder must have approved 'spender' on the ctoken and receives their own components.
+    // Any failure reverts the whole batch.
+    pub fn burn_batch(
+        env: Env,
+        spender: Address,
+        froms: Vec<Address>,
+        ctoken: Address,
+        ctoken_amounts: Vec<i128>,
+    ) {
+        require_initialized(&env);
+        spender.require_auth();
+        if froms.len() != ctoken_amounts.len() {
+            panic!("holders and amounts must have the same length")
+        }
+
+        for i in 0..froms.len() {
+            let from = froms.get(i).unwrap();
+            redeem(
+                &env,
+                spender.clone(),
+                from.clone(),
+                from,
+                ctoken.clone(),
+                ctoken_amounts.get(i).unwrap(),
+                None,
+            );
+        }
+    }
+
+    // Redeem several ctokens for one holder at once, the counterpart of burn_batch() across ctokens,
+    // see batch.rs. One redeem event is emitted per request.
+    pub fn burn_ctoken_batch(env: Env, from: Address, requests: Vec<BurnRequest>) {
+        require_initialized(&env);
+        from.require_auth();
+        for request in requests.iter() {
+            redeem(
+                &env,
+                env.current_contract_address(),
+                from.clone(),
+                from.clone(),
+                request.ctoken,
+                request.amount,
+                Some(request.min_amounts_out),
+            );
+        }
+    }
+
+    // Redeem numerator/denominator of 'from's ctoken balance (rounded down), e.g. 1/2 for "redeem 50%"
+    pub fn redeem_fraction(
+        env: Env,
+        from: Address,
+        ctoken: Address,
+        numerator: i128,
+        denominator: i128,
+    ) {
+        require_initialized(&env);
+        from.require_auth();
+        if denominator <= 0 || numerator < 0 || numerator > denominator {
+            panic_with_error!(&env, Error::InvalidFraction);
+        }
+        check_registered(&env, ctoken.clone());
+
+        let balance = constellation_token::Client::new(&env, &ctoken).balance(&from);
+        let ctoken_amount = balance * numerator / denominator;
+        redeem(
+            &env,
+            env.current_contract_address(),
+            from.clone(),
+            from,
+            ctoken,
+            ctoken_amount,
+            None,
+        );
+    }
+
+    // Opt-in variant of burn() for when a component's transfers fail, e.g. because its issuer froze it.
+    // The ctokens are burned up front. Components that transfer are paid out immediately and any
+    // component that fails is queued as a pending claim that 'from' can collect later with claim().
+    // The redeem event lists what was released now, with 0 for each queued component, and every queued
+    // component gets its own queued event.
+    pub fn burn_with_queue(env: Env, from: Address, ctoken: Address, ctoken_amount: i128) {
+        require_initialized(&env);
+        from.require_auth();
+        check_nonnegative_amount(ctoken_amount);
+        check_registered(&env, ctoken.clone());
+        let ctoken_client = constellation_token::Client::new(&env, &ctoken);
+        check_redeem_allowed(&env, &ctoken_client);
+        enter(&env);
+
+        bump_instance(&env);
+
+        let basket = read_basket(&env, &ctoken_client);
+        let amounts_out = basket.required(&env, ctoken_amount);
+
+        decrease_issuance(&env, ctoken.clone(), ctoken_amount);
+        record_burn(&env, from.clone(), ctoken.clone(), ctoken_amount);
+        ctoken_client.burn_from(&env.current_contract_address(), &from, &ctoken_amount);
+
+        // What was paid out now, 0 for each queued component
+        let mut released = Vec::new(&env);
+        for i in 0..basket.components.len() {
+            let component = basket.components.get(i).unwrap();
+            let amount = amounts_out.get(i).unwrap();
+            let result = ctoken_client.try_release_component(&from, &component, &amount);
+            if matches!(result, Ok(Ok(()))) {
+                released.push_back(amount);
+            } else {
+                receive_claim(&env, from.clone(), ctoken.clone(), component.clone(), amount);
+                emit_claim_queued(&env, from.clone(), component, amount);
+                released.push_back(0);
+            }
+        }
+        emit_redeem(
+            &env,
+            ctoken,
+            from.clone(),
+            from,
+            ctoken_amount,
+            basket.components,
+            released,
+        );
+        exit(&env);
+    }
+
+    // Collect a component queued by burn_with_queue()
+    pub fn claim(env: Env, owner: Address, ctoken: Address, component: Address) {
+        require_initialized(&env);
+        owner.require_auth();
+        enter(&env);
+
+        bump_instance(&env);
+
+        let amount = read_claim(&env, owner.clone(), ctoken.clone(), component.clone());
+        if amount == 0 {
+            panic_with_error!(&env, Error::NothingToClaim);
+        }
+        remove_claim(&env, owner.clone(), ctoken.clone(), component.clone());
+
+        constellation_token::Client::new(&env, &ctoken).release_component(
+            &owner,
+            &component,
+            &amount,
+        );
+        emit_claimed(&env, owner, component, amount);
+        exit(&env);
+    }
+
+    pub fn pending_claim(env: Env, owner: Address, ctoken: Address, component: Address) -> i128 {
+        require_initialized(&env);
+        bump_instance(&env);
+        read_claim(&env, owner, ctoken, component)
+    }
+
+    // Dust-level deficit per component the ctoken's is_fully_backed() tolerates, capped by the ctoken
+    pub fn set_backing_epsilon(env: Env, ctoken: Address, epsilon: i128) {
+        require_initialized(&env);
+        let admin = read_administrator(&env);
+        admin.require_auth();
+        check_registered(&env, ctoken.clone());
+
+        bump_instance(&env);
+
+        constellation_token::Client::new(&env, &ctoken).set_backing_epsilon(&epsilon);
+    }
+
+    // The admin of a ctoken is this contract, so the protocol admin curates each ctoken's allowed
+    // rebalance intermediate tokens through these two calls
+    pub fn add_intermediate(env: Env, ctoken: Address, token: Address) {
+        require_initialized(&env);
+        let admin = read_administrator(&env);
+        admin.require_auth();
+        check_registered(&env, ctoken.clone());
+
+        bump_instance(&env);
+
+        constellation_token::Client::new(&env, &ctoken).add_intermediate(&token);
+    }
+
+    pub fn remove_intermediate(env: Env, ctoken: Address, token: Address) {
+        require_initialized(&env);
+        let admin = read_administrator(&env);
+        admin.require_auth();
+        check_registered(&env, ctoken.clone());
+
+        bump_instance(&env);
+
+        constellation_token::Client::new(&env, &ctoken).remove_intermediate(&token);
+    }
+
+    // Total of 'component' held by 'ctoken' for pending claims; the ctoken keeps it out of collect_dust()
+    pub fn queued_claims(env: Env, ctoken: Address, component: Address) -> i128 {
+        require_initialized(&env);
+        bump_instance(&env);
+        read_queued_total(&env, ctoken, component)
+    }
+
+    // Throttle how much of 'ctoken' can be outstanding through this MinterBurner,
+    // independently of the token's own supply limits. Useful during a launch period.
+    pub fn set_issuance_cap(env: Env, ctoken: Address, cap: i128) {
+        require_initialized(&env);
+        let admin = read_administrator(&env);
+        admin.require_auth();
+        check_nonnegative_amount(cap);
+
+        bump_instance(&env);
+
+        write_issuance_cap(&env, ctoken, cap);
+    }
+
+    pub fn get_issuance(env: Env, ctoken: Address) -> i128 {
+        require_initialized(&env);
+        bump_instance(&env);
+        read_issuance(&env, ctoken)
+    }
+
+    // Lifetime ctokens 'user' minted and burned through this MinterBurner
+    pub fn get_user_stats(env: Env, user: Address, ctoken: Address) -> UserStats {
+        require_initialized(&env);
+        bump_instance(&env);
+        read_user_stats(&env, user, ctoken)
+    }
+
+    pub fn get_issuance_cap(env: Env, ctoken: Address) -> Option<i128> {
+        require_initialized(&env);
+        bump_instance(&env);
+        read_issuance_cap(&env, ctoken)
+    }
+
+    // Trap mints once the net amount of 'ctoken' minted through this MinterBurner within a window of
+    // 'window_ledgers' exceeds 'max_net_mint'. Redemptions in the window free up capacity.
+    pub fn set_issuance_rate_limit(
+        env: Env,
+        ctoken: Address,
+        max_net_mint: i128,
+        window_ledgers: u32,
+    ) {
+        require_initialized(&env);
+        let admin = read_administrator(&env);
+        admin.require_auth();
+        check_nonnegative_amount(max_net_mint);
+
+        bump_instance(&env);
+
+        write_rate_limit(
+            &env,
+            ctoken,
+            &RateLimit {
+                max_net_mint,
+                window_ledgers,
+            },
+        );
+    }
+
+    pub fn remove_issuance_rate_limit(env: Env, ctoken: Address) {
+        require_initialized(&env);
+        let admin = read_administrator(&env);
+        admin.require_auth();
+
+        bump_instance(&env);
+
+        remove_rate_limit(&env, ctoken);
+    }
+
+    // Capacity left in the current window and the ledger it ends at, None without a rate limit
+    pub fn get_rate_limit_status(env: Env, ctoken: Address) -> Option<RateLimitStatus> {
+        require_initialized(&env);
+        bump_instance(&env);
+        read_rate_limit_status(&env, ctoken)
+    }
+
+    // Stop all issuance. Redemptions continue unless allow_redeem_while_paused is turned off.
+    pub fn pause(env: Env) {
+        require_initialized(&env);
+        let admin = read_administrator(&env);
+        admin.require_auth();
+
+        bump_instance(&env);
+
+        write_paused(&env, true);
+    }
+
+    pub fn unpause(env: Env) {
+        require_initialized(&env);
+        let admin = read_administrator(&env);
+        admin.require_auth();
+
+        bump_instance(&env);
+
+        write_paused(&env, false);
+    }
+
+    // Defaults to true so a pause never traps holders; a ctoken winding down is redeemable either way
+    pub fn set_allow_redeem_while_paused(env: Env, allow: bool) {
+        require_initialized(&env);
+        let admin = read_administrator(&env);
+        admin.require_auth();
+
+        bump_instance(&env);
+
+        write_allow_redeem_while_paused(&env, allow);
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        require_initialized(&env);
+        bump_instance(&env);
+        is_paused(&env)
+    }
+
+    pub fn allow_redeem_while_paused(env: Env) -> bool {
+        require_initialized(&env);
+        bump_instance(&env);
+        read_allow_redeem_while_paused(&env)
     }
 }
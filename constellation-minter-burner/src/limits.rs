@@ -0,0 +1,53 @@
+// Caller-supplied bounds for mint_with_limits() and burn_with_limits(). Units can change between signing
+// and execution (e.g. a rebalance), so callers can cap what they pay, floor what they receive, and set a
+// deadline after which the transaction must not execute.
+// A limit that traps is reported in an ("error", SlippageExceeded) event naming the component and how far
+// past its limit it is.
+use crate::error::Error;
+use constellation_shared::events::emit_error;
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+// 'deadline_ledger' of 0 means no deadline
+pub fn check_deadline(e: &Env, deadline_ledger: u32) {
+    if deadline_ledger != 0 && e.ledger().sequence() > deadline_ledger {
+        panic_with_error!(e, Error::DeadlineExpired);
+    }
+}
+
+// Every required component amount must be at most its limit
+pub fn check_max_amounts(
+    e: &Env,
+    components: &Vec<Address>,
+    required: &Vec<i128>,
+    max_amounts: &Vec<i128>,
+) {
+    if required.len() != max_amounts.len() {
+        panic!("limits must have one amount per component")
+    }
+    for i in 0..required.len() {
+        let excess = required.get(i).unwrap() - max_amounts.get(i).unwrap();
+        if excess > 0 {
+            emit_error(e, Error::SlippageExceeded as u32, components.get(i).unwrap(), excess);
+            panic_with_error!(e, Error::SlippageExceeded);
+        }
+    }
+}
+
+// Every released component amount must be at least its limit
+pub fn check_min_amounts(
+    e: &Env,
+    components: &Vec<Address>,
+    released: &Vec<i128>,
+    min_amounts: &Vec<i128>,
+) {
+    if released.len() != min_amounts.len() {
+        panic!("limits must have one amount per component")
+    }
+    for i in 0..released.len() {
+        let shortfall = min_amounts.get(i).unwrap() - released.get(i).unwrap();
+        if shortfall > 0 {
+            emit_error(e, Error::SlippageExceeded as u32, components.get(i).unwrap(), shortfall);
+            panic_with_error!(e, Error::SlippageExceeded);
+        }
+    }
+}
@@ -0,0 +1,43 @@
+// The admin can pause the MinterBurner as a whole. A pause always stops new issuance; redemptions
+// continue unless the admin also turns off allow_redeem_while_paused, so holders aren't trapped by default.
+// A ctoken whose wind-down has been initiated can always be redeemed, whatever the pause settings.
+use crate::constellation_token;
+use crate::error::Error;
+use crate::storage_types::DataKey;
+use soroban_sdk::{panic_with_error, Env};
+
+pub fn is_paused(e: &Env) -> bool {
+    let key = DataKey::Paused;
+    e.storage().instance().get(&key).unwrap_or(false)
+}
+
+pub fn write_paused(e: &Env, paused: bool) {
+    let key = DataKey::Paused;
+    e.storage().instance().set(&key, &paused);
+}
+
+pub fn read_allow_redeem_while_paused(e: &Env) -> bool {
+    let key = DataKey::AllowRedeemWhilePaused;
+    e.storage().instance().get(&key).unwrap_or(true)
+}
+
+pub fn write_allow_redeem_while_paused(e: &Env, allow: bool) {
+    let key = DataKey::AllowRedeemWhilePaused;
+    e.storage().instance().set(&key, &allow);
+}
+
+pub fn check_mint_allowed(e: &Env) {
+    if is_paused(e) {
+        panic_with_error!(e, Error::Paused);
+    }
+}
+
+// The ctoken is only asked about its wind-down when redemption is paused
+pub fn check_redeem_allowed(e: &Env, ctoken_client: &constellation_token::Client) {
+    if is_paused(e)
+        && !read_allow_redeem_while_paused(e)
+        && ctoken_client.wind_down_ledger().is_none()
+    {
+        panic_with_error!(e, Error::Paused);
+    }
+}
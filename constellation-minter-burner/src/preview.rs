@@ -0,0 +1,31 @@
+// What mint() and burn() would do for a given amount, for wallets to show before signing.
+// Neither path charges an issuance or redemption fee today, so 'fees' is empty and the net amount
+// equals the gross; the fields are there so previews keep their shape if fees are introduced.
+// The performance fee is accrued separately on the ctoken and doesn't change these amounts.
+use soroban_sdk::{contracttype, Address, Vec};
+
+#[derive(Clone)]
+#[contracttype]
+pub struct MintPreview {
+    pub gross_amount: i128,
+    // (recipient, ctoken amount)
+    pub fees: Vec<(Address, i128)>,
+    // Delivered to 'to'
+    pub net_amount: i128,
+    pub components: Vec<Address>,
+    // Taken from 'from', in 'components' order
+    pub amounts_in: Vec<i128>,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct BurnPreview {
+    pub gross_amount: i128,
+    // (recipient, ctoken amount)
+    pub fees: Vec<(Address, i128)>,
+    // Burned from 'from'
+    pub net_amount: i128,
+    pub components: Vec<Address>,
+    // Released to the redeemer, in 'components' order
+    pub amounts_out: Vec<i128>,
+}
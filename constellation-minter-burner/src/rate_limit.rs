@@ -0,0 +1,107 @@
+// Optional circuit breaker on net issuance of a ctoken, to bound the damage of an exploit in a
+// component or in this contract. Mints add to the net amount of the current window and redemptions
+// subtract from it. A window starts with the first issuance after the previous one ended.
+use crate::error::Error;
+use crate::storage_types::{DataKey, ISSUANCE_BUMP_AMOUNT, ISSUANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::{contracttype, panic_with_error, Address, Env};
+
+#[derive(Clone)]
+#[contracttype]
+pub struct RateLimit {
+    pub max_net_mint: i128,
+    pub window_ledgers: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct RateWindow {
+    pub start_ledger: u32,
+    // Negative when more was redeemed than minted in the window
+    pub net_minted: i128,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct RateLimitStatus {
+    pub remaining: i128,
+    // First ledger of the next window
+    pub window_end: u32,
+}
+
+pub fn read_rate_limit(e: &Env, ctoken: Address) -> Option<RateLimit> {
+    let key = DataKey::RateLimit(ctoken);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_rate_limit(e: &Env, ctoken: Address, limit: &RateLimit) {
+    if limit.window_ledgers == 0 {
+        panic!("window must be at least one ledger")
+    }
+    let key = DataKey::RateLimit(ctoken);
+    e.storage().persistent().set(&key, limit);
+    e.storage()
+        .persistent()
+        .bump(&key, ISSUANCE_LIFETIME_THRESHOLD, ISSUANCE_BUMP_AMOUNT);
+}
+
+pub fn remove_rate_limit(e: &Env, ctoken: Address) {
+    e.storage()
+        .persistent()
+        .remove(&DataKey::RateLimit(ctoken.clone()));
+    e.storage()
+        .persistent()
+        .remove(&DataKey::RateWindow(ctoken));
+}
+
+// The window in effect at the current ledger; a fresh one if the stored window has ended
+fn read_window(e: &Env, ctoken: Address, limit: &RateLimit) -> RateWindow {
+    let key = DataKey::RateWindow(ctoken);
+    let sequence = e.ledger().sequence();
+    match e.storage().persistent().get::<DataKey, RateWindow>(&key) {
+        Some(window) if sequence < window.start_ledger.saturating_add(limit.window_ledgers) => {
+            window
+        }
+        _ => RateWindow {
+            start_ledger: sequence,
+            net_minted: 0,
+        },
+    }
+}
+
+fn write_window(e: &Env, ctoken: Address, window: &RateWindow) {
+    let key = DataKey::RateWindow(ctoken);
+    e.storage().persistent().set(&key, window);
+    e.storage()
+        .persistent()
+        .bump(&key, ISSUANCE_LIFETIME_THRESHOLD, ISSUANCE_BUMP_AMOUNT);
+}
+
+// None if 'ctoken' has no rate limit
+pub fn read_rate_limit_status(e: &Env, ctoken: Address) -> Option<RateLimitStatus> {
+    let limit = read_rate_limit(e, ctoken.clone())?;
+    let window = read_window(e, ctoken, &limit);
+    Some(RateLimitStatus {
+        remaining: limit.max_net_mint - window.net_minted,
+        window_end: window.start_ledger.saturating_add(limit.window_ledgers),
+    })
+}
+
+// Traps with RateLimited if minting 'amount' takes the window's net issuance above the limit
+pub fn consume_rate_limit(e: &Env, ctoken: Address, amount: i128) {
+    if let Some(limit) = read_rate_limit(e, ctoken.clone()) {
+        let mut window = read_window(e, ctoken.clone(), &limit);
+        window.net_minted += amount;
+        if window.net_minted > limit.max_net_mint {
+            panic_with_error!(e, Error::RateLimited);
+        }
+        write_window(e, ctoken, &window);
+    }
+}
+
+pub fn release_rate_limit(e: &Env, ctoken: Address, amount: i128) {
+    if let Some(limit) = read_rate_limit(e, ctoken.clone()) {
+        let mut window = read_window(e, ctoken.clone(), &limit);
+        window.net_minted -= amount;
+        write_window(e, ctoken, &window);
+    }
+}
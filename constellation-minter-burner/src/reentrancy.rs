@@ -0,0 +1,19 @@
+// Component tokens are arbitrary contracts, so a malicious one could call back into the
+// MinterBurner from inside a transfer. mint and burn flows hold this lock while they run.
+// A trap anywhere in the invocation rolls the lock back along with everything else.
+use crate::error::Error;
+use crate::storage_types::DataKey;
+use soroban_sdk::{panic_with_error, Env};
+
+pub fn enter(e: &Env) {
+    let key = DataKey::Locked;
+    if e.storage().instance().has(&key) {
+        panic_with_error!(e, Error::Reentrancy);
+    }
+    e.storage().instance().set(&key, &true);
+}
+
+pub fn exit(e: &Env) {
+    let key = DataKey::Locked;
+    e.storage().instance().remove(&key);
+}
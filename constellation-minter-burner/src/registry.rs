@@ -0,0 +1,98 @@
+// Constellation tokens this MinterBurner issues. A ctoken can only be registered once its admin is
+// this contract, so a misconfigured deployment is caught at registration instead of at the first mint.
+// The ctoken's decimals are cached at registration, so a ctoken that later changes its answer can't
+// skew this contract's math; the admin refreshes them after a legitimate upgrade.
+// The first mint into an empty ctoken sets the reserve-to-supply ratio later math relies on, so it must
+// be at least a minimum, and part of it can be locked for good so the supply never returns to a dust amount
+// that a donation of components could skew.
+use crate::error::Error;
+use crate::storage_types::{DataKey, CTOKEN_BUMP_AMOUNT, CTOKEN_LIFETIME_THRESHOLD};
+use soroban_sdk::{contracttype, panic_with_error, Address, Env};
+
+// Same bound as the Constellation Token's own
+pub(crate) const MAX_DECIMALS: u32 = 18;
+
+#[derive(Clone)]
+#[contracttype]
+pub struct CtokenInfo {
+    pub decimals: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct BootstrapConfig {
+    // Smallest mint accepted while the ctoken's supply is 0
+    pub initial_mint_minimum: i128,
+    // Taken from that mint and held by the ctoken contract itself, which can't transfer it
+    pub dead_shares: i128,
+}
+
+pub fn is_registered(e: &Env, ctoken: Address) -> bool {
+    let key = DataKey::Ctoken(ctoken);
+    if e.storage().persistent().has(&key) {
+        e.storage()
+            .persistent()
+            .bump(&key, CTOKEN_LIFETIME_THRESHOLD, CTOKEN_BUMP_AMOUNT);
+        true
+    } else {
+        false
+    }
+}
+
+// Traps with InvalidDecimals above MAX_DECIMALS
+pub fn write_registered(e: &Env, ctoken: Address, decimals: u32) {
+    if decimals > MAX_DECIMALS {
+        panic_with_error!(e, Error::InvalidDecimals);
+    }
+    let key = DataKey::Ctoken(ctoken);
+    e.storage().persistent().set(&key, &CtokenInfo { decimals });
+    e.storage()
+        .persistent()
+        .bump(&key, CTOKEN_LIFETIME_THRESHOLD, CTOKEN_BUMP_AMOUNT);
+}
+
+pub fn check_registered(e: &Env, ctoken: Address) {
+    if !is_registered(e, ctoken) {
+        panic_with_error!(e, Error::UnknownCtoken);
+    }
+}
+
+// Decimals cached when 'ctoken' was registered or last refreshed
+pub fn read_ctoken_decimals(e: &Env, ctoken: Address) -> u32 {
+    let key = DataKey::Ctoken(ctoken);
+    match e.storage().persistent().get::<DataKey, CtokenInfo>(&key) {
+        Some(info) => info.decimals,
+        None => panic_with_error!(e, Error::UnknownCtoken),
+    }
+}
+
+// Traps if the first mint couldn't cover the dead shares
+pub fn write_bootstrap_config(e: &Env, ctoken: Address, config: &BootstrapConfig) {
+    if config.dead_shares < 0 {
+        panic!("dead shares must not be negative")
+    }
+    if config.dead_shares > 0 && config.initial_mint_minimum <= config.dead_shares {
+        panic!("initial mint minimum must exceed the dead shares")
+    }
+    let key = DataKey::Bootstrap(ctoken);
+    e.storage().persistent().set(&key, config);
+    e.storage()
+        .persistent()
+        .bump(&key, CTOKEN_LIFETIME_THRESHOLD, CTOKEN_BUMP_AMOUNT);
+}
+
+// One whole ctoken and no dead shares for ctokens registered without a config
+pub fn read_bootstrap_config(e: &Env, ctoken: Address) -> BootstrapConfig {
+    let key = DataKey::Bootstrap(ctoken.clone());
+    match e.storage().persistent().get::<DataKey, BootstrapConfig>(&key) {
+        Some(config) => config,
+        None => BootstrapConfig {
+            initial_mint_minimum: default_initial_mint_minimum(read_ctoken_decimals(e, ctoken)),
+            dead_shares: 0,
+        },
+    }
+}
+
+pub fn default_initial_mint_minimum(decimals: u32) -> i128 {
+    10i128.pow(decimals)
+}
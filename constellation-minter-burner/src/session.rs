@@ -0,0 +1,89 @@
+// Split mints for baskets too large to deposit in one transaction.
+// mint_prepare() fixes the components and amounts owed, mint_execute() deposits a range of them into
+// this contract, and mint_finalize() moves them to the ctoken and mints once every one is in.
+// mint_abort() refunds the deposits: the owner can abort at any time, anyone once the session expired.
+use crate::error::Error;
+use crate::storage_types::{
+    DataKey, DAY_IN_LEDGERS, SESSION_BUMP_AMOUNT, SESSION_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{contracttype, panic_with_error, Address, Env, Vec};
+
+// Ledgers a session stays open for unless the admin configures otherwise
+pub(crate) const DEFAULT_SESSION_LEDGERS: u32 = DAY_IN_LEDGERS;
+
+#[derive(Clone)]
+#[contracttype]
+pub struct MintSession {
+    pub owner: Address,
+    pub ctoken: Address,
+    pub ctoken_amount: i128,
+    pub components: Vec<Address>,
+    pub required: Vec<i128>,
+    pub deposited: Vec<bool>,
+    pub expiration_ledger: u32,
+}
+
+impl MintSession {
+    pub fn is_complete(&self) -> bool {
+        !self.deposited.contains(false)
+    }
+
+    pub fn is_expired(&self, e: &Env) -> bool {
+        e.ledger().sequence() > self.expiration_ledger
+    }
+}
+
+pub fn read_session_ledgers(e: &Env) -> u32 {
+    let key = DataKey::SessionLedgers;
+    e.storage()
+        .instance()
+        .get(&key)
+        .unwrap_or(DEFAULT_SESSION_LEDGERS)
+}
+
+pub fn write_session_ledgers(e: &Env, ledgers: u32) {
+    if ledgers == 0 {
+        panic!("sessions must last at least one ledger")
+    }
+    let key = DataKey::SessionLedgers;
+    e.storage().instance().set(&key, &ledgers);
+}
+
+// Allocates the next session id, starting at 1
+pub fn create_session(e: &Env, session: &MintSession) -> u32 {
+    let key = DataKey::SessionCounter;
+    let id = e
+        .storage()
+        .instance()
+        .get::<DataKey, u32>(&key)
+        .unwrap_or(0)
+        + 1;
+    e.storage().instance().set(&key, &id);
+    write_session(e, id, session);
+    id
+}
+
+pub fn read_session(e: &Env, id: u32) -> Option<MintSession> {
+    let key = DataKey::Session(id);
+    e.storage().persistent().get(&key)
+}
+
+// Traps with NoSession if 'id' doesn't exist or was already finalized or aborted
+pub fn load_session(e: &Env, id: u32) -> MintSession {
+    match read_session(e, id) {
+        Some(session) => session,
+        None => panic_with_error!(e, Error::NoSession),
+    }
+}
+
+pub fn write_session(e: &Env, id: u32, session: &MintSession) {
+    let key = DataKey::Session(id);
+    e.storage().persistent().set(&key, session);
+    e.storage()
+        .persistent()
+        .bump(&key, SESSION_LIFETIME_THRESHOLD, SESSION_BUMP_AMOUNT);
+}
+
+pub fn remove_session(e: &Env, id: u32) {
+    e.storage().persistent().remove(&DataKey::Session(id));
+}
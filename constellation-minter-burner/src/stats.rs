@@ -0,0 +1,43 @@
+// Lifetime issuance and redemption totals per (user, ctoken), so analytics don't have to index every event.
+// One persistent entry per pair, extended whenever it is updated.
+use crate::storage_types::{DataKey, STATS_BUMP_AMOUNT, STATS_LIFETIME_THRESHOLD};
+use soroban_sdk::{contracttype, Address, Env};
+
+#[derive(Clone)]
+#[contracttype]
+pub struct UserStats {
+    pub minted: i128,
+    pub burned: i128,
+    pub last_action_ledger: u32,
+}
+
+pub fn read_user_stats(e: &Env, user: Address, ctoken: Address) -> UserStats {
+    let key = DataKey::UserStats(user, ctoken);
+    e.storage().persistent().get(&key).unwrap_or(UserStats {
+        minted: 0,
+        burned: 0,
+        last_action_ledger: 0,
+    })
+}
+
+fn write_user_stats(e: &Env, user: Address, ctoken: Address, stats: &UserStats) {
+    let key = DataKey::UserStats(user, ctoken);
+    e.storage().persistent().set(&key, stats);
+    e.storage()
+        .persistent()
+        .bump(&key, STATS_LIFETIME_THRESHOLD, STATS_BUMP_AMOUNT);
+}
+
+pub fn record_mint(e: &Env, user: Address, ctoken: Address, amount: i128) {
+    let mut stats = read_user_stats(e, user.clone(), ctoken.clone());
+    stats.minted += amount;
+    stats.last_action_ledger = e.ledger().sequence();
+    write_user_stats(e, user, ctoken, &stats);
+}
+
+pub fn record_burn(e: &Env, user: Address, ctoken: Address, amount: i128) {
+    let mut stats = read_user_stats(e, user.clone(), ctoken.clone());
+    stats.burned += amount;
+    stats.last_action_ledger = e.ledger().sequence();
+    write_user_stats(e, user, ctoken, &stats);
+}
@@ -0,0 +1,33 @@
+// The keys are defined in constellation_shared::storage, with the Constellation Token's
+pub use constellation_shared::storage::{
+    ClaimDataKey, EscrowDataKey, MinterBurnerDataKey as DataKey,
+};
+use soroban_sdk::contracttype;
+
+pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
+pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
+pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+pub(crate) const ESCROW_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const ESCROW_LIFETIME_THRESHOLD: u32 = ESCROW_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+pub(crate) const ISSUANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const ISSUANCE_LIFETIME_THRESHOLD: u32 = ISSUANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+pub(crate) const CLAIM_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const CLAIM_LIFETIME_THRESHOLD: u32 = CLAIM_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+pub(crate) const CTOKEN_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const CTOKEN_LIFETIME_THRESHOLD: u32 = CTOKEN_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+pub(crate) const STATS_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const STATS_LIFETIME_THRESHOLD: u32 = STATS_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+pub(crate) const SESSION_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const SESSION_LIFETIME_THRESHOLD: u32 = SESSION_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+#[contracttype]
+pub struct EscrowValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
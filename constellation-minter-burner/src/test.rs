@@ -0,0 +1,1868 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::batch::BurnRequest;
+use crate::constellation_token;
+use crate::error::Error;
+use crate::storage_types::DAY_IN_LEDGERS;
+use crate::{ConstellationMinterBurner, ConstellationMinterBurnerClient};
+use constellation_mocks::{MockToken, MockTokenClient};
+use constellation_shared::events::{
+    AmountEvent, ComponentErrorEvent, ErrorEvent, IssueEvent, RedeemEvent, ERROR, ISSUE, QUEUED,
+    REDEEM,
+};
+use soroban_sdk::testutils::{Address as _, AuthorizedFunction, Events, Ledger};
+use soroban_sdk::{
+    contracttype, symbol_short, vec, Address, Env, IntoVal, String, Symbol, TryFromVal, Val, Vec,
+};
+
+const DECIMALS: u32 = 7;
+
+struct Setup<'a> {
+    env: Env,
+    minter: ConstellationMinterBurnerClient<'a>,
+    ctoken: constellation_token::Client<'a>,
+    components: std::vec::Vec<MockTokenClient<'a>>,
+}
+
+fn create_component<'a>(e: &Env, decimals: u32) -> MockTokenClient<'a> {
+    let component = MockTokenClient::new(e, &e.register_contract(None, MockToken));
+    component.initialize(
+        &decimals,
+        &String::from_str(e, "Component"),
+        &String::from_str(e, "CMP"),
+    );
+    component
+}
+
+// A 7-decimal ctoken over 'components' registered with 'minter', which becomes its admin
+fn create_ctoken<'a>(
+    e: &Env,
+    minter: &ConstellationMinterBurnerClient,
+    components: &[&MockTokenClient],
+    units: &[u32],
+) -> constellation_token::Client<'a> {
+    let ctoken = deploy_ctoken(e, minter, components, units);
+    minter.add_ctoken(&ctoken.address, &Some(1), &0);
+    ctoken
+}
+
+// create_ctoken() without registering the ctoken with 'minter'
+fn deploy_ctoken<'a>(
+    e: &Env,
+    minter: &ConstellationMinterBurnerClient,
+    components: &[&MockTokenClient],
+    units: &[u32],
+) -> constellation_token::Client<'a> {
+    let mut addresses = Vec::new(e);
+    let mut amounts = Vec::new(e);
+    for (component, u) in components.iter().zip(units) {
+        addresses.push_back(component.address.clone());
+        amounts.push_back(*u);
+    }
+    let ctoken = constellation_token::Client::new(
+        e,
+        &e.register_contract_wasm(None, constellation_token::WASM),
+    );
+    ctoken.initialize(
+        &DECIMALS,
+        &addresses,
+        &amounts,
+        &minter.address,
+        &Address::random(e),
+        &String::from_str(e, "Constellation"),
+        &String::from_str(e, "CTKN"),
+        &false,
+        &false,
+    );
+    ctoken
+}
+
+// A MinterBurner with one registered ctoken backed by one 7-decimal mock component per entry of 'units'
+fn setup<'a>(units: &[u32]) -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+
+    let minter = ConstellationMinterBurnerClient::new(
+        &env,
+        &env.register_contract(None, ConstellationMinterBurner),
+    );
+    minter.initialize(&Address::random(&env));
+
+    let components: std::vec::Vec<MockTokenClient> = units
+        .iter()
+        .map(|_| create_component(&env, DECIMALS))
+        .collect();
+    let ctoken = create_ctoken(
+        &env,
+        &minter,
+        &components.iter().collect::<std::vec::Vec<_>>(),
+        units,
+    );
+    Setup {
+        env,
+        minter,
+        ctoken,
+        components,
+    }
+}
+
+impl<'a> Setup<'a> {
+    // Give 'user' the components for 'amount' ctokens and mint them through the MinterBurner
+    fn mint(&self, user: &Address, amount: i128) {
+        self.mint_ctoken(&self.ctoken, user, amount);
+    }
+
+    fn mint_ctoken(&self, ctoken: &constellation_token::Client, user: &Address, amount: i128) {
+        let units = ctoken.getAmounts();
+        for (i, component) in ctoken.getComponents().iter().enumerate() {
+            MockTokenClient::new(&self.env, &component)
+                .mint(user, &(amount * units.get(i as u32).unwrap() as i128));
+        }
+        self.minter.mint(user, user, &ctoken.address, &amount);
+    }
+
+    // Let the MinterBurner burn 'amount' of the user's ctokens
+    fn approve(&self, user: &Address, amount: i128) {
+        self.approve_ctoken(&self.ctoken, user, amount);
+    }
+
+    fn approve_ctoken(&self, ctoken: &constellation_token::Client, user: &Address, amount: i128) {
+        ctoken.approve(
+            user,
+            &self.minter.address,
+            &amount,
+            &(self.env.ledger().sequence() + 1000),
+        );
+    }
+
+    // Topics and payload of each ("issuance", 'action', ...) event the MinterBurner published, oldest first
+    fn events(&self, action: Symbol) -> std::vec::Vec<(Vec<Val>, Val)> {
+        let mut events = std::vec::Vec::new();
+        for (contract, topics, data) in self.env.events().all().iter() {
+            if contract == self.minter.address
+                && Symbol::try_from_val(&self.env, &topics.get(1).unwrap()) == Ok(action.clone())
+            {
+                events.push((topics, data));
+            }
+        }
+        events
+    }
+
+    // Code and payload of the last ("error", code) event the MinterBurner published
+    fn last_error(&self) -> (u32, ErrorEvent) {
+        let (_, topics, data) = self
+            .env
+            .events()
+            .all()
+            .iter()
+            .filter(|(contract, topics, _)| {
+                *contract == self.minter.address
+                    && Symbol::try_from_val(&self.env, &topics.get(0).unwrap()) == Ok(ERROR)
+            })
+            .last()
+            .unwrap();
+        (
+            u32::try_from_val(&self.env, &topics.get(1).unwrap()).unwrap(),
+            ErrorEvent::try_from_val(&self.env, &data).unwrap(),
+        )
+    }
+
+    // Redeem events with their ctoken
+    fn redeem_events(&self) -> std::vec::Vec<(Address, RedeemEvent)> {
+        self.events(REDEEM)
+            .iter()
+            .map(|(topics, data)| {
+                (
+                    Address::try_from_val(&self.env, &topics.get(2).unwrap()).unwrap(),
+                    RedeemEvent::try_from_val(&self.env, data).unwrap(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_burn_with_queue_queues_failed_component() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.approve(&user, 100);
+
+    // The issuer of the second component freezes its transfers
+    s.components[1].set_fail_transfers(&true);
+    s.minter.burn_with_queue(&user, &s.ctoken.address, &100);
+
+    assert_eq!(s.ctoken.balance(&user), 0);
+    assert_eq!(s.components[0].balance(&user), 200);
+    assert_eq!(s.components[1].balance(&user), 0);
+    let failed = s.components[1].address.clone();
+    assert_eq!(
+        s.minter.pending_claim(&user, &s.ctoken.address, &failed),
+        300
+    );
+    assert_eq!(s.minter.queued_claims(&s.ctoken.address, &failed), 300);
+
+    // Still frozen: the claim traps and stays queued
+    assert!(s
+        .minter
+        .try_claim(&user, &s.ctoken.address, &failed)
+        .is_err());
+    assert_eq!(
+        s.minter.pending_claim(&user, &s.ctoken.address, &failed),
+        300
+    );
+
+    s.components[1].set_fail_transfers(&false);
+    s.minter.claim(&user, &s.ctoken.address, &failed);
+    assert_eq!(s.components[1].balance(&user), 300);
+    assert_eq!(s.minter.pending_claim(&user, &s.ctoken.address, &failed), 0);
+    assert_eq!(s.minter.queued_claims(&s.ctoken.address, &failed), 0);
+}
+
+#[test]
+fn test_claim_with_nothing_queued() {
+    let s = setup(&[1]);
+    let user = Address::random(&s.env);
+    assert_eq!(
+        s.minter
+            .try_claim(&user, &s.ctoken.address, &s.components[0].address),
+        Err(Ok(Error::NothingToClaim.into()))
+    );
+}
+
+#[test]
+fn test_burn_traps_on_failed_component() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.approve(&user, 100);
+
+    // Without the queue the whole redemption reverts
+    s.components[1].set_fail_transfers(&true);
+    assert!(s.minter.try_burn(&user, &s.ctoken.address, &100).is_err());
+    assert_eq!(s.ctoken.balance(&user), 100);
+    assert_eq!(s.components[0].balance(&user), 0);
+}
+
+#[test]
+fn test_mint_round_trip() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.components[0].mint(&user, &200);
+    s.components[1].mint(&user, &300);
+    s.minter.mint(&user, &user, &s.ctoken.address, &100);
+    assert_eq!(s.ctoken.balance(&user), 100);
+    assert_eq!(s.components[0].balance(&user), 0);
+    assert_eq!(s.components[1].balance(&user), 0);
+    assert_eq!(s.components[0].balance(&s.ctoken.address), 200);
+    assert_eq!(s.components[1].balance(&s.ctoken.address), 300);
+
+    // Short of the second component, nothing is taken and nothing is minted
+    let other = Address::random(&s.env);
+    s.components[0].mint(&other, &200);
+    assert!(s
+        .minter
+        .try_mint(&other, &other, &s.ctoken.address, &100)
+        .is_err());
+    assert_eq!(s.ctoken.balance(&other), 0);
+    assert_eq!(s.components[0].balance(&other), 200);
+}
+
+// Standard token events ('name', ...) the ctoken published, oldest first
+fn token_events(s: &Setup, name: &str) -> std::vec::Vec<(Vec<Val>, i128)> {
+    let mut events = std::vec::Vec::new();
+    for (contract, topics, data) in s.env.events().all().iter() {
+        if contract == s.ctoken.address
+            && Symbol::try_from_val(&s.env, &topics.get(0).unwrap())
+                == Ok(Symbol::new(&s.env, name))
+        {
+            events.push((topics, i128::try_from_val(&s.env, &data).unwrap()));
+        }
+    }
+    events
+}
+
+#[test]
+fn test_mint_and_redeem_end_to_end() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    for (i, component) in s.components.iter().enumerate() {
+        component.mint(&user, &(1000 * [2, 3][i]));
+    }
+
+    // Mint: components move from the user to the ctoken, ctokens to the user
+    s.minter.mint(&user, &user, &s.ctoken.address, &400);
+    assert_eq!(s.ctoken.balance(&user), 400);
+    assert_eq!(s.ctoken.total_supply(), 400);
+    assert_eq!(s.components[0].balance(&user), 1200);
+    assert_eq!(s.components[1].balance(&user), 1800);
+    assert_eq!(s.components[0].balance(&s.ctoken.address), 800);
+    assert_eq!(s.components[1].balance(&s.ctoken.address), 1200);
+    assert_eq!(s.minter.get_issuance(&s.ctoken.address), 400);
+
+    let issue = s.events(ISSUE);
+    assert_eq!(issue.len(), 1);
+    assert_eq!(
+        Address::try_from_val(&s.env, &issue[0].0.get(3).unwrap()).unwrap(),
+        user
+    );
+    let issue = IssueEvent::try_from_val(&s.env, &issue[0].1).unwrap();
+    assert_eq!(issue.to, user);
+    assert_eq!(issue.amount, 400);
+    let minted = token_events(&s, "mint");
+    assert_eq!(minted.len(), 1);
+    assert_eq!(minted[0].1, 400);
+
+    // Redeem: the MinterBurner burns with the user's approval and the ctoken releases the components
+    s.approve(&user, 150);
+    s.minter.burn(&user, &s.ctoken.address, &150);
+    assert_eq!(s.ctoken.balance(&user), 250);
+    assert_eq!(s.ctoken.total_supply(), 250);
+    assert_eq!(s.ctoken.allowance(&user, &s.minter.address), 0);
+    assert_eq!(s.components[0].balance(&user), 1500);
+    assert_eq!(s.components[1].balance(&user), 2250);
+    assert_eq!(s.components[0].balance(&s.ctoken.address), 500);
+    assert_eq!(s.components[1].balance(&s.ctoken.address), 750);
+    assert_eq!(s.minter.get_issuance(&s.ctoken.address), 250);
+    assert!(s.ctoken.is_fully_backed());
+
+    let redeemed = s.redeem_events();
+    assert_eq!(redeemed.len(), 1);
+    assert_eq!(redeemed[0].0, s.ctoken.address);
+    assert_eq!(redeemed[0].1.to, user);
+    assert_eq!(redeemed[0].1.amount, 150);
+    assert_eq!(redeemed[0].1.released, vec![&s.env, 300, 450]);
+    let burned = token_events(&s, "burn");
+    assert_eq!(burned.len(), 1);
+    assert_eq!(
+        Address::try_from_val(&s.env, &burned[0].0.get(1).unwrap()).unwrap(),
+        user
+    );
+    assert_eq!(burned[0].1, 150);
+    assert!(redeemed[0].1.seq > issue.seq);
+}
+
+#[test]
+fn test_redeem_without_approval() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+
+    // burn() spends the ctoken allowance the user gave the MinterBurner
+    assert!(s.minter.try_burn(&user, &s.ctoken.address, &100).is_err());
+    assert_eq!(s.ctoken.balance(&user), 100);
+    assert_eq!(s.components[0].balance(&s.ctoken.address), 200);
+}
+
+#[test]
+fn test_burn_ctoken_batch() {
+    let s = setup(&[2, 3]);
+    // A second ctoken sharing the second component
+    let other = create_component(&s.env, DECIMALS);
+    let ctoken = create_ctoken(&s.env, &s.minter, &[&s.components[1], &other], &[4, 1]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.mint_ctoken(&ctoken, &user, 50);
+    s.approve(&user, 100);
+    s.approve_ctoken(&ctoken, &user, 50);
+
+    s.minter.burn_ctoken_batch(
+        &user,
+        &vec![
+            &s.env,
+            BurnRequest {
+                ctoken: s.ctoken.address.clone(),
+                amount: 100,
+                min_amounts_out: vec![&s.env, 200, 300],
+            },
+            BurnRequest {
+                ctoken: ctoken.address.clone(),
+                amount: 50,
+                min_amounts_out: vec![&s.env, 200, 50],
+            },
+        ],
+    );
+
+    // One redeem event per ctoken
+    let events = s.redeem_events();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].0, s.ctoken.address);
+    assert_eq!(events[0].1.released, vec![&s.env, 200, 300]);
+    assert_eq!(events[1].0, ctoken.address);
+    assert_eq!(events[1].1.released, vec![&s.env, 200, 50]);
+
+    // The shared component arrives from both reserves
+    assert_eq!(s.components[0].balance(&user), 200);
+    assert_eq!(s.components[1].balance(&user), 500);
+    assert_eq!(other.balance(&user), 50);
+    assert_eq!(s.ctoken.balance(&user), 0);
+    assert_eq!(ctoken.balance(&user), 0);
+}
+
+#[test]
+fn test_burn_ctoken_batch_is_all_or_nothing() {
+    let s = setup(&[2, 3]);
+    let other = create_component(&s.env, DECIMALS);
+    let ctoken = create_ctoken(&s.env, &s.minter, &[&s.components[1], &other], &[4, 1]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.mint_ctoken(&ctoken, &user, 50);
+    s.approve(&user, 100);
+    s.approve_ctoken(&ctoken, &user, 50);
+
+    // The second redemption releases less than its floor, so the first is reverted too
+    assert_eq!(
+        s.minter.try_burn_ctoken_batch(
+            &user,
+            &vec![
+                &s.env,
+                BurnRequest {
+                    ctoken: s.ctoken.address.clone(),
+                    amount: 100,
+                    min_amounts_out: vec![&s.env, 200, 300],
+                },
+                BurnRequest {
+                    ctoken: ctoken.address.clone(),
+                    amount: 50,
+                    min_amounts_out: vec![&s.env, 201, 50],
+                },
+            ],
+        ),
+        Err(Ok(Error::SlippageExceeded.into()))
+    );
+    let (code, error) = s.last_error();
+    assert_eq!(code, Error::SlippageExceeded as u32);
+    assert_eq!(
+        (error.component, error.shortfall),
+        (s.components[1].address.clone(), 1)
+    );
+    assert_eq!(s.ctoken.balance(&user), 100);
+    assert_eq!(ctoken.balance(&user), 50);
+    assert_eq!(s.components[1].balance(&user), 0);
+}
+
+#[test]
+fn test_wind_down_overrides_redeem_pause() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.approve(&user, 100);
+
+    s.minter.pause();
+    s.minter.set_allow_redeem_while_paused(&false);
+    assert_eq!(
+        s.minter.try_burn(&user, &s.ctoken.address, &10),
+        Err(Ok(Error::Paused.into()))
+    );
+
+    // Redeemable as soon as the wind-down is announced, even by a frozen account
+    s.ctoken.initiate_wind_down();
+    s.ctoken.freeze_account(&user);
+    s.minter.burn(&user, &s.ctoken.address, &10);
+    s.minter.burn_with_queue(&user, &s.ctoken.address, &10);
+    assert_eq!(s.ctoken.balance(&user), 80);
+    assert_eq!(s.components[1].balance(&user), 60);
+}
+
+#[test]
+fn test_mint_traps_after_wind_down() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    let effective_ledger = s.ctoken.initiate_wind_down();
+
+    // Open during the notice period
+    s.mint(&user, 100);
+    s.env
+        .ledger()
+        .with_mut(|li| li.sequence_number = effective_ledger);
+    for (i, component) in s.components.iter().enumerate() {
+        component.mint(&user, &(10 * [2, 3][i]));
+    }
+    assert_eq!(
+        s.minter.try_mint(&user, &user, &s.ctoken.address, &10),
+        Err(Ok(Error::WindDown.into()))
+    );
+}
+
+#[test]
+fn test_set_unit_increase_excludes_queued_claims() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.approve(&user, 50);
+    // 150 of the second component stay in the ctoken for a queued claim
+    s.components[1].set_fail_transfers(&true);
+    s.minter.burn_with_queue(&user, &s.ctoken.address, &50);
+    s.components[1].set_fail_transfers(&false);
+
+    // Holding 300 for a supply of 50 covers 4 units only once the claim is counted as backing
+    assert_eq!(
+        s.ctoken.try_set_unit(&s.components[1].address, &4),
+        Err(Ok(constellation_token::Error::Undercollateralized.into()))
+    );
+    s.ctoken.set_unit(&s.components[1].address, &3);
+
+    // A deposit covering 50 * 4 beyond the claim is enough
+    let manager = Address::random(&s.env);
+    s.components[1].mint(&manager, &50);
+    s.components[1].transfer(&manager, &s.ctoken.address, &50);
+    s.ctoken.set_unit(&s.components[1].address, &4);
+    assert_eq!(s.ctoken.getAmounts(), vec![&s.env, 2, 4]);
+}
+
+#[test]
+fn test_set_unit_decrease_sweeps_excess_as_dust() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.approve(&user, 10);
+    s.components[1].set_fail_transfers(&true);
+    s.minter.burn_with_queue(&user, &s.ctoken.address, &10);
+    s.components[1].set_fail_transfers(&false);
+    let manager = s.ctoken.dump_config().manager;
+
+    // Supply 90: 3 -> 1 frees 180, and the 30 queued for the claim stay
+    s.ctoken.set_unit(&s.components[1].address, &1);
+    assert_eq!(s.components[1].balance(&manager), 180);
+    assert_eq!(s.components[1].balance(&s.ctoken.address), 90 + 30);
+
+    s.minter
+        .claim(&user, &s.ctoken.address, &s.components[1].address);
+    assert_eq!(s.components[1].balance(&user), 30);
+}
+
+#[test]
+fn test_burn_emits_released_amounts() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.approve(&user, 40);
+
+    s.minter.burn(&user, &s.ctoken.address, &40);
+    let events = s.redeem_events();
+    assert_eq!(events.len(), 1);
+    let (ctoken, event) = &events[0];
+    assert_eq!(*ctoken, s.ctoken.address);
+    assert_eq!(event.to, user);
+    assert_eq!(event.amount, 40);
+    assert_eq!(
+        event.components,
+        vec![
+            &s.env,
+            s.components[0].address.clone(),
+            s.components[1].address.clone()
+        ]
+    );
+    assert_eq!(event.released, vec![&s.env, 80, 120]);
+}
+
+#[test]
+fn test_burn_with_queue_emits_redeem_and_queued() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.approve(&user, 100);
+
+    s.components[1].set_fail_transfers(&true);
+    s.minter.burn_with_queue(&user, &s.ctoken.address, &100);
+
+    let queued = s.events(QUEUED);
+    let redeemed = s.redeem_events();
+    assert_eq!(redeemed.len(), 1);
+    assert_eq!(redeemed[0].1.amount, 100);
+    assert_eq!(redeemed[0].1.released, vec![&s.env, 200, 0]);
+
+    assert_eq!(queued.len(), 1);
+    let (topics, data) = &queued[0];
+    assert_eq!(
+        Address::try_from_val(&s.env, &topics.get(3).unwrap()).unwrap(),
+        s.components[1].address
+    );
+    assert_eq!(AmountEvent::try_from_val(&s.env, data).unwrap().amount, 300);
+}
+
+#[test]
+fn test_mint_from_escrow_emits_issue() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    for (i, component) in s.components.iter().enumerate() {
+        component.mint(&user, &(100 * [2, 3][i]));
+        s.minter
+            .deposit(&user, &component.address, &(100 * [2, 3][i]), &0);
+    }
+
+    // Short of escrow for 101
+    assert!(s
+        .minter
+        .try_mint_from_escrow(&user, &user, &s.ctoken.address, &101)
+        .is_err());
+
+    s.minter
+        .mint_from_escrow(&user, &user, &s.ctoken.address, &100);
+    assert_eq!(s.ctoken.balance(&user), 100);
+    let events = s.events(ISSUE);
+    assert_eq!(events.len(), 1);
+    let event = IssueEvent::try_from_val(&s.env, &events[0].1).unwrap();
+    assert_eq!(event.to, user);
+    assert_eq!(event.amount, 100);
+    assert!(!event.via_allowance);
+    assert_eq!(event.seq, s.minter.get_event_seq());
+}
+
+#[test]
+fn test_event_seq_has_no_gaps() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    let mut seq = s.minter.get_event_seq();
+
+    s.mint(&user, 100);
+    let issue = IssueEvent::try_from_val(&s.env, &s.events(ISSUE).last().unwrap().1).unwrap();
+    assert_eq!(issue.seq, seq + 1);
+    seq = issue.seq;
+
+    s.approve(&user, 100);
+    s.minter.burn(&user, &s.ctoken.address, &40);
+    assert_eq!(s.redeem_events().last().unwrap().1.seq, seq + 1);
+    seq += 1;
+
+    // One queued event, then the redeem event
+    s.components[1].set_fail_transfers(&true);
+    s.minter.burn_with_queue(&user, &s.ctoken.address, &60);
+    let queued = AmountEvent::try_from_val(&s.env, &s.events(QUEUED).last().unwrap().1).unwrap();
+    assert_eq!(queued.seq, seq + 1);
+    assert_eq!(s.redeem_events().last().unwrap().1.seq, seq + 2);
+    assert_eq!(s.minter.get_event_seq(), seq + 2);
+}
+
+#[test]
+fn test_collect_dust_goes_to_fee_recipient() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.approve(&user, 10);
+    // 30 of the second component stay in the ctoken for a queued claim
+    s.components[1].set_fail_transfers(&true);
+    s.minter.burn_with_queue(&user, &s.ctoken.address, &10);
+    s.components[1].set_fail_transfers(&false);
+    let manager = s.ctoken.dump_config().manager;
+    assert_eq!(s.ctoken.fee_recipient(), manager);
+
+    let donor = Address::random(&s.env);
+    s.components[0].mint(&donor, &7);
+    s.components[0].transfer(&donor, &s.ctoken.address, &7);
+    s.components[1].mint(&donor, &5);
+    s.components[1].transfer(&donor, &s.ctoken.address, &5);
+    s.ctoken.set_dust_epsilon(&2);
+    // Without a fee recipient the manager gets everything above 90 * units, the claim and the epsilon
+    assert_eq!(s.ctoken.collect_dust(), vec![&s.env, 5, 3]);
+    assert_eq!(s.components[0].balance(&manager), 5);
+    assert_eq!(s.components[1].balance(&manager), 3);
+    assert_eq!(s.components[0].balance(&s.ctoken.address), 180 + 2);
+    assert_eq!(s.components[1].balance(&s.ctoken.address), 270 + 30 + 2);
+
+    let recipient = Address::random(&s.env);
+    s.ctoken.set_fee_recipient(&recipient);
+    assert_eq!(s.ctoken.fee_recipient(), recipient);
+    s.components[0].mint(&donor, &4);
+    s.components[0].transfer(&donor, &s.ctoken.address, &4);
+    assert_eq!(s.ctoken.collect_dust(), vec![&s.env, 4, 0]);
+    assert_eq!(s.components[0].balance(&recipient), 4);
+    assert_eq!(s.components[0].balance(&manager), 5);
+
+    // Nothing left above the requirement, and the claim is still paid in full
+    assert_eq!(s.ctoken.collect_dust(), vec![&s.env, 0, 0]);
+    s.minter
+        .claim(&user, &s.ctoken.address, &s.components[1].address);
+    assert_eq!(s.components[1].balance(&user), 30);
+}
+
+// The instance is archived once its TTL runs out, so each call proves how far the last one extended it
+#[test]
+#[should_panic]
+fn test_default_ttl_expires_after_a_week() {
+    let s = setup(&[2, 3]);
+    let day = 17280;
+    s.minter.is_paused();
+    s.env
+        .ledger()
+        .with_mut(|li| li.sequence_number += 7 * day + 1);
+    s.minter.is_paused();
+}
+
+#[test]
+fn test_ttl_config() {
+    let s = setup(&[2, 3]);
+    let day = 17280;
+    assert_eq!(s.minter.ttl_config().threshold, 6 * day);
+    assert_eq!(s.minter.ttl_config().bump_amount, 7 * day);
+
+    s.minter.set_ttl_config(&(8 * day), &(10 * day));
+    assert_eq!(s.minter.dump_config().ttl.bump_amount, 10 * day);
+    // Past the default week, and below the new threshold, so this call extends another 10 days
+    s.env.ledger().with_mut(|li| li.sequence_number += 9 * day);
+    s.minter.is_paused();
+    s.env.ledger().with_mut(|li| li.sequence_number += 9 * day);
+    s.minter.is_paused();
+}
+
+#[test]
+fn test_ttl_config_bounds() {
+    let s = setup(&[2, 3]);
+    let day = 17280;
+    assert!(s.minter.try_set_ttl_config(&(7 * day), &(7 * day)).is_err());
+    assert!(s.minter.try_set_ttl_config(&(day - 1), &(7 * day)).is_err());
+    assert!(s.minter.try_set_ttl_config(&day, &(181 * day)).is_err());
+    s.minter.set_ttl_config(&day, &(180 * day));
+    assert_eq!(s.minter.ttl_config().bump_amount, 180 * day);
+}
+
+#[test]
+fn test_deposit_mint_and_refund_escrow() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.components[0].mint(&user, &250);
+    s.components[1].mint(&user, &300);
+    s.minter
+        .deposit(&user, &s.components[0].address, &250, &200);
+    s.minter
+        .deposit(&user, &s.components[1].address, &300, &200);
+    assert_eq!(s.components[0].balance(&s.minter.address), 250);
+
+    s.minter
+        .mint_from_escrow(&user, &user, &s.ctoken.address, &100);
+    assert_eq!(s.ctoken.balance(&user), 100);
+    assert_eq!(s.components[0].balance(&s.ctoken.address), 200);
+    assert_eq!(s.minter.escrow(&user, &s.components[0].address).amount, 50);
+    assert_eq!(s.minter.escrow(&user, &s.components[1].address).amount, 0);
+
+    // The owner's signature is needed until the escrow expires
+    s.minter.refund_deposit(&user, &s.components[0].address);
+    assert_eq!(s.env.auths()[0].0, user);
+    assert_eq!(s.components[0].balance(&user), 50);
+    assert_eq!(s.minter.escrow(&user, &s.components[0].address).amount, 0);
+}
+
+#[test]
+fn test_expired_escrow_refund_needs_no_signature() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.components[0].mint(&user, &50);
+    s.minter.deposit(&user, &s.components[0].address, &50, &200);
+    s.env.ledger().with_mut(|li| li.sequence_number = 201);
+
+    s.minter.refund_deposit(&user, &s.components[0].address);
+    assert!(s.env.auths().is_empty());
+    assert_eq!(s.components[0].balance(&user), 50);
+}
+
+#[test]
+fn test_deposit_traps() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.components[0].mint(&user, &50);
+    // Already expired
+    assert!(s
+        .minter
+        .try_deposit(&user, &s.components[0].address, &50, &99)
+        .is_err());
+    // A fee-on-transfer component would credit more escrow than arrived
+    s.components[0].set_fee_bps(&100);
+    assert_eq!(
+        s.minter
+            .try_deposit(&user, &s.components[0].address, &50, &0),
+        Err(Ok(Error::FeeOnTransferNotSupported.into()))
+    );
+    // Nothing escrowed to mint from
+    assert!(s
+        .minter
+        .try_mint_from_escrow(&user, &user, &s.ctoken.address, &1)
+        .is_err());
+}
+
+#[test]
+fn test_mint_is_one_signature() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+
+    // The component transfers are authorized as sub-invocations of the user's one mint() authorization
+    let auths = s.env.auths();
+    assert_eq!(auths.len(), 1);
+    let (signer, invocation) = &auths[0];
+    assert_eq!(*signer, user);
+    match &invocation.function {
+        AuthorizedFunction::Contract((contract, name, _)) => {
+            assert_eq!(*contract, s.minter.address);
+            assert_eq!(*name, Symbol::new(&s.env, "mint"));
+        }
+        _ => panic!("expected a contract call"),
+    }
+    let transfers: std::vec::Vec<Address> = invocation
+        .sub_invocations
+        .iter()
+        .map(|sub| match &sub.function {
+            AuthorizedFunction::Contract((contract, name, _)) => {
+                assert_eq!(*name, Symbol::new(&s.env, "transfer"));
+                contract.clone()
+            }
+            _ => panic!("expected a contract call"),
+        })
+        .collect();
+    assert_eq!(
+        transfers,
+        [
+            s.components[0].address.clone(),
+            s.components[1].address.clone()
+        ]
+    );
+    assert!(
+        !IssueEvent::try_from_val(&s.env, &s.events(ISSUE)[0].1)
+            .unwrap()
+            .via_allowance
+    );
+}
+
+#[test]
+fn test_mint_with_allowance() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.components[0].mint(&user, &200);
+    s.components[1].mint(&user, &300);
+    // Without approvals the fallback path has nothing to spend
+    assert!(s
+        .minter
+        .try_mint_with_allowance(&user, &user, &s.ctoken.address, &100)
+        .is_err());
+
+    let expiration = s.env.ledger().sequence() + 1000;
+    s.components[0].approve(&user, &s.minter.address, &200, &expiration);
+    s.components[1].approve(&user, &s.minter.address, &300, &expiration);
+    s.minter
+        .mint_with_allowance(&user, &user, &s.ctoken.address, &100);
+    assert_eq!(s.ctoken.balance(&user), 100);
+    assert!(
+        IssueEvent::try_from_val(&s.env, &s.events(ISSUE)[0].1)
+            .unwrap()
+            .via_allowance
+    );
+}
+
+#[test]
+fn test_escrow_is_tracked_per_user_and_component() {
+    let s = setup(&[2, 3]);
+    let alice = Address::random(&s.env);
+    let bob = Address::random(&s.env);
+    for user in [&alice, &bob] {
+        for component in s.components.iter() {
+            component.mint(user, &40);
+            s.minter.deposit(user, &component.address, &40, &0);
+        }
+    }
+    // A top-up replaces the expiry
+    s.components[0].mint(&alice, &10);
+    s.minter
+        .deposit(&alice, &s.components[0].address, &10, &150);
+    let escrow = s.minter.escrow(&alice, &s.components[0].address);
+    assert_eq!(escrow.amount, 50);
+    assert_eq!(escrow.expiration_ledger, 150);
+
+    s.minter.refund_deposit(&alice, &s.components[0].address);
+    assert_eq!(s.components[0].balance(&alice), 50);
+    assert_eq!(s.minter.escrow(&alice, &s.components[1].address).amount, 40);
+    assert_eq!(s.minter.escrow(&bob, &s.components[0].address).amount, 40);
+    assert_eq!(s.components[0].balance(&s.minter.address), 40);
+
+    // Bob's escrow never expires, so refunding it still takes his signature
+    s.minter.refund_deposit(&bob, &s.components[1].address);
+    assert_eq!(s.env.auths()[0].0, bob);
+    assert_eq!(s.components[1].balance(&bob), 40);
+}
+
+#[test]
+fn test_issuance_cap() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    assert_eq!(s.minter.get_issuance_cap(&s.ctoken.address), None);
+    s.minter.set_issuance_cap(&s.ctoken.address, &150);
+    assert_eq!(s.minter.get_issuance_cap(&s.ctoken.address), Some(150));
+
+    s.mint(&user, 100);
+    assert_eq!(s.minter.get_issuance(&s.ctoken.address), 100);
+    for (i, component) in s.components.iter().enumerate() {
+        component.mint(&user, &(60 * [2, 3][i]));
+    }
+    assert_eq!(
+        s.minter.try_mint(&user, &user, &s.ctoken.address, &60),
+        Err(Ok(Error::IssuanceCapExceeded.into()))
+    );
+
+    // Redemptions free headroom
+    s.approve(&user, 30);
+    s.minter.burn(&user, &s.ctoken.address, &30);
+    assert_eq!(s.minter.get_issuance(&s.ctoken.address), 70);
+    s.minter.mint(&user, &user, &s.ctoken.address, &60);
+    s.mint(&user, 20);
+    assert_eq!(s.minter.get_issuance(&s.ctoken.address), 150);
+    assert_eq!(
+        s.minter.try_mint(&user, &user, &s.ctoken.address, &1),
+        Err(Ok(Error::IssuanceCapExceeded.into()))
+    );
+}
+
+#[test]
+fn test_burn_reentry_traps() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.approve(&user, 100);
+    // The first component burns the same ctokens again from inside its transfer
+    s.components[0].set_reenter(
+        &s.minter.address,
+        &Symbol::new(&s.env, "burn"),
+        &vec![
+            &s.env,
+            user.into_val(&s.env),
+            s.ctoken.address.into_val(&s.env),
+            50_i128.into_val(&s.env),
+        ],
+    );
+    // The host refuses to re-enter a contract already on the call stack before the lock in reentrancy.rs
+    // is reached; either way the whole burn traps
+    assert!(s.minter.try_burn(&user, &s.ctoken.address, &50).is_err());
+    // Nothing was withdrawn, once or twice
+    assert_eq!(s.ctoken.balance(&user), 100);
+    assert_eq!(s.ctoken.total_supply(), 100);
+    assert_eq!(s.components[0].balance(&user), 0);
+    assert_eq!(s.components[0].balance(&s.ctoken.address), 200);
+    assert_eq!(s.minter.get_issuance(&s.ctoken.address), 100);
+}
+
+#[test]
+fn test_mint_reentry_traps() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    for (i, component) in s.components.iter().enumerate() {
+        component.mint(&user, &(100 * [2, 3][i]));
+    }
+    // The second component mints again from inside its transfer, after the first component moved
+    s.components[1].set_reenter(
+        &s.minter.address,
+        &Symbol::new(&s.env, "mint"),
+        &vec![
+            &s.env,
+            user.into_val(&s.env),
+            user.into_val(&s.env),
+            s.ctoken.address.into_val(&s.env),
+            50_i128.into_val(&s.env),
+        ],
+    );
+    assert!(s
+        .minter
+        .try_mint(&user, &user, &s.ctoken.address, &50)
+        .is_err());
+    // The counters updated before the transfers are rolled back with them
+    assert_eq!(s.minter.get_issuance(&s.ctoken.address), 100);
+    assert_eq!(
+        s.minter.get_user_stats(&user, &s.ctoken.address).minted,
+        100
+    );
+    assert_eq!(s.ctoken.total_supply(), 100);
+    assert_eq!(s.components[0].balance(&user), 200);
+}
+
+#[test]
+fn test_redeem_fraction() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.approve(&user, 100);
+
+    s.minter.redeem_fraction(&user, &s.ctoken.address, &1, &4);
+    assert_eq!(s.ctoken.balance(&user), 75);
+    assert_eq!(s.components[0].balance(&user), 50);
+    assert_eq!(s.components[1].balance(&user), 75);
+
+    s.minter.redeem_fraction(&user, &s.ctoken.address, &1, &1);
+    assert_eq!(s.ctoken.balance(&user), 0);
+    assert_eq!(s.components[0].balance(&user), 200);
+    assert_eq!(s.components[1].balance(&user), 300);
+}
+
+#[test]
+fn test_redeem_fraction_rejects_invalid_fractions() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.approve(&user, 100);
+    for (numerator, denominator) in [(1, 0), (2, 1), (-1, 2)] {
+        assert_eq!(
+            s.minter
+                .try_redeem_fraction(&user, &s.ctoken.address, &numerator, &denominator),
+            Err(Ok(Error::InvalidFraction.into()))
+        );
+    }
+    assert_eq!(s.ctoken.balance(&user), 100);
+}
+
+#[test]
+fn test_paused_component_blocks_mint_only() {
+    let s = setup(&[1, 2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 10);
+    s.ctoken.pause_component(&s.components[1].address);
+
+    for (i, component) in s.components.iter().enumerate() {
+        component.mint(&user, &(10 * [1, 2, 3][i]));
+    }
+    assert_eq!(
+        s.minter.try_mint(&user, &user, &s.ctoken.address, &10),
+        Err(Ok(Error::ComponentPaused.into()))
+    );
+    // The ("error", code) event names the paused component and its index
+    let (contract, topics, data) = s.env.events().all().last().unwrap();
+    assert_eq!(contract, s.minter.address);
+    assert_eq!(
+        Symbol::try_from_val(&s.env, &topics.get(0).unwrap()),
+        Ok(ERROR)
+    );
+    assert_eq!(
+        u32::try_from_val(&s.env, &topics.get(1).unwrap()),
+        Ok(Error::ComponentPaused as u32)
+    );
+    let event = ComponentErrorEvent::try_from_val(&s.env, &data).unwrap();
+    assert_eq!(event.component, s.components[1].address);
+    assert_eq!(event.index, 1);
+
+    // Holders can still redeem
+    s.approve(&user, 5);
+    s.minter.burn(&user, &s.ctoken.address, &5);
+    assert_eq!(s.ctoken.balance(&user), 5);
+    assert_eq!(s.components[1].balance(&user), 30);
+
+    s.ctoken.unpause_component(&s.components[1].address);
+    s.minter.mint(&user, &user, &s.ctoken.address, &10);
+    assert_eq!(s.ctoken.balance(&user), 15);
+}
+
+#[test]
+fn test_pause_keeps_redemptions_open_by_default() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.approve(&user, 100);
+    assert!(s.minter.allow_redeem_while_paused());
+
+    s.minter.pause();
+    assert!(s.minter.is_paused());
+    s.components[0].mint(&user, &20);
+    s.components[1].mint(&user, &30);
+    assert_eq!(
+        s.minter.try_mint(&user, &user, &s.ctoken.address, &10),
+        Err(Ok(Error::Paused.into()))
+    );
+    s.minter.redeem_fraction(&user, &s.ctoken.address, &1, &2);
+    assert_eq!(s.ctoken.balance(&user), 50);
+
+    // With the flag off, a pause traps holders too
+    s.minter.set_allow_redeem_while_paused(&false);
+    assert_eq!(
+        s.minter
+            .try_redeem_fraction(&user, &s.ctoken.address, &1, &2),
+        Err(Ok(Error::Paused.into()))
+    );
+    s.minter.unpause();
+    s.minter.redeem_fraction(&user, &s.ctoken.address, &1, &1);
+    assert_eq!(s.ctoken.balance(&user), 0);
+}
+
+#[test]
+fn test_burn_batch_across_holders() {
+    let s = setup(&[2, 3]);
+    let custodian = Address::random(&s.env);
+    let alice = Address::random(&s.env);
+    let bob = Address::random(&s.env);
+    s.mint(&alice, 100);
+    s.mint(&bob, 50);
+    let expiration = s.env.ledger().sequence() + 1000;
+    s.ctoken.approve(&alice, &custodian, &100, &expiration);
+    s.ctoken.approve(&bob, &custodian, &50, &expiration);
+
+    s.minter.burn_batch(
+        &custodian,
+        &vec![&s.env, alice.clone(), bob.clone()],
+        &s.ctoken.address,
+        &vec![&s.env, 100, 20],
+    );
+    assert_eq!(s.ctoken.balance(&alice), 0);
+    assert_eq!(s.ctoken.balance(&bob), 30);
+    assert_eq!(s.components[0].balance(&alice), 200);
+    assert_eq!(s.components[1].balance(&alice), 300);
+    assert_eq!(s.components[0].balance(&bob), 40);
+    assert_eq!(s.components[1].balance(&bob), 60);
+    assert_eq!(s.components[0].balance(&custodian), 0);
+
+    // Bob's allowance doesn't cover 40, so nothing is burned
+    s.mint(&alice, 10);
+    s.ctoken.approve(&alice, &custodian, &10, &expiration);
+    assert!(s
+        .minter
+        .try_burn_batch(
+            &custodian,
+            &vec![&s.env, alice.clone(), bob.clone()],
+            &s.ctoken.address,
+            &vec![&s.env, 10, 40],
+        )
+        .is_err());
+    assert_eq!(s.ctoken.balance(&alice), 10);
+    assert!(s
+        .minter
+        .try_burn_batch(
+            &custodian,
+            &vec![&s.env, alice.clone()],
+            &s.ctoken.address,
+            &vec![&s.env, 10, 10],
+        )
+        .is_err());
+    assert_eq!(s.ctoken.balance(&alice), 10);
+}
+
+#[test]
+fn test_mint_rejects_fee_on_transfer_component() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.components[0].mint(&user, &200);
+    s.components[1].mint(&user, &300);
+    s.components[1].set_fee_bps(&100);
+    assert_eq!(
+        s.minter.try_mint(&user, &user, &s.ctoken.address, &100),
+        Err(Ok(Error::FeeOnTransferNotSupported.into()))
+    );
+    // 1% of the 300 transferred never arrives
+    let (code, error) = s.last_error();
+    assert_eq!(code, Error::FeeOnTransferNotSupported as u32);
+    assert_eq!(
+        (error.component, error.shortfall),
+        (s.components[1].address.clone(), 3)
+    );
+    assert_eq!(s.components[0].balance(&user), 200);
+
+    s.components[1].set_fee_bps(&0);
+    s.minter.mint(&user, &user, &s.ctoken.address, &100);
+    assert_eq!(s.ctoken.balance(&user), 100);
+    assert_eq!(s.components[1].balance(&s.ctoken.address), 300);
+}
+
+#[test]
+fn test_burn_to_delivers_components_to_recipient() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    let settlement = Address::random(&s.env);
+    s.mint(&user, 100);
+    s.approve(&user, 100);
+
+    s.minter.burn_to(&user, &settlement, &s.ctoken.address, &40);
+    assert_eq!(s.env.auths()[0].0, user);
+    assert_eq!(s.ctoken.balance(&user), 60);
+    assert_eq!(s.components[0].balance(&settlement), 80);
+    assert_eq!(s.components[1].balance(&settlement), 120);
+    assert_eq!(s.components[0].balance(&user), 0);
+    let redeemed = s.redeem_events();
+    assert_eq!(redeemed.len(), 1);
+    assert_eq!(redeemed[0].1.to, settlement);
+
+    // Limited by what 'from' approved
+    assert!(s
+        .minter
+        .try_burn_to(&user, &settlement, &s.ctoken.address, &61)
+        .is_err());
+    assert_eq!(s.ctoken.balance(&user), 60);
+}
+
+#[test]
+fn test_add_ctoken_requires_minter_as_admin() {
+    let s = setup(&[2]);
+    let deployer = Address::random(&s.env);
+    let ctoken = constellation_token::Client::new(
+        &s.env,
+        &s.env
+            .register_contract_wasm(None, constellation_token::WASM),
+    );
+    ctoken.initialize(
+        &DECIMALS,
+        &vec![&s.env, s.components[0].address.clone()],
+        &vec![&s.env, 2],
+        &deployer,
+        &Address::random(&s.env),
+        &String::from_str(&s.env, "Misconfigured"),
+        &String::from_str(&s.env, "MIS"),
+        &false,
+        &false,
+    );
+    assert_eq!(
+        s.minter.try_add_ctoken(&ctoken.address, &Some(1), &0),
+        Err(Ok(Error::AdminMismatch.into()))
+    );
+    assert!(!s.minter.is_ctoken(&ctoken.address));
+
+    // Registers once the deployer hands the token over
+    ctoken.set_admin(&s.minter.address);
+    s.minter.add_ctoken(&ctoken.address, &Some(1), &0);
+    assert!(s.minter.is_ctoken(&ctoken.address));
+    let user = Address::random(&s.env);
+    s.mint_ctoken(&ctoken, &user, 10);
+    assert_eq!(ctoken.balance(&user), 10);
+}
+
+#[test]
+fn test_mint_shortfall_is_diagnosed() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.components[0].mint(&user, &200);
+    s.components[1].mint(&user, &100);
+
+    let shortfalls = s.minter.diagnose_mint(&user, &s.ctoken.address, &100);
+    assert_eq!(shortfalls.len(), 1);
+    let shortfall = shortfalls.get(0).unwrap();
+    assert_eq!(shortfall.component, s.components[1].address);
+    assert_eq!(shortfall.required, 300);
+    assert_eq!(shortfall.available, 100);
+
+    assert_eq!(
+        s.minter.try_mint(&user, &user, &s.ctoken.address, &100),
+        Err(Ok(Error::InsufficientComponent.into()))
+    );
+    // One ("error", code) event per missing component, published before the trap
+    let mut errors = std::vec::Vec::new();
+    for (contract, topics, data) in s.env.events().all().iter() {
+        if contract == s.minter.address
+            && Symbol::try_from_val(&s.env, &topics.get(0).unwrap()) == Ok(ERROR)
+        {
+            assert_eq!(
+                u32::try_from_val(&s.env, &topics.get(1).unwrap()),
+                Ok(Error::InsufficientComponent as u32)
+            );
+            errors.push(ErrorEvent::try_from_val(&s.env, &data).unwrap());
+        }
+    }
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].component, s.components[1].address);
+    assert_eq!(errors[0].shortfall, 200);
+
+    s.components[1].mint(&user, &200);
+    assert!(s
+        .minter
+        .diagnose_mint(&user, &s.ctoken.address, &100)
+        .is_empty());
+    s.minter.mint(&user, &user, &s.ctoken.address, &100);
+    assert_eq!(s.ctoken.balance(&user), 100);
+}
+
+#[test]
+fn test_user_stats_accumulate() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    let stats = s.minter.get_user_stats(&user, &s.ctoken.address);
+    assert_eq!(
+        (stats.minted, stats.burned, stats.last_action_ledger),
+        (0, 0, 0)
+    );
+
+    s.mint(&user, 100);
+    s.env.ledger().with_mut(|li| li.sequence_number += 10);
+    s.mint(&user, 50);
+    s.approve(&user, 30);
+    s.minter.burn(&user, &s.ctoken.address, &30);
+    let stats = s.minter.get_user_stats(&user, &s.ctoken.address);
+    assert_eq!(stats.minted, 150);
+    assert_eq!(stats.burned, 30);
+    assert_eq!(stats.last_action_ledger, 110);
+
+    // A failed mint records nothing
+    assert!(s
+        .minter
+        .try_mint(&user, &user, &s.ctoken.address, &1000)
+        .is_err());
+    assert_eq!(
+        s.minter.get_user_stats(&user, &s.ctoken.address).minted,
+        150
+    );
+
+    // Past the instance threshold twice over, with the instance kept alive by other calls
+    for _ in 0..2 {
+        s.env
+            .ledger()
+            .with_mut(|li| li.sequence_number += 6 * DAY_IN_LEDGERS + 1);
+        s.minter.get_issuance(&s.ctoken.address);
+    }
+    let stats = s.minter.get_user_stats(&user, &s.ctoken.address);
+    assert_eq!((stats.minted, stats.burned), (150, 30));
+}
+
+#[test]
+fn test_rebalance_manual() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    let manager = s.ctoken.dump_config().manager;
+    let c0 = &s.components[0];
+    c0.mint(&manager, &100);
+
+    // 100 more covers a unit of 3 at a supply of 100, 99 doesn't
+    assert_eq!(
+        s.ctoken
+            .try_rebalance_manual(&manager, &c0.address, &3, &99),
+        Err(Ok(constellation_token::Error::Undercollateralized.into()))
+    );
+    assert_eq!(
+        s.ctoken.try_rebalance_manual(&user, &c0.address, &3, &100),
+        Err(Ok(constellation_token::Error::NotAuthorized.into()))
+    );
+    assert_eq!(c0.balance(&manager), 100);
+    assert_eq!(s.ctoken.getAmounts(), vec![&s.env, 2, 3]);
+
+    s.ctoken.rebalance_manual(&manager, &c0.address, &3, &100);
+    assert_eq!(s.ctoken.getAmounts(), vec![&s.env, 3, 3]);
+    assert_eq!(c0.balance(&s.ctoken.address), 300);
+    assert!(s.ctoken.is_fully_backed());
+}
+
+#[test]
+fn test_mint_and_burn_deadlines() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    for (i, component) in s.components.iter().enumerate() {
+        component.mint(&user, &(100 * [2, 3][i]));
+    }
+    let max_in = vec![&s.env, 200, 300];
+    s.minter
+        .mint_with_limits(&user, &user, &s.ctoken.address, &50, &max_in, &100);
+    s.approve(&user, 100);
+    let min_out = vec![&s.env, 0, 0];
+    s.minter
+        .burn_with_limits(&user, &s.ctoken.address, &10, &min_out, &100);
+
+    s.env.ledger().with_mut(|li| li.sequence_number = 101);
+    assert_eq!(
+        s.minter
+            .try_mint_with_limits(&user, &user, &s.ctoken.address, &50, &max_in, &100),
+        Err(Ok(Error::DeadlineExpired.into()))
+    );
+    assert_eq!(
+        s.minter
+            .try_burn_with_limits(&user, &s.ctoken.address, &10, &min_out, &100),
+        Err(Ok(Error::DeadlineExpired.into()))
+    );
+    assert_eq!(s.ctoken.balance(&user), 40);
+
+    // 0 means no deadline
+    s.minter
+        .mint_with_limits(&user, &user, &s.ctoken.address, &50, &max_in, &0);
+    s.minter
+        .burn_with_limits(&user, &s.ctoken.address, &10, &min_out, &0);
+    assert_eq!(s.ctoken.balance(&user), 80);
+}
+
+#[test]
+fn test_entry_points_before_initialize() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let minter = ConstellationMinterBurnerClient::new(
+        &env,
+        &env.register_contract(None, ConstellationMinterBurner),
+    );
+    let (user, ctoken) = (Address::random(&env), Address::random(&env));
+    let expected = Some(Ok(Error::NotInitialized.into()));
+
+    assert_eq!(minter.try_mint(&user, &user, &ctoken, &1).err(), expected);
+    assert_eq!(minter.try_burn(&user, &ctoken, &1).err(), expected);
+    assert_eq!(minter.try_add_ctoken(&ctoken, &None, &0).err(), expected);
+    assert_eq!(minter.try_get_issuance(&ctoken).err(), expected);
+    assert_eq!(minter.try_get_user_stats(&user, &ctoken).err(), expected);
+    assert_eq!(minter.try_diagnose_mint(&user, &ctoken, &1).err(), expected);
+    assert_eq!(minter.try_claim(&user, &ctoken, &user).err(), expected);
+    assert_eq!(minter.try_pause().err(), expected);
+    assert_eq!(minter.try_is_paused().err(), expected);
+
+    minter.initialize(&user);
+    assert!(!minter.is_paused());
+}
+
+#[test]
+fn test_mixed_decimals_basket() {
+    let s = setup(&[1]);
+    // A SAC-like 7-decimal component and an 18-decimal one, at 1 and 0.01 whole tokens per ctoken
+    let sac = create_component(&s.env, 7);
+    let wide = create_component(&s.env, 18);
+    let ctoken = create_ctoken(&s.env, &s.minter, &[&sac, &wide], &[1, 1_000_000_000]);
+    assert_eq!(ctoken.component_decimals(), vec![&s.env, 7, 18]);
+
+    let one = 10_i128.pow(DECIMALS);
+    let preview = s.minter.preview_mint(&ctoken.address, &one);
+    assert_eq!(
+        preview.amounts_in,
+        vec![&s.env, 10_i128.pow(7), 10_i128.pow(16)]
+    );
+    let user = Address::random(&s.env);
+    s.mint_ctoken(&ctoken, &user, one);
+    assert_eq!(ctoken.balance(&user), one);
+    assert_eq!(sac.balance(&ctoken.address), 10_i128.pow(7));
+    assert_eq!(wide.balance(&ctoken.address), 10_i128.pow(16));
+
+    // A 7-decimal component can't back an 18-decimal ctoken
+    let ctoken = constellation_token::Client::new(
+        &s.env,
+        &s.env
+            .register_contract_wasm(None, constellation_token::WASM),
+    );
+    assert_eq!(
+        ctoken.try_initialize(
+            &18,
+            &vec![&s.env, sac.address.clone()],
+            &vec![&s.env, 1],
+            &s.minter.address,
+            &Address::random(&s.env),
+            &String::from_str(&s.env, "Constellation"),
+            &String::from_str(&s.env, "CTKN"),
+            &false,
+            &false,
+        ),
+        Err(Ok(constellation_token::Error::InvalidDecimals.into()))
+    );
+}
+
+#[test]
+fn test_issuance_rate_limit() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    let ctoken = &s.ctoken.address;
+    assert!(s.minter.get_rate_limit_status(ctoken).is_none());
+    s.minter.set_issuance_rate_limit(ctoken, &100, &50);
+
+    s.mint(&user, 60);
+    let status = s.minter.get_rate_limit_status(ctoken).unwrap();
+    assert_eq!((status.remaining, status.window_end), (40, 150));
+    s.components[0].mint(&user, &100);
+    s.components[1].mint(&user, &150);
+    assert_eq!(
+        s.minter.try_mint(&user, &user, ctoken, &50),
+        Err(Ok(Error::RateLimited.into()))
+    );
+
+    // Redemptions free up capacity
+    s.approve(&user, 20);
+    s.minter.burn(&user, ctoken, &20);
+    assert_eq!(
+        s.minter.get_rate_limit_status(ctoken).unwrap().remaining,
+        60
+    );
+    s.minter.mint(&user, &user, ctoken, &50);
+    assert_eq!(
+        s.minter.get_rate_limit_status(ctoken).unwrap().remaining,
+        10
+    );
+
+    // A new window starts once this one ends
+    s.env.ledger().with_mut(|li| li.sequence_number = 150);
+    let status = s.minter.get_rate_limit_status(ctoken).unwrap();
+    assert_eq!((status.remaining, status.window_end), (100, 200));
+    s.mint(&user, 100);
+    assert_eq!(s.minter.get_rate_limit_status(ctoken).unwrap().remaining, 0);
+
+    s.minter.remove_issuance_rate_limit(ctoken);
+    assert!(s.minter.get_rate_limit_status(ctoken).is_none());
+    s.mint(&user, 100);
+    assert_eq!(s.ctoken.balance(&user), 290);
+}
+
+// The ctoken's metadata entry, as soroban-token-sdk stores it
+#[derive(Clone)]
+#[contracttype]
+struct TokenMetadata {
+    decimal: u32,
+    name: String,
+    symbol: String,
+}
+
+// Change what the ctoken's decimals() returns, as an upgrade could
+fn set_ctoken_decimals(s: &Setup, decimal: u32) {
+    s.env.as_contract(&s.ctoken.address, || {
+        let key = symbol_short!("METADATA");
+        let mut metadata: TokenMetadata = s.env.storage().instance().get(&key).unwrap();
+        metadata.decimal = decimal;
+        s.env.storage().instance().set(&key, &metadata);
+    });
+}
+
+#[test]
+fn test_ctoken_decimals_are_cached() {
+    let s = setup(&[2, 3]);
+    assert_eq!(s.minter.ctoken_decimals(&s.ctoken.address), DECIMALS);
+
+    set_ctoken_decimals(&s, 9);
+    assert_eq!(s.ctoken.decimals(), 9);
+    assert_eq!(s.minter.ctoken_decimals(&s.ctoken.address), DECIMALS);
+    assert_eq!(
+        s.minter.dump_ctoken_config(&s.ctoken.address).decimals,
+        DECIMALS
+    );
+
+    s.minter.refresh_ctoken_metadata(&s.ctoken.address);
+    assert_eq!(s.minter.ctoken_decimals(&s.ctoken.address), 9);
+
+    set_ctoken_decimals(&s, 19);
+    assert_eq!(
+        s.minter.try_refresh_ctoken_metadata(&s.ctoken.address),
+        Err(Ok(Error::InvalidDecimals.into()))
+    );
+    assert_eq!(s.minter.ctoken_decimals(&s.ctoken.address), 9);
+    assert_eq!(
+        s.minter
+            .try_refresh_ctoken_metadata(&Address::random(&s.env)),
+        Err(Ok(Error::UnknownCtoken.into()))
+    );
+}
+
+#[test]
+fn test_reconcile_distributes_donations() {
+    let s = setup(&[2, 3]);
+    assert_eq!(
+        s.ctoken.try_reconcile(),
+        Err(Ok(constellation_token::Error::NoSupply.into()))
+    );
+
+    let user = Address::random(&s.env);
+    s.mint(&user, 100);
+    // 150 donated on top of 200 held backs a unit of 3, 50 more isn't a whole unit
+    s.components[0].mint(&s.ctoken.address, &150);
+    s.components[1].mint(&s.ctoken.address, &50);
+    s.ctoken.reconcile();
+    assert_eq!(s.ctoken.getAmounts(), vec![&s.env, 3, 3]);
+    assert!(s.ctoken.is_fully_backed());
+
+    // Only the first component changed
+    let reconciled = s
+        .env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract, topics, _)| {
+            *contract == s.ctoken.address
+                && Symbol::try_from_val(&s.env, &topics.get(1).unwrap())
+                    == Ok(Symbol::new(&s.env, "reconcile"))
+        })
+        .count();
+    assert_eq!(reconciled, 1);
+}
+
+#[test]
+fn test_split_mint_session() {
+    let s = setup(&[1, 2, 3]);
+    let user = Address::random(&s.env);
+    let to = Address::random(&s.env);
+    for (i, component) in s.components.iter().enumerate() {
+        component.mint(&user, &(10 * (i as i128 + 1)));
+    }
+
+    // One component per transaction
+    let id = s.minter.mint_prepare(&user, &s.ctoken.address, &10);
+    assert_eq!(
+        s.minter.get_mint_session(&id).unwrap().required,
+        vec![&s.env, 10, 20, 30]
+    );
+    s.minter.mint_execute(&id, &0, &1);
+    assert_eq!(
+        s.minter.try_mint_finalize(&id, &to),
+        Err(Ok(Error::SessionIncomplete.into()))
+    );
+    s.minter.mint_execute(&id, &1, &2);
+    // Already deposited components are skipped and 'end' is clamped
+    s.minter.mint_execute(&id, &0, &10);
+    assert_eq!(
+        s.minter.get_mint_session(&id).unwrap().deposited,
+        vec![&s.env, true, true, true]
+    );
+    for component in s.components.iter() {
+        assert_eq!(component.balance(&user), 0);
+    }
+
+    s.minter.mint_finalize(&id, &to);
+    assert_eq!(s.ctoken.balance(&to), 10);
+    for (i, component) in s.components.iter().enumerate() {
+        assert_eq!(component.balance(&s.ctoken.address), 10 * (i as i128 + 1));
+        assert_eq!(component.balance(&s.minter.address), 0);
+    }
+    assert!(s.minter.get_mint_session(&id).is_none());
+    assert_eq!(
+        s.minter.try_mint_finalize(&id, &to),
+        Err(Ok(Error::NoSession.into()))
+    );
+}
+
+#[test]
+fn test_split_mint_session_abort() {
+    let s = setup(&[1, 2]);
+    let user = Address::random(&s.env);
+    s.components[0].mint(&user, &10);
+    s.components[1].mint(&user, &20);
+    s.minter.set_session_ledgers(&10);
+
+    let id = s.minter.mint_prepare(&user, &s.ctoken.address, &10);
+    s.minter.mint_execute(&id, &0, &1);
+    assert_eq!(s.components[0].balance(&s.minter.address), 10);
+    s.minter.mint_abort(&id);
+    assert_eq!(s.components[0].balance(&user), 10);
+    assert_eq!(s.components[1].balance(&user), 20);
+    assert!(s.minter.get_mint_session(&id).is_none());
+    assert_eq!(
+        s.minter.try_mint_execute(&id, &1, &2),
+        Err(Ok(Error::NoSession.into()))
+    );
+
+    // An expired session can only be aborted
+    let id = s.minter.mint_prepare(&user, &s.ctoken.address, &10);
+    s.minter.mint_execute(&id, &0, &2);
+    s.env.ledger().with_mut(|li| li.sequence_number += 11);
+    assert_eq!(
+        s.minter.try_mint_finalize(&id, &user),
+        Err(Ok(Error::SessionExpired.into()))
+    );
+    s.minter.mint_abort(&id);
+    assert_eq!(s.components[0].balance(&user), 10);
+    assert_eq!(s.components[1].balance(&user), 20);
+    assert_eq!(s.ctoken.total_supply(), 0);
+}
+
+#[test]
+fn test_mint_exact_in() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    let to = Address::random(&s.env);
+    s.components[0].mint(&user, &205);
+    s.components[1].mint(&user, &301);
+
+    // 205 backs 102 ctokens, 301 only 100; the excess never leaves the user
+    let supplied = vec![&s.env, 205, 301];
+    assert_eq!(
+        s.minter
+            .mint_exact_in(&user, &to, &s.ctoken.address, &supplied),
+        100
+    );
+    assert_eq!(s.ctoken.balance(&to), 100);
+    assert_eq!(s.components[0].balance(&user), 5);
+    assert_eq!(s.components[1].balance(&user), 1);
+    assert_eq!(s.components[0].balance(&s.ctoken.address), 200);
+    assert_eq!(s.components[1].balance(&s.ctoken.address), 300);
+
+    assert!(s
+        .minter
+        .try_mint_exact_in(&user, &to, &s.ctoken.address, &vec![&s.env, 5])
+        .is_err());
+    assert!(s
+        .minter
+        .try_mint_exact_in(&user, &to, &s.ctoken.address, &vec![&s.env, 5, 1])
+        .is_err());
+    assert_eq!(s.ctoken.balance(&to), 100);
+}
+
+#[test]
+fn test_previews_match_execution() {
+    let s = setup(&[2, 3]);
+    let user = Address::random(&s.env);
+    s.components[0].mint(&user, &200);
+    s.components[1].mint(&user, &300);
+
+    let before = s.env.to_ledger_snapshot();
+    let mint = s.minter.preview_mint(&s.ctoken.address, &100);
+    assert_eq!(
+        s.env.to_ledger_snapshot().ledger_entries,
+        before.ledger_entries
+    );
+    assert_eq!(mint.gross_amount, 100);
+    assert!(mint.fees.is_empty());
+    assert_eq!(mint.components, s.ctoken.getComponents());
+    s.minter.mint(&user, &user, &s.ctoken.address, &100);
+    assert_eq!(s.ctoken.balance(&user), mint.net_amount);
+    for (i, component) in s.components.iter().enumerate() {
+        assert_eq!(
+            component.balance(&s.ctoken.address),
+            mint.amounts_in.get(i as u32).unwrap()
+        );
+    }
+
+    let burn = s.minter.preview_burn(&s.ctoken.address, &40);
+    assert_eq!(burn.gross_amount, 40);
+    assert!(burn.fees.is_empty());
+    s.approve(&user, 40);
+    s.minter.burn(&user, &s.ctoken.address, &40);
+    assert_eq!(s.ctoken.balance(&user), 100 - burn.net_amount);
+    for (i, component) in s.components.iter().enumerate() {
+        assert_eq!(
+            component.balance(&user),
+            burn.amounts_out.get(i as u32).unwrap()
+        );
+    }
+
+    // Traps where mint() would
+    s.ctoken.pause_component(&s.components[0].address);
+    assert!(s.minter.try_preview_mint(&s.ctoken.address, &1).is_err());
+    assert_eq!(
+        s.minter
+            .try_preview_mint(&Address::random(&s.env), &1)
+            .err(),
+        Some(Ok(Error::UnknownCtoken.into()))
+    );
+}
+
+#[test]
+fn test_required_amounts_are_exact() {
+    let s = setup(&[3, 7]);
+    let user = Address::random(&s.env);
+    s.components[0].mint(&user, &21);
+    s.components[1].mint(&user, &49);
+
+    // No division, so no rounding: 7 ctokens take exactly 7 * units of each
+    let preview = s.minter.preview_mint(&s.ctoken.address, &7);
+    assert_eq!(preview.amounts_in, vec![&s.env, 21, 49]);
+    s.minter.mint(&user, &user, &s.ctoken.address, &7);
+    assert_eq!(s.components[0].balance(&user), 0);
+    assert_eq!(s.components[1].balance(&user), 0);
+    assert_eq!(s.ctoken.balance(&user), 7);
+
+    // An amount whose backing overflows traps instead of wrapping
+    assert!(s
+        .minter
+        .try_preview_mint(&s.ctoken.address, &(i128::MAX / 3 + 1))
+        .is_err());
+}
+
+#[test]
+fn test_first_mint_minimum_and_dead_shares() {
+    let s = setup(&[1]);
+    // Re-registering replaces the bootstrap config: one whole token, 1000 dead shares
+    s.minter.add_ctoken(&s.ctoken.address, &None, &1000);
+    let user = Address::random(&s.env);
+    let whole = 10i128.pow(DECIMALS);
+    s.components[0].mint(&user, &(whole + 1));
+
+    assert_eq!(
+        s.minter
+            .try_mint(&user, &user, &s.ctoken.address, &(whole - 1)),
+        Err(Ok(Error::InitialMintTooSmall.into()))
+    );
+    s.minter.mint(&user, &user, &s.ctoken.address, &whole);
+    assert_eq!(s.ctoken.balance(&user), whole - 1000);
+    assert_eq!(s.ctoken.balance(&s.ctoken.address), 1000);
+    assert_eq!(s.ctoken.total_supply(), whole);
+
+    // Only the first mint is held to the minimum and gives up dead shares
+    s.minter.mint(&user, &user, &s.ctoken.address, &1);
+    assert_eq!(s.ctoken.balance(&user), whole - 999);
+    assert_eq!(s.ctoken.balance(&s.ctoken.address), 1000);
+}
+
+#[test]
+fn test_dump_config() {
+    let s = setup(&[2, 3]);
+    s.minter.set_session_ledgers(&50);
+    let admin = s.env.auths()[0].0.clone();
+    s.minter.set_allow_redeem_while_paused(&false);
+    s.minter.pause();
+    s.minter.set_issuance_cap(&s.ctoken.address, &1000);
+    s.minter
+        .set_issuance_rate_limit(&s.ctoken.address, &500, &100);
+
+    let dump = s.minter.dump_config();
+    assert_eq!(dump.storage_version, 2);
+    assert_eq!(dump.admin, admin);
+    assert_eq!(dump.paused, s.minter.is_paused());
+    assert!(dump.paused);
+    assert_eq!(
+        dump.allow_redeem_while_paused,
+        s.minter.allow_redeem_while_paused()
+    );
+    assert_eq!(dump.session_ledgers, 50);
+    assert_eq!(dump.ttl.threshold, s.minter.ttl_config().threshold);
+    assert_eq!(dump.ttl.bump_amount, s.minter.ttl_config().bump_amount);
+
+    let ctoken = s.minter.dump_ctoken_config(&s.ctoken.address);
+    assert_eq!(ctoken.decimals, s.minter.ctoken_decimals(&s.ctoken.address));
+    assert_eq!(ctoken.bootstrap.initial_mint_minimum, 1);
+    assert_eq!(ctoken.bootstrap.dead_shares, 0);
+    assert_eq!(
+        ctoken.issuance_cap,
+        s.minter.get_issuance_cap(&s.ctoken.address)
+    );
+    assert_eq!(ctoken.rate_limit.len(), 1);
+    assert_eq!(ctoken.rate_limit.get(0).unwrap().max_net_mint, 500);
+    assert_eq!(ctoken.rate_limit.get(0).unwrap().window_ledgers, 100);
+
+    assert_eq!(
+        s.minter
+            .try_dump_ctoken_config(&Address::random(&s.env))
+            .err(),
+        Some(Ok(Error::UnknownCtoken.into()))
+    );
+}
+
+// Guards the single basket read per invocation: a 10-component mint and burn stay within CPU ceilings
+// set about 25% above their measured cost. Reading the basket again costs about 130k instructions.
+#[test]
+fn test_ten_component_budget() {
+    let units: std::vec::Vec<u32> = (1..=10).collect();
+    let s = setup(&units);
+    let user = Address::random(&s.env);
+    for (component, u) in s.components.iter().zip(&units) {
+        component.mint(&user, &(100 * *u as i128));
+    }
+
+    s.env.budget().reset_default();
+    s.minter.mint(&user, &user, &s.ctoken.address, &100);
+    let mint_cpu = s.env.budget().cpu_instruction_cost();
+    assert_eq!(s.ctoken.balance(&user), 100);
+
+    s.approve(&user, 100);
+    s.env.budget().reset_default();
+    s.minter.burn(&user, &s.ctoken.address, &100);
+    let burn_cpu = s.env.budget().cpu_instruction_cost();
+    for (component, u) in s.components.iter().zip(&units) {
+        assert_eq!(component.balance(&user), 100 * *u as i128);
+    }
+
+    assert!(mint_cpu < 3_750_000, "mint took {} instructions", mint_cpu);
+    assert!(burn_cpu < 1_900_000, "burn took {} instructions", burn_cpu);
+}
+
+#[test]
+fn test_redeem_traps_for_unregistered_ctoken() {
+    let s = setup(&[2, 3]);
+    let ctoken = deploy_ctoken(
+        &s.env,
+        &s.minter,
+        &[&s.components[0], &s.components[1]],
+        &[2, 3],
+    );
+    // Minted directly by its admin, bypassing the MinterBurner's mint paths
+    let user = Address::random(&s.env);
+    s.components[0].mint(&ctoken.address, &200);
+    s.components[1].mint(&ctoken.address, &300);
+    ctoken.mint(&user, &100);
+    s.approve_ctoken(&ctoken, &user, 100);
+
+    assert_eq!(
+        s.minter.try_burn(&user, &ctoken.address, &10),
+        Err(Ok(Error::UnknownCtoken.into()))
+    );
+    assert_eq!(
+        s.minter.try_redeem_fraction(&user, &ctoken.address, &1, &2),
+        Err(Ok(Error::UnknownCtoken.into()))
+    );
+    assert_eq!(
+        s.minter.try_burn_with_queue(&user, &ctoken.address, &10),
+        Err(Ok(Error::UnknownCtoken.into()))
+    );
+    assert_eq!(ctoken.balance(&user), 100);
+    assert_eq!(s.components[0].balance(&ctoken.address), 200);
+}
@@ -0,0 +1,27 @@
+// Implements constellation_shared::hook::TransferHook, allowing or denying every transfer as set by the test
+use constellation_shared::hook::TransferHook;
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+
+#[derive(Clone)]
+#[contracttype]
+enum DataKey {
+    Allowed,
+}
+
+#[contract]
+pub struct MockTransferHook;
+
+#[contractimpl]
+impl MockTransferHook {
+    // Transfers are allowed until this is called with false
+    pub fn set_transfers_allowed(e: Env, allowed: bool) {
+        e.storage().instance().set(&DataKey::Allowed, &allowed);
+    }
+}
+
+#[contractimpl]
+impl TransferHook for MockTransferHook {
+    fn check_transfer(e: Env, _from: Address, _to: Address, _amount: i128) -> bool {
+        e.storage().instance().get(&DataKey::Allowed).unwrap_or(true)
+    }
+}
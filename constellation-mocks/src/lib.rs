@@ -0,0 +1,19 @@
+// Test doubles for Constellation integrations. Not for production use.
+// - MockToken: a SEP-41 token with configurable decimals, failing transfers, a fee on transfer, re-entry and
+//   transfer_and_call()
+// - MockOracle: a price oracle with settable prices and publication ledgers
+// - MockTransferHook: a transfer hook that allows or denies every transfer
+// - MockReceiver: a transfer_and_call() recipient that accepts or rejects the callback
+// All contracts live in one wasm, so their function names must not overlap.
+
+#![no_std]
+
+pub mod hook;
+pub mod oracle;
+pub mod receiver;
+pub mod token;
+
+pub use crate::hook::{MockTransferHook, MockTransferHookClient};
+pub use crate::oracle::{MockOracle, MockOracleClient};
+pub use crate::receiver::{MockReceiver, MockReceiverClient};
+pub use crate::token::{MockToken, MockTokenClient};
@@ -0,0 +1,33 @@
+// Implements constellation_shared::oracle::Oracle with prices set directly by the test
+use constellation_shared::oracle::{Oracle, PriceData};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+
+#[derive(Clone)]
+#[contracttype]
+enum DataKey {
+    Price(Address),
+}
+
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    // Set 'asset's price, scaled by PRICE_SCALE, as if it was published at 'ledger'
+    // Pass an old ledger to simulate a stale quote
+    pub fn set_price(e: Env, asset: Address, price: i128, ledger: u32) {
+        e.storage()
+            .instance()
+            .set(&DataKey::Price(asset), &PriceData { price, ledger });
+    }
+}
+
+#[contractimpl]
+impl Oracle for MockOracle {
+    fn lastprice(e: Env, asset: Address) -> PriceData {
+        match e.storage().instance().get(&DataKey::Price(asset)) {
+            Some(price) => price,
+            None => panic!("no price for asset"),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+// Implements constellation_shared::receiver::TokenReceiver, accepting or rejecting transfers as set by the test
+use constellation_shared::receiver::TokenReceiver;
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Bytes, Env};
+
+#[derive(Clone)]
+#[contracttype]
+enum DataKey {
+    Rejecting,
+    // (from, amount, data) of the last accepted callback
+    LastTransfer,
+}
+
+#[contract]
+pub struct MockReceiver;
+
+#[contractimpl]
+impl MockReceiver {
+    // Callbacks trap while this is set
+    pub fn set_rejecting(e: Env, rejecting: bool) {
+        e.storage().instance().set(&DataKey::Rejecting, &rejecting);
+    }
+
+    pub fn last_transfer(e: Env) -> Option<(Address, i128, Bytes)> {
+        e.storage().instance().get(&DataKey::LastTransfer)
+    }
+}
+
+#[contractimpl]
+impl TokenReceiver for MockReceiver {
+    fn on_token_transfer(e: Env, from: Address, amount: i128, data: Bytes) {
+        if e.storage().instance().get(&DataKey::Rejecting).unwrap_or(false) {
+            panic!("transfer rejected")
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::LastTransfer, &(from, amount, data));
+    }
+}
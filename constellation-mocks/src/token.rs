@@ -0,0 +1,189 @@
+// SEP-41 token whose behavior a test can change at any time
+// - decimals: any value, to exercise decimal mismatches
+// - fail_transfers: every transfer, transfer_from and burn traps, e.g. a frozen asset
+// - fee_bps: transfers deliver amount * (10000 - fee_bps) / 10000 and burn the rest, a fee-on-transfer token
+// - reenter: the next transfer first calls a contract function, a malicious token re-entering its caller
+// transfer_and_call() follows constellation_shared::receiver, always calling 'to', which must be a contract.
+// Anyone can mint. Balances and allowances live in instance storage and allowances never expire.
+use constellation_shared::receiver::TokenReceiverClient;
+use soroban_sdk::token::{self, Interface as _};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Bytes, Env, String, Symbol, Val, Vec};
+
+const MAX_FEE_BPS: u32 = 10_000;
+
+#[derive(Clone)]
+#[contracttype]
+enum DataKey {
+    Balance(Address),
+    Allowance(Address, Address),
+    Decimals,
+    Name,
+    Symbol,
+    FailTransfers,
+    FeeBps,
+    Reenter,
+}
+
+fn read_balance(e: &Env, id: &Address) -> i128 {
+    e.storage()
+        .instance()
+        .get(&DataKey::Balance(id.clone()))
+        .unwrap_or(0)
+}
+
+fn write_balance(e: &Env, id: &Address, amount: i128) {
+    e.storage()
+        .instance()
+        .set(&DataKey::Balance(id.clone()), &amount);
+}
+
+fn spend_balance(e: &Env, id: &Address, amount: i128) {
+    let balance = read_balance(e, id);
+    if balance < amount {
+        panic!("insufficient balance");
+    }
+    write_balance(e, id, balance - amount);
+}
+
+fn spend_allowance(e: &Env, from: &Address, spender: &Address, amount: i128) {
+    let key = DataKey::Allowance(from.clone(), spender.clone());
+    let allowance: i128 = e.storage().instance().get(&key).unwrap_or(0);
+    if allowance < amount {
+        panic!("insufficient allowance");
+    }
+    e.storage().instance().set(&key, &(allowance - amount));
+}
+
+fn check_transfers_enabled(e: &Env) {
+    if e.storage()
+        .instance()
+        .get(&DataKey::FailTransfers)
+        .unwrap_or(false)
+    {
+        panic!("transfers are disabled");
+    }
+}
+
+// Make the call set_reenter() queued, once
+fn reenter(e: &Env) {
+    let call: Option<(Address, Symbol, Vec<Val>)> = e.storage().instance().get(&DataKey::Reenter);
+    if let Some((contract, function, args)) = call {
+        e.storage().instance().remove(&DataKey::Reenter);
+        e.invoke_contract::<Val>(&contract, &function, args);
+    }
+}
+
+// Debit 'amount' from 'from' and credit 'to' with what is left after the fee
+fn move_balance(e: &Env, from: &Address, to: &Address, amount: i128) {
+    check_transfers_enabled(e);
+    reenter(e);
+    let fee_bps: u32 = e.storage().instance().get(&DataKey::FeeBps).unwrap_or(0);
+    let received = amount * (MAX_FEE_BPS - fee_bps) as i128 / MAX_FEE_BPS as i128;
+    spend_balance(e, from, amount);
+    write_balance(e, to, read_balance(e, to) + received);
+}
+
+#[contract]
+pub struct MockToken;
+
+#[contractimpl]
+impl MockToken {
+    pub fn initialize(e: Env, decimal: u32, name: String, symbol: String) {
+        e.storage().instance().set(&DataKey::Decimals, &decimal);
+        e.storage().instance().set(&DataKey::Name, &name);
+        e.storage().instance().set(&DataKey::Symbol, &symbol);
+    }
+
+    pub fn mint(e: Env, to: Address, amount: i128) {
+        write_balance(&e, &to, read_balance(&e, &to) + amount);
+    }
+
+    pub fn set_decimals(e: Env, decimal: u32) {
+        e.storage().instance().set(&DataKey::Decimals, &decimal);
+    }
+
+    pub fn set_fail_transfers(e: Env, fail: bool) {
+        e.storage().instance().set(&DataKey::FailTransfers, &fail);
+    }
+
+    pub fn set_fee_bps(e: Env, fee_bps: u32) {
+        if fee_bps > MAX_FEE_BPS {
+            panic!("fee is more than 100%");
+        }
+        e.storage().instance().set(&DataKey::FeeBps, &fee_bps);
+    }
+
+    pub fn transfer_and_call(e: Env, from: Address, to: Address, amount: i128, data: Bytes) {
+        from.require_auth();
+        move_balance(&e, &from, &to, amount);
+        TokenReceiverClient::new(&e, &to).on_token_transfer(&from, &amount, &data);
+    }
+
+    // Call 'function' on 'contract' with 'args' at the start of the next transfer or transfer_from
+    pub fn set_reenter(e: Env, contract: Address, function: Symbol, args: Vec<Val>) {
+        e.storage()
+            .instance()
+            .set(&DataKey::Reenter, &(contract, function, args));
+    }
+}
+
+#[contractimpl]
+impl token::Interface for MockToken {
+    fn allowance(e: Env, from: Address, spender: Address) -> i128 {
+        e.storage()
+            .instance()
+            .get(&DataKey::Allowance(from, spender))
+            .unwrap_or(0)
+    }
+
+    fn approve(e: Env, from: Address, spender: Address, amount: i128, _expiration_ledger: u32) {
+        from.require_auth();
+        e.storage()
+            .instance()
+            .set(&DataKey::Allowance(from, spender), &amount);
+    }
+
+    fn balance(e: Env, id: Address) -> i128 {
+        read_balance(&e, &id)
+    }
+
+    fn spendable_balance(e: Env, id: Address) -> i128 {
+        read_balance(&e, &id)
+    }
+
+    fn transfer(e: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        move_balance(&e, &from, &to, amount);
+    }
+
+    fn transfer_from(e: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+        spend_allowance(&e, &from, &spender, amount);
+        move_balance(&e, &from, &to, amount);
+    }
+
+    fn burn(e: Env, from: Address, amount: i128) {
+        from.require_auth();
+        check_transfers_enabled(&e);
+        spend_balance(&e, &from, amount);
+    }
+
+    fn burn_from(e: Env, spender: Address, from: Address, amount: i128) {
+        spender.require_auth();
+        check_transfers_enabled(&e);
+        spend_allowance(&e, &from, &spender, amount);
+        spend_balance(&e, &from, amount);
+    }
+
+    fn decimals(e: Env) -> u32 {
+        e.storage().instance().get(&DataKey::Decimals).unwrap_or(7)
+    }
+
+    fn name(e: Env) -> String {
+        e.storage().instance().get(&DataKey::Name).unwrap()
+    }
+
+    fn symbol(e: Env) -> String {
+        e.storage().instance().get(&DataKey::Symbol).unwrap()
+    }
+}
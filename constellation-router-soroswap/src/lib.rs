@@ -0,0 +1,126 @@
+// SwapRouter adapter for Soroswap.
+// Swaps between a component and the intermediate token go through their pair directly; swaps between two
+// components hop through the intermediate token. The adapter holds the input for the duration of the swap,
+// so Soroswap sees it as the trader and 'from' only has to authorize a single transfer to the adapter.
+
+#![no_std]
+
+mod soroswap;
+mod test;
+
+use crate::soroswap::SoroswapRouterClient;
+use constellation_shared::router::SwapRouter;
+use soroban_sdk::auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, token, vec, Address, Env, IntoVal, Symbol, Vec,
+};
+
+pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
+pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
+pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+#[derive(Clone)]
+#[contracttype]
+enum DataKey {
+    Router,
+    Intermediate,
+}
+
+fn read_router(e: &Env) -> Address {
+    e.storage().instance().get(&DataKey::Router).unwrap()
+}
+
+fn read_intermediate(e: &Env) -> Address {
+    e.storage().instance().get(&DataKey::Intermediate).unwrap()
+}
+
+// [token_in, token_out] when either side is the intermediate token, otherwise a hop through it
+fn build_path(e: &Env, token_in: Address, token_out: Address) -> Vec<Address> {
+    let intermediate = read_intermediate(e);
+    if token_in == intermediate || token_out == intermediate {
+        vec![e, token_in, token_out]
+    } else {
+        vec![e, token_in, intermediate, token_out]
+    }
+}
+
+#[contract]
+pub struct SoroswapAdapter;
+
+#[contractimpl]
+impl SoroswapAdapter {
+    pub fn initialize(e: Env, router: Address, intermediate_token: Address) {
+        if e.storage().instance().has(&DataKey::Router) {
+            panic!("already initialized")
+        }
+        e.storage().instance().set(&DataKey::Router, &router);
+        e.storage()
+            .instance()
+            .set(&DataKey::Intermediate, &intermediate_token);
+    }
+
+    pub fn router(e: Env) -> Address {
+        read_router(&e)
+    }
+
+    pub fn intermediate_token(e: Env) -> Address {
+        read_intermediate(&e)
+    }
+}
+
+#[contractimpl]
+impl SwapRouter for SoroswapAdapter {
+    #[allow(clippy::too_many_arguments)]
+    fn swap_exact_in(
+        e: Env,
+        from: Address,
+        to: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_amount_out: i128,
+        deadline: u64,
+    ) -> i128 {
+        from.require_auth();
+        if amount_in <= 0 {
+            panic!("amount_in must be positive")
+        }
+
+        e.storage()
+            .instance()
+            .bump(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+
+        let adapter = e.current_contract_address();
+        let router = SoroswapRouterClient::new(&e, &read_router(&e));
+        let path = build_path(&e, token_in.clone(), token_out.clone());
+
+        token::Client::new(&e, &token_in).transfer(&from, &adapter, &amount_in);
+
+        // Soroswap moves the input from the adapter into the first pair, one call below this contract,
+        // so that transfer has to be authorized explicitly
+        let pair = router.router_pair_for(&path.get(0).unwrap(), &path.get(1).unwrap());
+        e.authorize_as_current_contract(vec![
+            &e,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token_in,
+                    fn_name: Symbol::new(&e, "transfer"),
+                    args: (adapter.clone(), pair, amount_in).into_val(&e),
+                },
+                sub_invocations: vec![&e],
+            }),
+        ]);
+
+        let amounts = router.swap_exact_tokens_for_tokens(
+            &amount_in,
+            &min_amount_out,
+            &path,
+            &adapter,
+            &deadline,
+        );
+        let amount_out = amounts.last().unwrap();
+
+        token::Client::new(&e, &token_out).transfer(&adapter, &to, &amount_out);
+        amount_out
+    }
+}
@@ -0,0 +1,19 @@
+// The subset of the Soroswap router ABI this adapter calls
+// https://github.com/soroswap/core/tree/main/contracts/router
+use soroban_sdk::{contractclient, Address, Env, Vec};
+
+#[contractclient(name = "SoroswapRouterClient")]
+pub trait SoroswapRouter {
+    // Swaps along 'path', taking amount_in of path[0] from 'to' and delivering the output of the last hop to 'to'
+    // Returns the amount at every hop; the last one is the amount received
+    fn swap_exact_tokens_for_tokens(
+        e: Env,
+        amount_in: i128,
+        amount_out_min: i128,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<i128>;
+
+    fn router_pair_for(e: Env, token_a: Address, token_b: Address) -> Address;
+}
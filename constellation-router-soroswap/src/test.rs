@@ -0,0 +1,252 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::soroswap::SoroswapRouter;
+use crate::{SoroswapAdapter, SoroswapAdapterClient};
+use constellation_mocks::{MockToken, MockTokenClient};
+use soroban_sdk::testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, token, vec, Address, Env, IntoVal, String, Vec,
+};
+
+// Every hop of the mock router pays out this many times its input
+const RATE: i128 = 2;
+
+#[derive(Clone)]
+#[contracttype]
+enum DataKey {
+    Pair,
+    LastPath,
+}
+
+// Stands in for the Soroswap router with the same function signatures. The input goes to a single pair
+// reported for every token pair, and the output of the last hop is minted to 'to'.
+#[contract]
+pub struct MockSoroswapRouter;
+
+#[contractimpl]
+impl MockSoroswapRouter {
+    pub fn set_pair(e: Env, pair: Address) {
+        e.storage().instance().set(&DataKey::Pair, &pair);
+    }
+
+    pub fn last_path(e: Env) -> Vec<Address> {
+        e.storage().instance().get(&DataKey::LastPath).unwrap()
+    }
+}
+
+#[contractimpl]
+impl SoroswapRouter for MockSoroswapRouter {
+    fn swap_exact_tokens_for_tokens(
+        e: Env,
+        amount_in: i128,
+        amount_out_min: i128,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<i128> {
+        if e.ledger().timestamp() > deadline {
+            panic!("deadline expired")
+        }
+        let pair = Self::router_pair_for(e.clone(), path.get(0).unwrap(), path.get(1).unwrap());
+        token::Client::new(&e, &path.get(0).unwrap()).transfer(&to, &pair, &amount_in);
+
+        let mut amounts = vec![&e, amount_in];
+        for _ in 1..path.len() {
+            amounts.push_back(amounts.last().unwrap() * RATE);
+        }
+        let amount_out = amounts.last().unwrap();
+        if amount_out < amount_out_min {
+            panic!("insufficient output amount")
+        }
+        MockTokenClient::new(&e, &path.last().unwrap()).mint(&to, &amount_out);
+        e.storage().instance().set(&DataKey::LastPath, &path);
+        amounts
+    }
+
+    fn router_pair_for(e: Env, _token_a: Address, _token_b: Address) -> Address {
+        e.storage().instance().get(&DataKey::Pair).unwrap()
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    adapter: SoroswapAdapterClient<'a>,
+    router: MockSoroswapRouterClient<'a>,
+    pair: Address,
+    intermediate: MockTokenClient<'a>,
+    components: [MockTokenClient<'a>; 2],
+}
+
+fn create_token<'a>(e: &Env) -> MockTokenClient<'a> {
+    let token = MockTokenClient::new(e, &e.register_contract(None, MockToken));
+    token.initialize(
+        &7,
+        &String::from_str(e, "Token"),
+        &String::from_str(e, "TKN"),
+    );
+    token
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+
+    let router =
+        MockSoroswapRouterClient::new(&env, &env.register_contract(None, MockSoroswapRouter));
+    let pair = Address::random(&env);
+    router.set_pair(&pair);
+    let intermediate = create_token(&env);
+    let adapter = SoroswapAdapterClient::new(&env, &env.register_contract(None, SoroswapAdapter));
+    adapter.initialize(&router.address, &intermediate.address);
+    let components = [create_token(&env), create_token(&env)];
+    Setup {
+        env,
+        adapter,
+        router,
+        pair,
+        intermediate,
+        components,
+    }
+}
+
+#[test]
+fn test_initialize() {
+    let s = setup();
+    assert_eq!(s.adapter.router(), s.router.address);
+    assert_eq!(s.adapter.intermediate_token(), s.intermediate.address);
+    assert!(s
+        .adapter
+        .try_initialize(&s.router.address, &s.components[0].address)
+        .is_err());
+}
+
+#[test]
+fn test_swap_component_for_intermediate() {
+    let s = setup();
+    s.env.mock_all_auths();
+    let (from, to) = (Address::random(&s.env), Address::random(&s.env));
+    let component = &s.components[0];
+    component.mint(&from, &100);
+
+    let out = s.adapter.swap_exact_in(
+        &from,
+        &to,
+        &component.address,
+        &s.intermediate.address,
+        &100,
+        &200,
+        &1000,
+    );
+    assert_eq!(out, 200);
+    assert_eq!(
+        s.router.last_path(),
+        vec![
+            &s.env,
+            component.address.clone(),
+            s.intermediate.address.clone()
+        ]
+    );
+    assert_eq!(component.balance(&from), 0);
+    assert_eq!(component.balance(&s.pair), 100);
+    assert_eq!(s.intermediate.balance(&to), 200);
+    // Nothing is left with the adapter
+    assert_eq!(component.balance(&s.adapter.address), 0);
+    assert_eq!(s.intermediate.balance(&s.adapter.address), 0);
+
+    // And back
+    let out = s.adapter.swap_exact_in(
+        &to,
+        &from,
+        &s.intermediate.address,
+        &component.address,
+        &200,
+        &0,
+        &1000,
+    );
+    assert_eq!(out, 400);
+    assert_eq!(
+        s.router.last_path(),
+        vec![
+            &s.env,
+            s.intermediate.address.clone(),
+            component.address.clone()
+        ]
+    );
+    assert_eq!(component.balance(&from), 400);
+}
+
+#[test]
+fn test_swap_between_components_hops_through_intermediate() {
+    let s = setup();
+    let from = Address::random(&s.env);
+    let (c0, c1) = (&s.components[0], &s.components[1]);
+    c0.mint(&from, &100);
+
+    // 'from' signs the swap and the transfer into the adapter; the adapter authorizes the pair transfer itself
+    let args = (
+        &from,
+        &from,
+        &c0.address,
+        &c1.address,
+        100_i128,
+        400_i128,
+        1000_u64,
+    );
+    s.env.mock_auths(&[MockAuth {
+        address: &from,
+        invoke: &MockAuthInvoke {
+            contract: &s.adapter.address,
+            fn_name: "swap_exact_in",
+            args: args.into_val(&s.env),
+            sub_invokes: &[MockAuthInvoke {
+                contract: &c0.address,
+                fn_name: "transfer",
+                args: (&from, &s.adapter.address, 100_i128).into_val(&s.env),
+                sub_invokes: &[],
+            }],
+        },
+    }]);
+    let out = s
+        .adapter
+        .swap_exact_in(&from, &from, &c0.address, &c1.address, &100, &400, &1000);
+    assert_eq!(out, 400);
+    assert_eq!(
+        s.router.last_path(),
+        vec![
+            &s.env,
+            c0.address.clone(),
+            s.intermediate.address.clone(),
+            c1.address.clone()
+        ]
+    );
+    assert_eq!(c0.balance(&from), 0);
+    assert_eq!(c1.balance(&from), 400);
+    assert_eq!(s.intermediate.balance(&s.adapter.address), 0);
+}
+
+#[test]
+fn test_swap_traps() {
+    let s = setup();
+    s.env.mock_all_auths();
+    let from = Address::random(&s.env);
+    let (c0, c1) = (&s.components[0], &s.components[1]);
+    c0.mint(&from, &100);
+
+    // Slippage: the hop pays 400
+    assert!(s
+        .adapter
+        .try_swap_exact_in(&from, &from, &c0.address, &c1.address, &100, &401, &1000)
+        .is_err());
+    // Deadline already passed
+    assert!(s
+        .adapter
+        .try_swap_exact_in(&from, &from, &c0.address, &c1.address, &100, &0, &999)
+        .is_err());
+    assert!(s
+        .adapter
+        .try_swap_exact_in(&from, &from, &c0.address, &c1.address, &0, &0, &1000)
+        .is_err());
+    assert_eq!(c0.balance(&from), 100);
+    assert_eq!(c1.balance(&from), 0);
+}
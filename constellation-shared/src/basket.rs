@@ -0,0 +1,27 @@
+// Helpers over basket compositions, (component, units) pairs as returned by get_basket().
+use soroban_sdk::{Address, Vec};
+
+// True if 'a' and 'b' hold the same components with the same units, in any order.
+// Baskets are small, so the quadratic scan is cheaper than sorting in the host.
+pub fn compositions_equal(a: Vec<(Address, u32)>, b: Vec<(Address, u32)>) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    // Counting occurrences keeps the comparison exact if a basket repeats an entry
+    for entry in a.iter() {
+        if count(&a, &entry) != count(&b, &entry) {
+            return false;
+        }
+    }
+    true
+}
+
+fn count(composition: &Vec<(Address, u32)>, entry: &(Address, u32)) -> u32 {
+    let mut n = 0;
+    for other in composition.iter() {
+        if other == *entry {
+            n += 1;
+        }
+    }
+    n
+}
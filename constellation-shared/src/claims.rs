@@ -0,0 +1,9 @@
+// Read-only view the Constellation Token needs from its MinterBurner: components held for users
+// whose redemption was queued, which must not be mistaken for surplus.
+use soroban_sdk::{contractclient, Address, Env};
+
+#[contractclient(name = "ClaimsClient")]
+pub trait Claims {
+    // Total of 'component' that 'ctoken' holds for pending claims
+    fn queued_claims(env: Env, ctoken: Address, component: Address) -> i128;
+}
@@ -0,0 +1,500 @@
+// Every Constellation-specific event is emitted through this module so indexers have one schema to follow.
+// Topics are (area, action, ...addresses); payloads are contracttype structs carrying EVENT_VERSION,
+// which is bumped whenever a payload changes shape, and 'seq', numbering every event the contract publishes
+// from 1 without gaps, so consumers can detect missed events.
+// Standard token events (mint, burn, transfer, approve, set_admin) keep the soroban-token-sdk format.
+use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol, Vec};
+
+pub const EVENT_VERSION: u32 = 4;
+
+// Instance storage key of the event sequence; a Symbol can't collide with a contracttype DataKey
+const EVENT_SEQ: Symbol = symbol_short!("event_seq");
+
+pub const ISSUANCE: Symbol = symbol_short!("issuance");
+pub const ESCROW: Symbol = symbol_short!("escrow");
+pub const ALLOWANCE: Symbol = symbol_short!("allowance");
+pub const REBALANCE: Symbol = symbol_short!("rebalance");
+pub const ERROR: Symbol = symbol_short!("error");
+pub const FEE: Symbol = symbol_short!("fee");
+pub const METADATA: Symbol = symbol_short!("metadata");
+pub const SOLVENCY: Symbol = symbol_short!("solvency");
+
+pub const ISSUE: Symbol = symbol_short!("issue");
+pub const REDEEM: Symbol = symbol_short!("redeem");
+pub const REFUND: Symbol = symbol_short!("refund");
+pub const QUEUED: Symbol = symbol_short!("queued");
+pub const CLAIMED: Symbol = symbol_short!("claimed");
+pub const SPENT: Symbol = symbol_short!("spent");
+pub const START: Symbol = symbol_short!("start");
+pub const PROGRESS: Symbol = symbol_short!("progress");
+pub const MIN_PRICE: Symbol = symbol_short!("min_price");
+pub const FINALIZE: Symbol = symbol_short!("finalize");
+pub const CANCEL: Symbol = symbol_short!("cancel");
+pub const PERFORMANCE: Symbol = symbol_short!("perf");
+pub const UNIT: Symbol = symbol_short!("unit");
+pub const DUST: Symbol = symbol_short!("dust");
+pub const RECONCILE: Symbol = symbol_short!("reconcile");
+pub const REPRICED: Symbol = symbol_short!("repriced");
+pub const UPDATED: Symbol = symbol_short!("updated");
+pub const EPSILON: Symbol = symbol_short!("epsilon");
+pub const WIND_DOWN: Symbol = symbol_short!("wind_down");
+
+// Sequence number of the last event this contract published, 0 before the first
+pub fn read_event_seq(e: &Env) -> u64 {
+    e.storage().instance().get(&EVENT_SEQ).unwrap_or(0)
+}
+
+fn next_event_seq(e: &Env) -> u64 {
+    let seq = read_event_seq(e) + 1;
+    e.storage().instance().set(&EVENT_SEQ, &seq);
+    seq
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct IssueEvent {
+    pub version: u32,
+    pub seq: u64,
+    pub to: Address,
+    pub amount: i128,
+    pub via_allowance: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RedeemEvent {
+    pub version: u32,
+    pub seq: u64,
+    // Recipient of the released components, 'from' unless redeemed with burn_to
+    pub to: Address,
+    pub amount: i128,
+    // Components released to 'to' and the amount of each, in the same order
+    pub components: Vec<Address>,
+    pub released: Vec<i128>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AmountEvent {
+    pub version: u32,
+    pub seq: u64,
+    pub amount: i128,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RebalanceStartEvent {
+    pub version: u32,
+    pub seq: u64,
+    pub target_amount: u32,
+    pub start_price: i128,
+    pub min_price: i128,
+    pub duration_ledgers: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RebalanceProgressEvent {
+    pub version: u32,
+    pub seq: u64,
+    pub filled: i128,
+    pub remaining: i128,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RebalanceEndEvent {
+    pub version: u32,
+    pub seq: u64,
+    pub components: Vec<Address>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct UnitUpdatedEvent {
+    pub version: u32,
+    pub seq: u64,
+    pub old_unit: u32,
+    pub new_unit: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct MetadataUpdatedEvent {
+    pub version: u32,
+    pub seq: u64,
+    pub old_name: String,
+    pub old_symbol: String,
+    pub new_name: String,
+    pub new_symbol: String,
+}
+
+// Published just before trapping with the contract error 'code', so support can see what was missing
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ErrorEvent {
+    pub version: u32,
+    pub seq: u64,
+    pub component: Address,
+    pub shortfall: i128,
+}
+
+// Published before trapping with an allowance error, with what 'spender' could still spend
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AllowanceErrorEvent {
+    pub version: u32,
+    pub seq: u64,
+    pub from: Address,
+    pub spender: Address,
+    pub requested: i128,
+    pub available: i128,
+}
+
+// Published before trapping with a component error, naming the component and its getComponents() index
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ComponentErrorEvent {
+    pub version: u32,
+    pub seq: u64,
+    pub component: Address,
+    pub index: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct WindDownEvent {
+    pub version: u32,
+    pub seq: u64,
+    // Ledger from which issuance stops
+    pub effective_ledger: u32,
+}
+
+// ("issuance", "issue", ctoken, from)
+pub fn emit_issue(
+    e: &Env,
+    ctoken: Address,
+    from: Address,
+    to: Address,
+    amount: i128,
+    via_allowance: bool,
+) {
+    e.events().publish(
+        (ISSUANCE, ISSUE, ctoken, from),
+        IssueEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            to,
+            amount,
+            via_allowance,
+        },
+    );
+}
+
+// ("issuance", "redeem", ctoken, from)
+pub fn emit_redeem(
+    e: &Env,
+    ctoken: Address,
+    from: Address,
+    to: Address,
+    amount: i128,
+    components: Vec<Address>,
+    released: Vec<i128>,
+) {
+    e.events().publish(
+        (ISSUANCE, REDEEM, ctoken, from),
+        RedeemEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            to,
+            amount,
+            components,
+            released,
+        },
+    );
+}
+
+// ("issuance", "queued", owner, component)
+pub fn emit_claim_queued(e: &Env, owner: Address, component: Address, amount: i128) {
+    e.events().publish(
+        (ISSUANCE, QUEUED, owner, component),
+        AmountEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            amount,
+        },
+    );
+}
+
+// ("issuance", "claimed", owner, component)
+pub fn emit_claimed(e: &Env, owner: Address, component: Address, amount: i128) {
+    e.events().publish(
+        (ISSUANCE, CLAIMED, owner, component),
+        AmountEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            amount,
+        },
+    );
+}
+
+// ("escrow", "refund", owner, component)
+pub fn emit_escrow_refund(e: &Env, owner: Address, component: Address, amount: i128) {
+    e.events().publish(
+        (ESCROW, REFUND, owner, component),
+        AmountEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            amount,
+        },
+    );
+}
+
+// ("fee", "perf", recipient), amount is the ctokens minted to the fee recipient
+pub fn emit_performance_fee(e: &Env, recipient: Address, amount: i128) {
+    e.events().publish(
+        (FEE, PERFORMANCE, recipient),
+        AmountEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            amount,
+        },
+    );
+}
+
+// ("fee", "dust", component, recipient)
+pub fn emit_dust_collected(e: &Env, component: Address, recipient: Address, amount: i128) {
+    e.events().publish(
+        (FEE, DUST, component, recipient),
+        AmountEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            amount,
+        },
+    );
+}
+
+// ("solvency", "epsilon"), amount is the new backing epsilon
+pub fn emit_backing_epsilon(e: &Env, epsilon: i128) {
+    e.events().publish(
+        (SOLVENCY, EPSILON),
+        AmountEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            amount: epsilon,
+        },
+    );
+}
+
+// ("issuance", "wind_down"), published when the wind-down is initiated rather than when it takes effect
+pub fn emit_wind_down(e: &Env, effective_ledger: u32) {
+    e.events().publish(
+        (ISSUANCE, WIND_DOWN),
+        WindDownEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            effective_ledger,
+        },
+    );
+}
+
+// ("allowance", "spent", from, spender), amount is the allowance remaining
+pub fn emit_allowance_spent(e: &Env, from: Address, spender: Address, amount: i128) {
+    e.events().publish(
+        (ALLOWANCE, SPENT, from, spender),
+        AmountEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            amount,
+        },
+    );
+}
+
+// ("rebalance", "start", component)
+pub fn emit_rebalance_start(
+    e: &Env,
+    component: Address,
+    target_amount: u32,
+    start_price: i128,
+    min_price: i128,
+    duration_ledgers: u32,
+) {
+    e.events().publish(
+        (REBALANCE, START, component),
+        RebalanceStartEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            target_amount,
+            start_price,
+            min_price,
+            duration_ledgers,
+        },
+    );
+}
+
+// ("rebalance", "repriced", component), the auction restarts its price curve from the current ledger
+pub fn emit_auction_repriced(
+    e: &Env,
+    component: Address,
+    target_amount: u32,
+    start_price: i128,
+    min_price: i128,
+    duration_ledgers: u32,
+) {
+    e.events().publish(
+        (REBALANCE, REPRICED, component),
+        RebalanceStartEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            target_amount,
+            start_price,
+            min_price,
+            duration_ledgers,
+        },
+    );
+}
+
+// ("rebalance", "progress", component)
+pub fn emit_rebalance_progress(e: &Env, component: Address, filled: i128, remaining: i128) {
+    e.events().publish(
+        (REBALANCE, PROGRESS, component),
+        RebalanceProgressEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            filled,
+            remaining,
+        },
+    );
+}
+
+// ("rebalance", "min_price", component), amount is the auction's min_price
+pub fn emit_auction_min_price(e: &Env, component: Address, min_price: i128) {
+    e.events().publish(
+        (REBALANCE, MIN_PRICE, component),
+        AmountEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            amount: min_price,
+        },
+    );
+}
+
+// ("rebalance", "unit", component), a manual change without an auction
+pub fn emit_unit_updated(e: &Env, component: Address, old_unit: u32, new_unit: u32) {
+    e.events().publish(
+        (REBALANCE, UNIT, component),
+        UnitUpdatedEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            old_unit,
+            new_unit,
+        },
+    );
+}
+
+// ("rebalance", "reconcile", component), units trued up to the held reserve
+pub fn emit_reconcile(e: &Env, component: Address, old_unit: u32, new_unit: u32) {
+    e.events().publish(
+        (REBALANCE, RECONCILE, component),
+        UnitUpdatedEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            old_unit,
+            new_unit,
+        },
+    );
+}
+
+// ("metadata", "updated"), decimals never change
+pub fn emit_metadata_updated(
+    e: &Env,
+    old_name: String,
+    old_symbol: String,
+    new_name: String,
+    new_symbol: String,
+) {
+    e.events().publish(
+        (METADATA, UPDATED),
+        MetadataUpdatedEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            old_name,
+            old_symbol,
+            new_name,
+            new_symbol,
+        },
+    );
+}
+
+// ("rebalance", "finalize")
+pub fn emit_rebalance_finalize(e: &Env, components: Vec<Address>) {
+    e.events().publish(
+        (REBALANCE, FINALIZE),
+        RebalanceEndEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            components,
+        },
+    );
+}
+
+// ("rebalance", "cancel")
+pub fn emit_rebalance_cancel(e: &Env, components: Vec<Address>) {
+    e.events().publish(
+        (REBALANCE, CANCEL),
+        RebalanceEndEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            components,
+        },
+    );
+}
+
+// ("error", code)
+// Some configurations drop events from failed transactions, so contracts also offer read-only diagnostics
+pub fn emit_error(e: &Env, code: u32, component: Address, shortfall: i128) {
+    e.events().publish(
+        (ERROR, code),
+        ErrorEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            component,
+            shortfall,
+        },
+    );
+}
+
+// ("error", code), for a spend above the expiry-adjusted allowance
+pub fn emit_allowance_error(
+    e: &Env,
+    code: u32,
+    from: Address,
+    spender: Address,
+    requested: i128,
+    available: i128,
+) {
+    e.events().publish(
+        (ERROR, code),
+        AllowanceErrorEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            from,
+            spender,
+            requested,
+            available,
+        },
+    );
+}
+
+// ("error", code), for a component that blocks the operation, e.g. one whose issuance is paused
+pub fn emit_component_error(e: &Env, code: u32, component: Address, index: u32) {
+    e.events().publish(
+        (ERROR, code),
+        ComponentErrorEvent {
+            version: EVENT_VERSION,
+            seq: next_event_seq(e),
+            component,
+            index,
+        },
+    );
+}
@@ -0,0 +1,9 @@
+// Interface a compliance contract implements to restrict transfers of a Constellation Token.
+// The token calls it on every transfer and transfer_from once the manager has set a hook.
+use soroban_sdk::{contractclient, Address, Env};
+
+#[contractclient(name = "TransferHookClient")]
+pub trait TransferHook {
+    // False rejects the transfer
+    fn check_transfer(env: Env, from: Address, to: Address, amount: i128) -> bool;
+}
@@ -0,0 +1,11 @@
+#![no_std]
+
+pub mod basket;
+pub mod claims;
+pub mod events;
+pub mod hook;
+pub mod oracle;
+pub mod receiver;
+pub mod router;
+pub mod storage;
+mod test;
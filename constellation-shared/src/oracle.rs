@@ -0,0 +1,19 @@
+// Price oracle interface used by the Constellation contracts.
+// Prices are quote asset per token, scaled by PRICE_SCALE, the same scale auction prices use.
+use soroban_sdk::{contractclient, contracttype, Address, Env};
+
+pub const PRICE_SCALE: i128 = 10_000_000;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PriceData {
+    pub price: i128,
+    // Ledger sequence the price was published at, used to reject stale quotes
+    pub ledger: u32,
+}
+
+#[contractclient(name = "OracleClient")]
+pub trait Oracle {
+    // Latest price of 'asset'
+    fn lastprice(env: Env, asset: Address) -> PriceData;
+}
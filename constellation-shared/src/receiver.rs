@@ -0,0 +1,13 @@
+// Callback a contract implements to accept Constellation Tokens through transfer_and_call(), so a deposit
+// takes one call instead of approve followed by the protocol's own deposit.
+// The token calls it on the recipient after the tokens have moved; trapping reverts the whole transfer.
+// The callback doesn't say which token sent it. A receiver can require_auth() the token it expects, which
+// succeeds only when that token contract is the direct invoker, or check its balance of each token rather
+// than trust 'amount'.
+use soroban_sdk::{contractclient, Address, Bytes, Env};
+
+#[contractclient(name = "TokenReceiverClient")]
+pub trait TokenReceiver {
+    // 'data' is passed through unchanged from transfer_and_call()
+    fn on_token_transfer(env: Env, from: Address, amount: i128, data: Bytes);
+}
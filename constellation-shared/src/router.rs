@@ -0,0 +1,21 @@
+// Swap interface the Constellation contracts use to trade components against an intermediate token.
+// Each DEX gets its own adapter contract implementing SwapRouter, e.g. constellation-router-soroswap.
+use soroban_sdk::{contractclient, Address, Env};
+
+#[contractclient(name = "SwapRouterClient")]
+pub trait SwapRouter {
+    // Swap exactly 'amount_in' of 'token_in' taken from 'from' for at least 'min_amount_out' of 'token_out',
+    // delivered to 'to'. Traps if the swap can't complete by ledger timestamp 'deadline'.
+    // Returns the amount of 'token_out' delivered.
+    #[allow(clippy::too_many_arguments)]
+    fn swap_exact_in(
+        env: Env,
+        from: Address,
+        to: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_amount_out: i128,
+        deadline: u64,
+    ) -> i128;
+}
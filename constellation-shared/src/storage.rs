@@ -0,0 +1,104 @@
+// Storage keys of the Constellation contracts, kept in one place so auditors can reconstruct the state of
+// a deployed instance. A contracttype enum is stored by variant name and fields, so those names are the
+// stable discriminants: a variant must never be renamed or have its fields changed, or the entries
+// already stored under it are orphaned. New variants are only ever added.
+// STORAGE_VERSION is bumped whenever a key or the shape of a stored value changes; each contract reports
+// it in dump_config(). Besides these keys, constellation_shared::events stores its sequence under the
+// Symbol "event_seq" in each contract's instance storage.
+use soroban_sdk::{contracttype, Address};
+
+// 2: SnapshotSupply added, BalanceCheckpoint holds a list of per-snapshot-id checkpoints
+pub const STORAGE_VERSION: u32 = 2;
+
+// Constellation Token
+
+#[derive(Clone)]
+#[contracttype]
+pub struct AllowanceDataKey {
+    pub from: Address,
+    pub spender: Address,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum TokenDataKey {
+    Allowance(AllowanceDataKey),
+    Balance(Address),
+    Nonce(Address),
+    State(Address),
+    Admin,
+    Manager,
+    Operator,
+    TtlConfig,
+    Oracle,
+    PerformanceFee,
+    HighWaterMark,
+    Components,
+    Amounts,
+    Paused(Address),
+    TotalSupply,
+    MaxSupply,
+    MintHalted,
+    Rebalance,
+    RebalanceSnapshot,
+    RebalanceProposal,
+    RebalanceEpoch,
+    ExecutionReport(u32),
+    DustEpsilon,
+    BackingEpsilon,
+    TransferHook,
+    Denylist,
+    MintDisabled,
+    BurnDisabled,
+    Intermediates,
+    LastPrice(Address),
+    Auction(Address),
+    Snapshot,
+    BalanceCheckpoint(Address),
+    WindDown,
+    UniformDecimals,
+    SnapshotSupply(u32),
+    FeeRecipient,
+}
+
+// Constellation MinterBurner
+
+#[derive(Clone)]
+#[contracttype]
+pub struct EscrowDataKey {
+    pub owner: Address,
+    pub component: Address,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct ClaimDataKey {
+    pub owner: Address,
+    pub ctoken: Address,
+    pub component: Address,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum MinterBurnerDataKey {
+    Escrow(EscrowDataKey),
+    Issuance(Address),
+    IssuanceCap(Address),
+    Admin,
+    Locked,
+    Paused,
+    AllowRedeemWhilePaused,
+    PendingClaim(ClaimDataKey),
+    Ctoken(Address),
+    Bootstrap(Address),
+    // (user, ctoken)
+    UserStats(Address, Address),
+    RateLimit(Address),
+    RateWindow(Address),
+    // (ctoken, component)
+    QueuedClaims(Address, Address),
+    Session(u32),
+    SessionCounter,
+    SessionLedgers,
+    TtlConfig,
+}
@@ -0,0 +1,179 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::basket::compositions_equal;
+use crate::storage::{
+    AllowanceDataKey, ClaimDataKey, EscrowDataKey, MinterBurnerDataKey, TokenDataKey,
+    STORAGE_VERSION,
+};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{vec, Address, Env, IntoVal, Symbol, TryFromVal, Val, Vec};
+
+#[test]
+fn test_compositions_equal() {
+    let env = Env::default();
+    let (a, b, c) = (
+        Address::random(&env),
+        Address::random(&env),
+        Address::random(&env),
+    );
+    let basket = vec![&env, (a.clone(), 1), (b.clone(), 2)];
+
+    assert!(compositions_equal(basket.clone(), basket.clone()));
+    assert!(compositions_equal(
+        basket.clone(),
+        vec![&env, (b.clone(), 2), (a.clone(), 1)]
+    ));
+    assert!(compositions_equal(vec![&env], vec![&env]));
+
+    // Differing units
+    assert!(!compositions_equal(
+        basket.clone(),
+        vec![&env, (a.clone(), 1), (b.clone(), 3)]
+    ));
+    // Differing members
+    assert!(!compositions_equal(
+        basket.clone(),
+        vec![&env, (a.clone(), 1), (c.clone(), 2)]
+    ));
+    assert!(!compositions_equal(
+        basket.clone(),
+        vec![&env, (a.clone(), 1)]
+    ));
+    // A repeated entry doesn't stand in for a missing one
+    assert!(!compositions_equal(
+        vec![&env, (a.clone(), 1), (a.clone(), 1), (b.clone(), 2)],
+        vec![&env, (a.clone(), 1), (b.clone(), 2), (b.clone(), 2)]
+    ));
+}
+
+// How a key is stored: a contracttype enum is a Vec of the variant name and its fields
+fn stored<K: IntoVal<Env, Val>>(env: &Env, key: K) -> Vec<Val> {
+    key.into_val(env).into_val(env)
+}
+
+fn variant<K: IntoVal<Env, Val>>(env: &Env, key: K) -> Symbol {
+    stored(env, key).get(0).unwrap().into_val(env)
+}
+
+// Pins the storage layout: renaming a variant orphans every entry stored under it, see storage.rs
+#[test]
+fn test_storage_key_layout() {
+    let env = Env::default();
+    let a = Address::random(&env);
+    let token_keys = [
+        (
+            TokenDataKey::Allowance(AllowanceDataKey {
+                from: a.clone(),
+                spender: a.clone(),
+            }),
+            "Allowance",
+        ),
+        (TokenDataKey::Balance(a.clone()), "Balance"),
+        (TokenDataKey::Nonce(a.clone()), "Nonce"),
+        (TokenDataKey::State(a.clone()), "State"),
+        (TokenDataKey::Admin, "Admin"),
+        (TokenDataKey::Manager, "Manager"),
+        (TokenDataKey::Operator, "Operator"),
+        (TokenDataKey::TtlConfig, "TtlConfig"),
+        (TokenDataKey::Oracle, "Oracle"),
+        (TokenDataKey::PerformanceFee, "PerformanceFee"),
+        (TokenDataKey::HighWaterMark, "HighWaterMark"),
+        (TokenDataKey::Components, "Components"),
+        (TokenDataKey::Amounts, "Amounts"),
+        (TokenDataKey::Paused(a.clone()), "Paused"),
+        (TokenDataKey::TotalSupply, "TotalSupply"),
+        (TokenDataKey::MaxSupply, "MaxSupply"),
+        (TokenDataKey::MintHalted, "MintHalted"),
+        (TokenDataKey::Rebalance, "Rebalance"),
+        (TokenDataKey::RebalanceSnapshot, "RebalanceSnapshot"),
+        (TokenDataKey::RebalanceProposal, "RebalanceProposal"),
+        (TokenDataKey::RebalanceEpoch, "RebalanceEpoch"),
+        (TokenDataKey::ExecutionReport(1), "ExecutionReport"),
+        (TokenDataKey::DustEpsilon, "DustEpsilon"),
+        (TokenDataKey::BackingEpsilon, "BackingEpsilon"),
+        (TokenDataKey::TransferHook, "TransferHook"),
+        (TokenDataKey::Denylist, "Denylist"),
+        (TokenDataKey::MintDisabled, "MintDisabled"),
+        (TokenDataKey::BurnDisabled, "BurnDisabled"),
+        (TokenDataKey::Intermediates, "Intermediates"),
+        (TokenDataKey::LastPrice(a.clone()), "LastPrice"),
+        (TokenDataKey::Auction(a.clone()), "Auction"),
+        (TokenDataKey::Snapshot, "Snapshot"),
+        (
+            TokenDataKey::BalanceCheckpoint(a.clone()),
+            "BalanceCheckpoint",
+        ),
+        (TokenDataKey::WindDown, "WindDown"),
+        (TokenDataKey::UniformDecimals, "UniformDecimals"),
+        (TokenDataKey::SnapshotSupply(1), "SnapshotSupply"),
+        (TokenDataKey::FeeRecipient, "FeeRecipient"),
+    ];
+    for (key, name) in token_keys {
+        assert_eq!(variant(&env, key), Symbol::new(&env, name));
+    }
+
+    let minter_keys = [
+        (
+            MinterBurnerDataKey::Escrow(EscrowDataKey {
+                owner: a.clone(),
+                component: a.clone(),
+            }),
+            "Escrow",
+        ),
+        (MinterBurnerDataKey::Issuance(a.clone()), "Issuance"),
+        (MinterBurnerDataKey::IssuanceCap(a.clone()), "IssuanceCap"),
+        (MinterBurnerDataKey::Admin, "Admin"),
+        (MinterBurnerDataKey::Locked, "Locked"),
+        (MinterBurnerDataKey::Paused, "Paused"),
+        (
+            MinterBurnerDataKey::AllowRedeemWhilePaused,
+            "AllowRedeemWhilePaused",
+        ),
+        (
+            MinterBurnerDataKey::PendingClaim(ClaimDataKey {
+                owner: a.clone(),
+                ctoken: a.clone(),
+                component: a.clone(),
+            }),
+            "PendingClaim",
+        ),
+        (MinterBurnerDataKey::Ctoken(a.clone()), "Ctoken"),
+        (MinterBurnerDataKey::Bootstrap(a.clone()), "Bootstrap"),
+        (
+            MinterBurnerDataKey::UserStats(a.clone(), a.clone()),
+            "UserStats",
+        ),
+        (MinterBurnerDataKey::RateLimit(a.clone()), "RateLimit"),
+        (MinterBurnerDataKey::RateWindow(a.clone()), "RateWindow"),
+        (
+            MinterBurnerDataKey::QueuedClaims(a.clone(), a.clone()),
+            "QueuedClaims",
+        ),
+        (MinterBurnerDataKey::Session(1), "Session"),
+        (MinterBurnerDataKey::SessionCounter, "SessionCounter"),
+        (MinterBurnerDataKey::SessionLedgers, "SessionLedgers"),
+        (MinterBurnerDataKey::TtlConfig, "TtlConfig"),
+    ];
+    for (key, name) in minter_keys {
+        assert_eq!(variant(&env, key), Symbol::new(&env, name));
+    }
+
+    // Key fields are part of the layout too
+    assert_eq!(stored(&env, TokenDataKey::Balance(a.clone())).len(), 2);
+    assert_eq!(
+        stored(&env, MinterBurnerDataKey::UserStats(a.clone(), a.clone())).len(),
+        3
+    );
+    assert_eq!(
+        stored(&env, TokenDataKey::BalanceCheckpoint(a.clone())).len(),
+        2
+    );
+    let snapshot_supply = stored(&env, TokenDataKey::SnapshotSupply(7));
+    assert_eq!(snapshot_supply.len(), 2);
+    assert_eq!(
+        u32::try_from_val(&env, &snapshot_supply.get(1).unwrap()),
+        Ok(7)
+    );
+    assert_eq!(STORAGE_VERSION, 2);
+}
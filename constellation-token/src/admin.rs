@@ -1,6 +1,8 @@
-// Unchanged from "https://github.com/stellar/soroban-examples/tree/main/token/src"
-use soroban_sdk::{Address, Env};
+// Based on "https://github.com/stellar/soroban-examples/tree/main/token/src"
+// The admin is written last in initialize(), so its presence means the contract is fully initialized.
+use soroban_sdk::{panic_with_error, Address, Env};
 
+use crate::error::Error;
 use crate::storage_types::DataKey;
 
 pub fn has_administrator(e: &Env) -> bool {
@@ -8,6 +10,12 @@ pub fn has_administrator(e: &Env) -> bool {
     e.storage().instance().has(&key)
 }
 
+pub fn require_initialized(e: &Env) {
+    if !has_administrator(e) {
+        panic_with_error!(e, Error::NotInitialized);
+    }
+}
+
 pub fn read_administrator(e: &Env) -> Address {
     let key = DataKey::Admin;
     e.storage().instance().get(&key).unwrap()
@@ -1,6 +1,8 @@
-// Unchanged from https://github.com/stellar/soroban-examples/tree/main/token/src
+// Based on https://github.com/stellar/soroban-examples/tree/main/token/src
+use crate::error::Error;
 use crate::storage_types::{AllowanceDataKey, AllowanceValue, DataKey};
-use soroban_sdk::{Address, Env};
+use constellation_shared::events::{emit_allowance_error, emit_allowance_spent};
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
 
 pub fn read_allowance(e: &Env, from: Address, spender: Address) -> AllowanceValue {
     let key = DataKey::Allowance(AllowanceDataKey { from, spender });
@@ -21,6 +23,18 @@ pub fn read_allowance(e: &Env, from: Address, spender: Address) -> AllowanceValu
     }
 }
 
+// Expiry-adjusted allowance 'from' gave each of 'spenders', in the same order
+// Storage can't be enumerated, so the caller names the candidate spenders
+pub fn read_allowances(e: &Env, from: Address, spenders: Vec<Address>) -> Vec<i128> {
+    let mut amounts = Vec::new(e);
+    for spender in spenders.iter() {
+        amounts.push_back(read_allowance(e, from.clone(), spender).amount);
+    }
+    amounts
+}
+
+// Traps with ExpiredApproval if 'expiration_ledger' has already passed, except for an amount of 0:
+// clearing an allowance needs no meaningful expiration
 pub fn write_allowance(
     e: &Env,
     from: Address,
@@ -34,7 +48,7 @@ pub fn write_allowance(
     };
 
     if amount > 0 && expiration_ledger < e.ledger().sequence() {
-        panic!("expiration_ledger is less than ledger seq when amount > 0")
+        panic_with_error!(e, Error::ExpiredApproval)
     }
 
     let key = DataKey::Allowance(AllowanceDataKey { from, spender });
@@ -49,16 +63,33 @@ pub fn write_allowance(
     }
 }
 
+// Emits ("allowance", "spent", from, spender) with the remaining amount so indexers can track
+// live allowances without reading storage. Expired allowances read as 0.
+// An unexpired allowance of i128::MAX is infinite and is never decremented or rewritten.
+// Traps with InsufficientAllowance, after an error event carrying the amount available
 pub fn spend_allowance(e: &Env, from: Address, spender: Address, amount: i128) {
     let allowance = read_allowance(e, from.clone(), spender.clone());
     if allowance.amount < amount {
-        panic!("insufficient allowance");
+        emit_allowance_error(
+            e,
+            Error::InsufficientAllowance as u32,
+            from,
+            spender,
+            amount,
+            allowance.amount,
+        );
+        panic_with_error!(e, Error::InsufficientAllowance);
+    }
+    if allowance.amount == i128::MAX {
+        return;
     }
+    let remaining = allowance.amount - amount;
     write_allowance(
         e,
-        from,
-        spender,
-        allowance.amount - amount,
+        from.clone(),
+        spender.clone(),
+        remaining,
         allowance.expiration_ledger,
     );
+    emit_allowance_spent(e, from, spender, remaining);
 }
@@ -0,0 +1,684 @@
+// Rebalancing by Dutch auction
+// The manager proposes a rebalance with target components and target amounts, priced in an intermediate token.
+// The manager or the operator then starts it.
+// Every component whose amount changes gets its own auction:
+// - a buy auction when the target amount is above the current amount: bidders sell the component to
+//   this contract in exchange for intermediate token
+// - a sell auction when the target amount is below the current amount: bidders buy the component from
+//   this contract with intermediate token
+// Prices are intermediate token per component token, scaled by PRICE_SCALE. They move linearly from
+// start_price to min_price over duration_ledgers, then stay at min_price.
+// An auction stays open until its component reaches the target amount. If it reaches min_price it remains
+// open indefinitely until the manager reprices it with reprice_auction or finalizes the rebalance.
+// Mint traps with RebalanceInProgress while auctions are open: the quantities and the amounts they move are
+// computed from the total supply when the rebalance started, which a mint would make stale.
+// Burn stays open so holders can always redeem, even from an auction left at min_price indefinitely.
+// Components must arrive in full: a bid whose transfer into this contract delivers less, e.g. a fee-on-transfer
+// token, traps with FeeOnTransferNotSupported.
+// A buy auction can also be filled from the component's own transfer_and_call(), with a BidData in 'data',
+// so a bidder signs one transfer instead of a bid; see bid_with_transfer().
+use constellation_shared::events::{
+    emit_auction_min_price, emit_auction_repriced, emit_error, emit_rebalance_cancel, emit_rebalance_finalize,
+    emit_rebalance_progress, emit_rebalance_start,
+};
+use constellation_shared::oracle::PRICE_SCALE;
+use soroban_sdk::{contracttype, panic_with_error, token, Address, Env, Vec};
+
+use crate::component::{
+    check_component_addresses, check_component_decimals, read_amounts, read_components,
+    write_components,
+};
+use crate::error::Error;
+use crate::intermediate::check_intermediate;
+use crate::metadata::read_decimal;
+use crate::oracle::{read_oracle_config, read_price};
+use crate::report::{execution_leg, next_epoch, write_execution_report};
+use crate::storage_types::{DataKey, AUCTION_BUMP_AMOUNT, AUCTION_LIFETIME_THRESHOLD};
+use crate::supply::read_total_supply;
+
+#[derive(Clone)]
+#[contracttype]
+pub struct AuctionParams {
+    pub start_price: i128,
+    pub min_price: i128,
+    pub duration_ledgers: u32,
+    // Smallest fill a bid may make, except the one that completes the auction; 0 for default_min_fill()
+    pub min_fill: i128,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Auction {
+    pub component: Address,
+    pub start_amount: u32,
+    pub target_amount: u32,
+    // Total quantity of the component to trade, fixed when the rebalance starts
+    pub quantity: i128,
+    pub filled: i128,
+    // Intermediate token paid out (buy) or received (sell) across every fill, see report.rs
+    pub intermediate_amount: i128,
+    pub params: AuctionParams,
+    pub start_ledger: u32,
+    // Set once the price has decayed to min_price, so keepers know to alert the manager
+    pub reached_min: bool,
+}
+
+// A rebalance queued by the manager, waiting to be started
+#[derive(Clone)]
+#[contracttype]
+pub struct RebalanceProposal {
+    pub target_components: Vec<Address>,
+    pub target_amounts: Vec<u32>,
+    pub auction_params: Vec<AuctionParams>,
+    pub intermediate_token: Address,
+}
+
+// Bid parameters of a transfer_and_call() fill, XDR-encoded in its 'data'
+#[derive(Clone)]
+#[contracttype]
+pub struct BidData {
+    // The component transferred, which must be the contract calling on_token_transfer()
+    pub component: Address,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Rebalance {
+    // Numbers the rebalance for its execution report, see report.rs
+    pub epoch: u32,
+    pub intermediate_token: Address,
+    // Components with an auction in this rebalance
+    pub components: Vec<Address>,
+    // Total supply when the rebalance started
+    pub supply: i128,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct AuctionStatus {
+    pub component: Address,
+    pub current_amount: u32,
+    pub target_amount: u32,
+    pub filled: i128,
+    pub remaining: i128,
+    pub current_price: i128,
+    pub reached_min: bool,
+    // Effective minimum fill, see AuctionParams
+    pub min_fill: i128,
+}
+
+impl Auction {
+    pub fn is_buy(&self) -> bool {
+        self.target_amount > self.start_amount
+    }
+
+    pub fn remaining(&self) -> i128 {
+        self.quantity - self.filled
+    }
+}
+
+pub fn read_rebalance(e: &Env) -> Option<Rebalance> {
+    let key = DataKey::Rebalance;
+    e.storage().instance().get(&key)
+}
+
+fn write_rebalance(e: &Env, rebalance: &Rebalance) {
+    let key = DataKey::Rebalance;
+    e.storage().instance().set(&key, rebalance);
+}
+
+pub fn read_proposal(e: &Env) -> Option<RebalanceProposal> {
+    let key = DataKey::RebalanceProposal;
+    e.storage().instance().get(&key)
+}
+
+// Checks shared by write_proposal and plan_rebalance
+pub fn check_proposal(e: &Env, proposal: &RebalanceProposal) {
+    if proposal.target_components.len() != proposal.target_amounts.len()
+        || proposal.target_components.len() != proposal.auction_params.len()
+    {
+        panic!("targets, amounts and auction params must have the same length")
+    }
+    check_intermediate(e, &proposal.intermediate_token);
+    check_component_addresses(e, &proposal.target_components);
+    check_component_decimals(e, read_decimal(e), &proposal.target_components);
+}
+
+// Replaces any earlier proposal that has not been started
+pub fn write_proposal(e: &Env, proposal: &RebalanceProposal) {
+    check_proposal(e, proposal);
+    let key = DataKey::RebalanceProposal;
+    e.storage().instance().set(&key, proposal);
+}
+
+// Traps with RebalanceInProgress while a rebalance is open, see the note on mint above
+pub fn check_no_rebalance(e: &Env) {
+    if read_rebalance(e).is_some() {
+        panic_with_error!(e, Error::RebalanceInProgress);
+    }
+}
+
+// Start the auctions for the queued proposal, consuming it
+pub fn start_proposed_rebalance(e: &Env) {
+    let proposal = match read_proposal(e) {
+        Some(proposal) => proposal,
+        None => panic_with_error!(e, Error::NoProposal),
+    };
+    // The token may have been delisted since the proposal
+    check_intermediate(e, &proposal.intermediate_token);
+    e.storage().instance().remove(&DataKey::RebalanceProposal);
+    start_rebalance_auctions(
+        e,
+        proposal.target_components,
+        proposal.target_amounts,
+        proposal.auction_params,
+        proposal.intermediate_token,
+    );
+}
+
+pub fn read_auction(e: &Env, component: Address) -> Option<Auction> {
+    let key = DataKey::Auction(component);
+    e.storage().persistent().get(&key)
+}
+
+// Keep open auctions alive while they wait for bids; bids extend them too
+pub fn bump_auctions(e: &Env) {
+    if let Some(rebalance) = read_rebalance(e) {
+        for component in rebalance.components.iter() {
+            let key = DataKey::Auction(component);
+            if e.storage().persistent().has(&key) {
+                e.storage()
+                    .persistent()
+                    .bump(&key, AUCTION_LIFETIME_THRESHOLD, AUCTION_BUMP_AMOUNT);
+            }
+        }
+    }
+}
+
+fn write_auction(e: &Env, auction: &Auction) {
+    let key = DataKey::Auction(auction.component.clone());
+    e.storage().persistent().set(&key, auction);
+    e.storage()
+        .persistent()
+        .bump(&key, AUCTION_LIFETIME_THRESHOLD, AUCTION_BUMP_AMOUNT);
+}
+
+pub fn current_price(e: &Env, auction: &Auction) -> i128 {
+    let params = &auction.params;
+    let elapsed = e.ledger().sequence() - auction.start_ledger;
+    if elapsed >= params.duration_ledgers {
+        return params.min_price;
+    }
+    params.start_price
+        - (params.start_price - params.min_price) * elapsed as i128
+            / params.duration_ledgers as i128
+}
+
+pub fn auction_status(e: &Env, component: Address) -> AuctionStatus {
+    let auction = match read_auction(e, component.clone()) {
+        Some(auction) => auction,
+        None => panic_with_error!(e, Error::NoAuction),
+    };
+    let components = read_components(e);
+    let amounts = read_amounts(e);
+    let index = components.first_index_of(&component).unwrap();
+    AuctionStatus {
+        component,
+        current_amount: amounts.get(index).unwrap(),
+        target_amount: auction.target_amount,
+        filled: auction.filled,
+        remaining: auction.remaining(),
+        current_price: current_price(e, &auction),
+        reached_min: auction.reached_min || has_reached_min(e, &auction),
+        min_fill: auction.params.min_fill,
+    }
+}
+
+fn has_reached_min(e: &Env, auction: &Auction) -> bool {
+    e.ledger().sequence() - auction.start_ledger >= auction.params.duration_ledgers
+}
+
+// Record that the auction's price has clamped at min_price, emitting ("rebalance", "min_price") once
+// Returns whether the flag is set
+pub fn update_reached_min(e: &Env, auction: &mut Auction) -> bool {
+    if !auction.reached_min && has_reached_min(e, auction) {
+        auction.reached_min = true;
+        write_auction(e, auction);
+        emit_auction_min_price(e, auction.component.clone(), auction.params.min_price);
+    }
+    auction.reached_min
+}
+
+// Lets keepers flag an auction that reached min_price without having to bid
+pub fn check_min_price(e: &Env, component: Address) -> bool {
+    let mut auction = match read_auction(e, component) {
+        Some(auction) => auction,
+        None => panic_with_error!(e, Error::NoAuction),
+    };
+    update_reached_min(e, &mut auction)
+}
+
+// Minimum fill when the manager doesn't set one: a millionth of a whole component token, or one base unit
+// for components with 6 decimals or fewer, so dust bids can't flood the auction's history
+pub fn default_min_fill(e: &Env, component: &Address) -> i128 {
+    let decimals = token::Client::new(e, component).decimals();
+    10i128.pow(decimals.saturating_sub(6))
+}
+
+// 'params' with min_fill resolved, as stored in the auction
+fn resolve_params(e: &Env, component: &Address, params: AuctionParams) -> AuctionParams {
+    if params.min_fill < 0 {
+        panic!("min_fill must not be negative")
+    }
+    if params.min_fill > 0 {
+        return params;
+    }
+    AuctionParams {
+        min_fill: default_min_fill(e, component),
+        ..params
+    }
+}
+
+// Restart the price curve of an open auction with 'params', e.g. one stuck at a min_price nobody bids at
+// The quantity and fills so far carry over. When an oracle is configured its price for the component,
+// in intermediate token, must lie on the new curve, otherwise this traps with PriceOutOfRange.
+pub fn reprice_auction(e: &Env, component: Address, params: AuctionParams) {
+    let rebalance = match read_rebalance(e) {
+        Some(rebalance) => rebalance,
+        None => panic_with_error!(e, Error::NoRebalance),
+    };
+    let mut auction = match read_auction(e, component.clone()) {
+        Some(auction) => auction,
+        None => panic_with_error!(e, Error::NoAuction),
+    };
+    if auction.remaining() == 0 {
+        panic_with_error!(e, Error::AuctionFilled);
+    }
+    if read_oracle_config(e).is_some() {
+        let intermediate_price = read_price(e, rebalance.intermediate_token);
+        if intermediate_price <= 0 {
+            panic!("intermediate token has no price")
+        }
+        let price = read_price(e, component.clone()) * PRICE_SCALE / intermediate_price;
+        if price < params.start_price.min(params.min_price)
+            || price > params.start_price.max(params.min_price)
+        {
+            panic_with_error!(e, Error::PriceOutOfRange);
+        }
+    }
+
+    auction.start_ledger = e.ledger().sequence();
+    auction.reached_min = false;
+    auction.params = resolve_params(e, &component, params);
+    write_auction(e, &auction);
+    emit_auction_repriced(
+        e,
+        component,
+        auction.target_amount,
+        auction.params.start_price,
+        auction.params.min_price,
+        auction.params.duration_ledgers,
+    );
+}
+
+fn start_rebalance_auctions(
+    e: &Env,
+    target_components: Vec<Address>,
+    target_amounts: Vec<u32>,
+    auction_params: Vec<AuctionParams>,
+    intermediate_token: Address,
+) {
+    if read_rebalance(e).is_some() {
+        panic_with_error!(e, Error::RebalanceInProgress);
+    }
+    if target_components.len() != target_amounts.len()
+        || target_components.len() != auction_params.len()
+    {
+        panic!("targets, amounts and auction params must have the same length")
+    }
+
+    let mut components = read_components(e);
+    let mut amounts = read_amounts(e);
+    // Components being removed must be listed with a target amount of 0
+    for component in components.iter() {
+        if !target_components.contains(&component) {
+            panic!("targets must include every current component")
+        }
+    }
+    write_rebalance_snapshot(e, &components, &amounts);
+
+    let supply = read_total_supply(e);
+    let mut auctioned = Vec::new(e);
+    for i in 0..target_components.len() {
+        let component = target_components.get(i).unwrap();
+        let target_amount = target_amounts.get(i).unwrap();
+        let params = auction_params.get(i).unwrap();
+
+        // New components start from 0
+        let index = match components.first_index_of(&component) {
+            Some(index) => index,
+            None => {
+                components.push_back(component.clone());
+                amounts.push_back(0);
+                components.len() - 1
+            }
+        };
+        let start_amount = amounts.get(index).unwrap();
+        if start_amount == target_amount {
+            continue;
+        }
+
+        let quantity = (target_amount as i128 - start_amount as i128).abs() * supply;
+        if quantity == 0 {
+            // Nothing is backed yet, so the new amount can be set directly
+            amounts.set(index, target_amount);
+            continue;
+        }
+
+        let params = resolve_params(e, &component, params);
+        write_auction(
+            e,
+            &Auction {
+                component: component.clone(),
+                start_amount,
+                target_amount,
+                quantity,
+                filled: 0,
+                intermediate_amount: 0,
+                params: params.clone(),
+                start_ledger: e.ledger().sequence(),
+                reached_min: false,
+            },
+        );
+        auctioned.push_back(component.clone());
+        emit_rebalance_start(
+            e,
+            component,
+            target_amount,
+            params.start_price,
+            params.min_price,
+            params.duration_ledgers,
+        );
+    }
+
+    write_components(e, components, amounts);
+    write_rebalance(
+        e,
+        &Rebalance {
+            epoch: next_epoch(e),
+            intermediate_token,
+            components: auctioned,
+            supply,
+        },
+    );
+}
+
+// Composition before the rebalance started, restored by cancel_rebalance and kept for audits
+// Cleared when the rebalance is finalized or cancelled
+fn write_rebalance_snapshot(e: &Env, components: &Vec<Address>, amounts: &Vec<u32>) {
+    let mut snapshot = Vec::new(e);
+    for i in 0..components.len() {
+        snapshot.push_back((components.get(i).unwrap(), amounts.get(i).unwrap()));
+    }
+    e.storage()
+        .instance()
+        .set(&DataKey::RebalanceSnapshot, &snapshot);
+}
+
+pub fn read_rebalance_snapshot(e: &Env) -> Option<Vec<(Address, u32)>> {
+    let key = DataKey::RebalanceSnapshot;
+    e.storage().instance().get(&key)
+}
+
+// Record the execution report of the rebalance and remove its auctions
+fn close_auctions(e: &Env, rebalance: &Rebalance) {
+    let mut legs = Vec::new(e);
+    for component in rebalance.components.iter() {
+        if let Some(auction) = read_auction(e, component.clone()) {
+            legs.push_back(execution_leg(&auction));
+        }
+        e.storage()
+            .persistent()
+            .remove(&DataKey::Auction(component));
+    }
+    write_execution_report(e, rebalance.epoch, &legs);
+    e.storage().instance().remove(&DataKey::Rebalance);
+    e.storage().instance().remove(&DataKey::RebalanceSnapshot);
+}
+
+// Fill up to 'amount' of the auction for 'component' at the current price
+// Returns the amount filled, which is capped at the quantity remaining so the auction never overshoots its target
+pub fn bid(e: &Env, bidder: Address, component: Address, amount: i128) -> i128 {
+    let (rebalance, auction, fill, payment) = record_fill(e, component.clone(), amount);
+
+    // Interactions
+    let contract = e.current_contract_address();
+    let component_client = token::Client::new(e, &component);
+    let intermediate_client = token::Client::new(e, &rebalance.intermediate_token);
+    if auction.is_buy() {
+        transfer_in(e, &component_client, &bidder, fill);
+        intermediate_client.transfer(&contract, &bidder, &payment);
+    } else {
+        transfer_in(e, &intermediate_client, &bidder, payment);
+        component_client.transfer(&contract, &bidder, &fill);
+    }
+
+    emit_rebalance_progress(e, component, fill, auction.remaining());
+    fill
+}
+
+// Fill of a buy auction paid for by 'component's transfer_and_call(): 'amount' has already arrived from
+// 'bidder', so only the intermediate token is paid out. The whole transfer must fill, trapping with BidTooLarge
+// otherwise, and what arrived can't be checked: Soroban doesn't allow calling back into the component while
+// its transfer_and_call() is running, to refund or to read a balance. Fee-on-transfer components must use bid().
+pub fn bid_with_transfer(e: &Env, bidder: Address, component: Address, amount: i128) -> i128 {
+    match read_auction(e, component.clone()) {
+        Some(auction) if auction.is_buy() => {
+            if amount > auction.remaining() {
+                emit_error(e, Error::BidTooLarge as u32, component, amount - auction.remaining());
+                panic_with_error!(e, Error::BidTooLarge);
+            }
+        }
+        _ => {
+            emit_error(e, Error::NoAuction as u32, component, amount);
+            panic_with_error!(e, Error::NoAuction)
+        }
+    }
+    let (rebalance, auction, fill, payment) = record_fill(e, component.clone(), amount);
+
+    // Interactions
+    token::Client::new(e, &rebalance.intermediate_token).transfer(
+        &e.current_contract_address(),
+        &bidder,
+        &payment,
+    );
+
+    emit_rebalance_progress(e, component, fill, auction.remaining());
+    fill
+}
+
+// Checks and effects of a fill shared by bid() and bid_with_transfer(), so both price and account for it the
+// same way. Returns the rebalance, the auction after the fill, the fill and the intermediate token payment.
+fn record_fill(e: &Env, component: Address, amount: i128) -> (Rebalance, Auction, i128, i128) {
+    // The shortfall reported is the part of the bid that can't be filled
+    let rebalance = match read_rebalance(e) {
+        Some(rebalance) => rebalance,
+        None => {
+            emit_error(e, Error::NoRebalance as u32, component, amount);
+            panic_with_error!(e, Error::NoRebalance)
+        }
+    };
+    let mut auction = match read_auction(e, component.clone()) {
+        Some(auction) => auction,
+        None => {
+            emit_error(e, Error::NoAuction as u32, component, amount);
+            panic_with_error!(e, Error::NoAuction)
+        }
+    };
+    if auction.remaining() == 0 {
+        emit_error(e, Error::AuctionFilled as u32, component, amount);
+        panic_with_error!(e, Error::AuctionFilled);
+    }
+
+    let fill = amount.min(auction.remaining());
+    if fill < auction.params.min_fill && fill < auction.remaining() {
+        emit_error(e, Error::BidTooSmall as u32, component, auction.params.min_fill - fill);
+        panic_with_error!(e, Error::BidTooSmall);
+    }
+    let price = current_price(e, &auction);
+    update_reached_min(e, &mut auction);
+
+    // Round the payment out of the contract down, and the payment into the contract up
+    let payment = if auction.is_buy() {
+        fill * price / PRICE_SCALE
+    } else {
+        (fill * price + PRICE_SCALE - 1) / PRICE_SCALE
+    };
+
+    // Effects
+    auction.filled += fill;
+    auction.intermediate_amount += payment;
+    write_auction(e, &auction);
+    update_amount(e, &auction, rebalance.supply);
+    write_last_price(e, component, price);
+    (rebalance, auction, fill, payment)
+}
+
+// Transfer 'amount' from 'from' to this contract, trapping with FeeOnTransferNotSupported unless all of it
+// arrives: the amounts were moved on the assumption that it does
+fn transfer_in(e: &Env, client: &token::Client, from: &Address, amount: i128) {
+    let contract = e.current_contract_address();
+    let before = client.balance(&contract);
+    client.transfer(from, &contract, &amount);
+    let received = client.balance(&contract) - before;
+    if received != amount {
+        emit_error(
+            e,
+            Error::FeeOnTransferNotSupported as u32,
+            client.address.clone(),
+            amount - received,
+        );
+        panic_with_error!(e, Error::FeeOnTransferNotSupported);
+    }
+}
+
+// Price of the most recent bid for a component, used when no auction is open
+fn write_last_price(e: &Env, component: Address, price: i128) {
+    let key = DataKey::LastPrice(component);
+    e.storage().persistent().set(&key, &price);
+    e.storage()
+        .persistent()
+        .bump(&key, AUCTION_LIFETIME_THRESHOLD, AUCTION_BUMP_AMOUNT);
+}
+
+pub fn read_last_price(e: &Env, component: Address) -> Option<i128> {
+    let key = DataKey::LastPrice(component);
+    e.storage().persistent().get(&key)
+}
+
+// Value of one Constellation Token base unit in intermediate token, scaled by PRICE_SCALE
+// Each component is priced at its open auction's current price, or else at the price of its last bid.
+// The intermediate token of the current rebalance is priced at 1. Components never traded are valued at 0.
+pub fn implied_value(e: &Env) -> i128 {
+    let supply = read_total_supply(e);
+    if supply == 0 {
+        return 0;
+    }
+    let intermediate_token = read_rebalance(e).map(|rebalance| rebalance.intermediate_token);
+    let contract = e.current_contract_address();
+
+    let mut value = 0;
+    for component in read_components(e).iter() {
+        let price = if Some(component.clone()) == intermediate_token {
+            PRICE_SCALE
+        } else {
+            match read_auction(e, component.clone()) {
+                Some(auction) if auction.remaining() > 0 => current_price(e, &auction),
+                _ => read_last_price(e, component.clone()).unwrap_or(0),
+            }
+        };
+        let reserve = token::Client::new(e, &component).balance(&contract);
+        value += reserve * price;
+    }
+    value / supply
+}
+
+// Amounts once every auction of the current rebalance reaches its target, in getComponents() order
+pub fn read_target_amounts(e: &Env) -> Vec<u32> {
+    if read_rebalance(e).is_none() {
+        panic_with_error!(e, Error::NoRebalance);
+    }
+    let components = read_components(e);
+    let mut amounts = read_amounts(e);
+    for i in 0..components.len() {
+        if let Some(auction) = read_auction(e, components.get(i).unwrap()) {
+            amounts.set(i, auction.target_amount);
+        }
+    }
+    amounts
+}
+
+// Move the component's amount towards its target as the auction fills
+// Buy auctions round the amount down and sell auctions round it up, so backing is never overstated
+fn update_amount(e: &Env, auction: &Auction, supply: i128) {
+    let components = read_components(e);
+    let mut amounts = read_amounts(e);
+    let index = components.first_index_of(&auction.component).unwrap();
+    // 'filled' accumulates across every bid, so many small bids progress the amount the same way one large bid would
+    let amount = if auction.remaining() == 0 {
+        auction.target_amount
+    } else if auction.is_buy() {
+        (auction.start_amount as i128 + auction.filled / supply) as u32
+    } else {
+        (auction.start_amount as i128 - (auction.filled + supply - 1) / supply) as u32
+    };
+    amounts.set(index, amount);
+    write_components(e, components, amounts);
+}
+
+// Close every auction and end the rebalance
+// Components whose amount reached 0 are removed from the basket
+pub fn finalize_rebalance(e: &Env) {
+    let rebalance = match read_rebalance(e) {
+        Some(rebalance) => rebalance,
+        None => panic_with_error!(e, Error::NoRebalance),
+    };
+    close_auctions(e, &rebalance);
+
+    let components = read_components(e);
+    let amounts = read_amounts(e);
+    let mut kept_components = Vec::new(e);
+    let mut kept_amounts = Vec::new(e);
+    for i in 0..components.len() {
+        let amount = amounts.get(i).unwrap();
+        if amount > 0 {
+            kept_components.push_back(components.get(i).unwrap());
+            kept_amounts.push_back(amount);
+        }
+    }
+    write_components(e, kept_components, kept_amounts);
+
+    emit_rebalance_finalize(e, rebalance.components);
+}
+
+// Close every auction and restore the amounts from before the rebalance
+// Components already swapped stay in the contract, only the amounts revert: after partial fills a sold
+// component can be short of its restored amount until the manager rebalances again
+pub fn cancel_rebalance(e: &Env) {
+    let rebalance = match read_rebalance(e) {
+        Some(rebalance) => rebalance,
+        None => panic_with_error!(e, Error::NoRebalance),
+    };
+    let snapshot = read_rebalance_snapshot(e).unwrap();
+    close_auctions(e, &rebalance);
+
+    let mut components = Vec::new(e);
+    let mut amounts = Vec::new(e);
+    for (component, amount) in snapshot.iter() {
+        components.push_back(component);
+        amounts.push_back(amount);
+    }
+    write_components(e, components, amounts);
+
+    emit_rebalance_cancel(e, rebalance.components);
+}
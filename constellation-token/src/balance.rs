@@ -1,37 +1,80 @@
 
-// Unchanged from https://github.com/stellar/soroban-examples/tree/main/token/src
-use crate::storage_types::{DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
-use soroban_sdk::{Address, Env};
+// Based on https://github.com/stellar/soroban-examples/tree/main/token/src
+// Balances are stored as a BalanceEntry so per-account fields can be added without a storage migration.
+// Reads don't extend the entry's TTL so balance() stays read-only; every write does.
+// A frozen account (authorized cleared by the manager) can't send: its transfers and mints to it trap with
+// AccountFrozen, see check_not_frozen(). It can still receive transfers and burn, so redemption stays open.
+use crate::error::Error;
+use crate::snapshot::checkpoint_balance;
+use crate::storage_types::{BalanceEntry, DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::{panic_with_error, Address, Env, TryFromVal, Val};
 
-pub fn read_balance(e: &Env, addr: Address) -> i128 {
+fn read_balance_entry(e: &Env, addr: Address) -> BalanceEntry {
     let key = DataKey::Balance(addr);
-    if let Some(balance) = e.storage().persistent().get::<DataKey, i128>(&key) {
-        e.storage()
-            .persistent()
-            .bump(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
-        balance
+    if let Some(raw) = e.storage().persistent().get::<DataKey, Val>(&key) {
+        match BalanceEntry::try_from_val(e, &raw) {
+            Ok(entry) => entry,
+            // Legacy entries hold a bare i128; they are upgraded on the next write
+            Err(_) => BalanceEntry {
+                amount: i128::try_from_val(e, &raw).unwrap(),
+                authorized: true,
+                locked_until: 0,
+            },
+        }
     } else {
-        0
+        BalanceEntry {
+            amount: 0,
+            authorized: true,
+            locked_until: 0,
+        }
     }
 }
 
-fn write_balance(e: &Env, addr: Address, amount: i128) {
+fn write_balance_entry(e: &Env, addr: Address, entry: &BalanceEntry) {
     let key = DataKey::Balance(addr);
-    e.storage().persistent().set(&key, &amount);
+    e.storage().persistent().set(&key, entry);
     e.storage()
         .persistent()
         .bump(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
 }
 
+pub fn read_balance(e: &Env, addr: Address) -> i128 {
+    read_balance_entry(e, addr).amount
+}
+
 pub fn receive_balance(e: &Env, addr: Address, amount: i128) {
-    let balance = read_balance(e, addr.clone());
-    write_balance(e, addr, balance + amount);
+    let mut entry = read_balance_entry(e, addr.clone());
+    checkpoint_balance(e, addr.clone(), entry.amount);
+    entry.amount += amount;
+    write_balance_entry(e, addr, &entry);
 }
 
 pub fn spend_balance(e: &Env, addr: Address, amount: i128) {
-    let balance = read_balance(e, addr.clone());
-    if balance < amount {
+    let mut entry = read_balance_entry(e, addr.clone());
+    if entry.locked_until > e.ledger().sequence() {
+        panic!("balance is locked");
+    }
+    if entry.amount < amount {
         panic!("insufficient balance");
     }
-    write_balance(e, addr, balance - amount);
+    checkpoint_balance(e, addr.clone(), entry.amount);
+    entry.amount -= amount;
+    write_balance_entry(e, addr, &entry);
+}
+
+pub fn is_frozen(e: &Env, addr: Address) -> bool {
+    !read_balance_entry(e, addr).authorized
+}
+
+// Called on the sender of a transfer and the recipient of a mint
+pub fn check_not_frozen(e: &Env, addr: &Address) {
+    if is_frozen(e, addr.clone()) {
+        panic_with_error!(e, Error::AccountFrozen);
+    }
+}
+
+pub fn write_frozen(e: &Env, addr: Address, frozen: bool) {
+    let mut entry = read_balance_entry(e, addr.clone());
+    entry.authorized = !frozen;
+    write_balance_entry(e, addr, &entry);
 }
@@ -0,0 +1,192 @@
+// Component tokens held by a Constellation Token, and the units of each backing one Constellation Token.
+// Components and amounts are stored as two parallel vectors in persistent storage, so a large basket
+// doesn't weigh on the instance entry every call loads; bump_instance() keeps them alive alongside it.
+// The manager can pause issuance of individual components; the flag is stored per component.
+// Units are base units of a component per base unit of Constellation Token, so every component needs at
+// least as many decimals as the Constellation Token. Stellar Asset Contracts always have 7 decimals.
+use soroban_sdk::{contracttype, panic_with_error, token, Address, Env, Vec};
+
+use crate::error::Error;
+use crate::storage_types::DataKey;
+use crate::ttl::{read_ttl_config, TtlConfig};
+
+// Largest page get_components_page() returns
+pub(crate) const MAX_PAGE_SIZE: u32 = 50;
+
+// One basket member as reported by get_basket()
+#[derive(Clone)]
+#[contracttype]
+pub struct Component {
+    pub address: Address,
+    pub units: u32,
+    pub paused: bool,
+}
+
+// Approximate serialized size of one basket member: an address plus its units
+pub(crate) const COMPONENT_ENTRY_SIZE: u32 = 48;
+
+pub fn read_components(e: &Env) -> Vec<Address> {
+    let key = DataKey::Components;
+    e.storage().persistent().get(&key).unwrap()
+}
+
+pub fn read_amounts(e: &Env) -> Vec<u32> {
+    let key = DataKey::Amounts;
+    e.storage().persistent().get(&key).unwrap()
+}
+
+pub fn write_components(e: &Env, components: Vec<Address>, amounts: Vec<u32>) {
+    if components.len() != amounts.len() {
+        panic!("components and amounts must have the same length")
+    }
+    e.storage().persistent().set(&DataKey::Components, &components);
+    e.storage().persistent().set(&DataKey::Amounts, &amounts);
+    bump_components(e, &read_ttl_config(e));
+}
+
+pub fn bump_components(e: &Env, config: &TtlConfig) {
+    for key in [DataKey::Components, DataKey::Amounts] {
+        e.storage()
+            .persistent()
+            .bump(&key, config.threshold, config.bump_amount);
+    }
+}
+
+// Components must be distinct and can't be this contract itself, else traps with InvalidComponent
+// Soroban addresses have no all-zero value to reject; an address with no contract behind it fails
+// at the first decimals() call instead.
+pub fn check_component_addresses(e: &Env, components: &Vec<Address>) {
+    let contract = e.current_contract_address();
+    for i in 0..components.len() {
+        let component = components.get(i).unwrap();
+        if component == contract {
+            panic_with_error!(e, Error::InvalidComponent)
+        }
+        if components.first_index_of(&component) != Some(i) {
+            panic_with_error!(e, Error::InvalidComponent)
+        }
+    }
+}
+
+// Chosen at initialize and fixed afterwards; only 'required' is stored
+pub fn is_uniform_decimals_required(e: &Env) -> bool {
+    e.storage().instance().has(&DataKey::UniformDecimals)
+}
+
+pub fn write_uniform_decimals_required(e: &Env) {
+    e.storage().instance().set(&DataKey::UniformDecimals, &true);
+}
+
+// Traps with InvalidDecimals if a component has fewer decimals than 'decimal', the Constellation Token's,
+// or, when uniform decimals are required, if its decimals differ from the first component's
+pub fn check_component_decimals(e: &Env, decimal: u32, components: &Vec<Address>) {
+    let uniform = is_uniform_decimals_required(e);
+    let mut first_decimals = None;
+    for component in components.iter() {
+        let component_decimals = token::Client::new(e, &component).decimals();
+        if component_decimals < decimal {
+            panic_with_error!(e, Error::InvalidDecimals)
+        }
+        match first_decimals {
+            None => first_decimals = Some(component_decimals),
+            Some(first) => {
+                if uniform && component_decimals != first {
+                    panic_with_error!(e, Error::InvalidDecimals)
+                }
+            }
+        }
+    }
+}
+
+// Decimals reported by each component token, in getComponents() order
+pub fn read_component_decimals(e: &Env) -> Vec<u32> {
+    let mut decimals = Vec::new(e);
+    for component in read_components(e).iter() {
+        decimals.push_back(token::Client::new(e, &component).decimals());
+    }
+    decimals
+}
+
+// Components in ascending address order with their amounts moved along, so the same basket always
+// has the same stored composition. Insertion sort; baskets are small.
+pub fn sort_basket(
+    e: &Env,
+    components: &Vec<Address>,
+    amounts: &Vec<u32>,
+) -> (Vec<Address>, Vec<u32>) {
+    if components.len() != amounts.len() {
+        panic!("components and amounts must have the same length")
+    }
+    let mut sorted_components: Vec<Address> = Vec::new(e);
+    let mut sorted_amounts = Vec::new(e);
+    for i in 0..components.len() {
+        let component = components.get(i).unwrap();
+        let mut index = sorted_components.len();
+        while index > 0 && sorted_components.get(index - 1).unwrap() > component {
+            index -= 1;
+        }
+        sorted_components.insert(index, component);
+        sorted_amounts.insert(index, amounts.get(i).unwrap());
+    }
+    (sorted_components, sorted_amounts)
+}
+
+// Units of 'component', None if it isn't in the basket
+pub fn read_unit(e: &Env, component: &Address) -> Option<u32> {
+    let index = read_components(e).first_index_of(component)?;
+    read_amounts(e).get(index)
+}
+
+// Linear scan of the components vector
+pub fn is_component(e: &Env, token: &Address) -> bool {
+    read_components(e).contains(token)
+}
+
+pub fn is_component_paused(e: &Env, component: Address) -> bool {
+    let key = DataKey::Paused(component);
+    e.storage().instance().get(&key).unwrap_or(false)
+}
+
+pub fn write_component_paused(e: &Env, component: Address, paused: bool) {
+    if !is_component(e, &component) {
+        panic!("not a component")
+    }
+    let key = DataKey::Paused(component);
+    if paused {
+        e.storage().instance().set(&key, &true);
+    } else {
+        e.storage().instance().remove(&key);
+    }
+}
+
+pub fn read_basket(e: &Env) -> Vec<Component> {
+    let components = read_components(e);
+    let amounts = read_amounts(e);
+    let mut basket = Vec::new(e);
+    for i in 0..components.len() {
+        let address = components.get(i).unwrap();
+        basket.push_back(Component {
+            paused: is_component_paused(e, address.clone()),
+            address,
+            units: amounts.get(i).unwrap(),
+        });
+    }
+    basket
+}
+
+// Up to 'limit' basket members starting at index 'start'; empty when 'start' is past the end
+pub fn read_basket_page(e: &Env, start: u32, limit: u32) -> Vec<Component> {
+    let components = read_components(e);
+    let amounts = read_amounts(e);
+    let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(components.len());
+    let mut page = Vec::new(e);
+    for i in start..end {
+        let address = components.get(i).unwrap();
+        page.push_back(Component {
+            paused: is_component_paused(e, address.clone()),
+            address,
+            units: amounts.get(i).unwrap(),
+        });
+    }
+    page
+}
@@ -0,0 +1,105 @@
+// Every configuration value of this token in one read, for auditors checking a deployed instance.
+// Balances, supply and auction progress are state rather than configuration and are left out.
+// Unset optional values are an empty Vec and set ones a Vec of one: a contracttype struct can't hold an
+// Option of an Address or another contracttype, since it then has no ScVal conversion under testutils.
+use constellation_shared::storage::STORAGE_VERSION;
+use soroban_sdk::{contracttype, Address, Env, IntoVal, String, TryFromVal, Val, Vec};
+
+use crate::admin::read_administrator;
+use crate::auction::{read_proposal, read_rebalance, Rebalance, RebalanceProposal};
+use crate::component::{
+    is_component_paused, is_uniform_decimals_required, read_amounts, read_components,
+};
+use crate::denylist::read_denylist;
+use crate::dust::read_dust_epsilon;
+use crate::fee::{read_fee_recipient, read_performance_fee};
+use crate::hook::read_transfer_hook;
+use crate::intermediate::read_intermediates;
+use crate::manager::{read_manager, read_operator};
+use crate::metadata::{read_decimal, read_name, read_symbol};
+use crate::oracle::{read_oracle_config, OracleConfig};
+use crate::report::read_epoch;
+use crate::solvency::{is_mint_halted, read_backing_epsilon};
+use crate::switches::{is_burn_enabled, is_mint_enabled};
+use crate::ttl::{read_ttl_config, TtlConfig};
+use crate::winddown::read_wind_down_ledger;
+
+#[derive(Clone)]
+#[contracttype]
+pub struct ConfigDump {
+    // constellation_shared::storage::STORAGE_VERSION this contract was built with
+    pub storage_version: u32,
+    pub admin: Address,
+    pub manager: Address,
+    pub operator: Vec<Address>,
+    pub decimals: u32,
+    pub name: String,
+    pub symbol: String,
+    pub components: Vec<Address>,
+    pub amounts: Vec<u32>,
+    pub paused_components: Vec<Address>,
+    pub uniform_decimals: bool,
+    pub ttl: TtlConfig,
+    pub oracle: Vec<OracleConfig>,
+    pub performance_fee_bps: u32,
+    pub fee_recipient: Address,
+    pub dust_epsilon: i128,
+    pub backing_epsilon: i128,
+    pub transfer_hook: Vec<Address>,
+    pub denylist: Vec<Address>,
+    pub intermediates: Vec<Address>,
+    pub mint_enabled: bool,
+    pub burn_enabled: bool,
+    pub mint_halted: bool,
+    pub wind_down_ledger: Option<u32>,
+    pub rebalance_proposal: Vec<RebalanceProposal>,
+    pub rebalance: Vec<Rebalance>,
+    pub rebalance_epoch: u32,
+}
+
+fn optional<T: IntoVal<Env, Val> + TryFromVal<Env, Val>>(e: &Env, value: Option<T>) -> Vec<T> {
+    let mut values = Vec::new(e);
+    if let Some(value) = value {
+        values.push_back(value);
+    }
+    values
+}
+
+pub fn read_config(e: &Env) -> ConfigDump {
+    let components = read_components(e);
+    let mut paused_components = Vec::new(e);
+    for component in components.iter() {
+        if is_component_paused(e, component.clone()) {
+            paused_components.push_back(component);
+        }
+    }
+    ConfigDump {
+        storage_version: STORAGE_VERSION,
+        admin: read_administrator(e),
+        manager: read_manager(e),
+        operator: optional(e, read_operator(e)),
+        decimals: read_decimal(e),
+        name: read_name(e),
+        symbol: read_symbol(e),
+        components,
+        amounts: read_amounts(e),
+        paused_components,
+        uniform_decimals: is_uniform_decimals_required(e),
+        ttl: read_ttl_config(e),
+        oracle: optional(e, read_oracle_config(e)),
+        performance_fee_bps: read_performance_fee(e),
+        fee_recipient: read_fee_recipient(e),
+        dust_epsilon: read_dust_epsilon(e),
+        backing_epsilon: read_backing_epsilon(e),
+        transfer_hook: optional(e, read_transfer_hook(e)),
+        denylist: read_denylist(e),
+        intermediates: read_intermediates(e),
+        mint_enabled: is_mint_enabled(e),
+        burn_enabled: is_burn_enabled(e),
+        mint_halted: is_mint_halted(e),
+        wind_down_ledger: read_wind_down_ledger(e),
+        rebalance_proposal: optional(e, read_proposal(e)),
+        rebalance: optional(e, read_rebalance(e)),
+        rebalance_epoch: read_epoch(e),
+    }
+}
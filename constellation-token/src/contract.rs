@@ -5,43 +5,182 @@
 // A Constellation Token holds balances of component tokens, which also follow the Soroban Token Interface.
 // A Constellation Token is initialized with a list of component tokens and their units
 // A Constellation Token can be only be minted or burned by the Constellation Minter Burner contract.
+// Functions that change state extend the instance TTL. Views don't write anything, so wallets can simulate them
+// as read-only; extend_ttl() keeps an idle contract alive.
 
-use crate::admin::{has_administrator, read_administrator, write_administrator};
-use crate::allowance::{read_allowance, spend_allowance, write_allowance};
-use crate::balance::{read_balance, receive_balance, spend_balance};
+use crate::admin::{
+    has_administrator, read_administrator, require_initialized, write_administrator,
+};
+use crate::allowance::{read_allowance, read_allowances, spend_allowance, write_allowance};
+use crate::auction::{
+    auction_status, bid, bid_with_transfer, bump_auctions, cancel_rebalance, check_min_price,
+    check_no_rebalance, finalize_rebalance, implied_value, read_proposal, read_rebalance_snapshot,
+    read_target_amounts, reprice_auction, start_proposed_rebalance, write_proposal, AuctionParams,
+    AuctionStatus, BidData, RebalanceProposal,
+};
+use crate::balance::{
+    check_not_frozen, is_frozen, read_balance, receive_balance, spend_balance, write_frozen,
+};
+use crate::component::{
+    check_component_addresses, check_component_decimals, is_component, read_amounts, read_basket,
+    read_basket_page, read_component_decimals, read_components, read_unit, sort_basket,
+    write_component_paused, write_components, write_uniform_decimals_required, Component,
+    COMPONENT_ENTRY_SIZE,
+};
+use crate::config::{read_config, ConfigDump};
+use crate::denylist::{add_to_denylist, check_not_denied, read_denylist, remove_from_denylist};
+use crate::dust::{collect_dust, read_dust_epsilon, write_dust_epsilon};
+use crate::error::Error;
+use crate::intermediate::{add_intermediate, read_intermediates, remove_intermediate};
+use crate::fee::{
+    accrue_fee, read_fee_recipient, read_high_water_mark, read_performance_fee, write_fee_recipient,
+    write_performance_fee,
+};
+use crate::hook::{check_transfer_hook, read_transfer_hook, write_transfer_hook};
+use crate::manager::{read_manager, read_operator, require_executor, write_manager, write_operator};
+use crate::manual::{rebalance_manual, reconcile, set_unit};
 use crate::metadata::{read_decimal, read_name, read_symbol, write_metadata};
-use crate::storage_types::{INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
+use crate::oracle::{
+    implied_price, nav_per_token, premium_discount_bps, read_oracle_config, weights,
+    write_oracle_config, OracleConfig,
+};
+use crate::plan::{plan_rebalance, RebalancePlan};
+use crate::report::{read_epoch, read_execution_report, ExecutionLeg};
+use crate::snapshot::{
+    read_balance_at, read_snapshot, read_total_supply_at, snapshot_id, take_snapshot, Snapshot,
+};
+use crate::solvency::{
+    assert_solvent, check_circuit_breaker, is_fully_backed, is_mint_halted, read_backing_epsilon,
+    read_backing_page, verify_backing, write_backing_epsilon, write_mint_halted,
+};
+use crate::supply::{
+    decrease_total_supply, increase_total_supply, read_max_supply, read_total_supply,
+    write_total_supply,
+};
+use crate::switches::{
+    check_burn_enabled, check_mint_enabled, is_burn_enabled, is_mint_enabled, write_burn_enabled,
+    write_mint_enabled,
+};
+use crate::ttl::{bump_instance, read_ttl_config, write_ttl_config, TtlConfig};
+use crate::winddown::{
+    check_not_winding_down, initiate_wind_down, is_winding_down, read_wind_down_ledger,
+};
+use constellation_shared::events::{emit_metadata_updated, read_event_seq};
+use constellation_shared::receiver::{TokenReceiver, TokenReceiverClient};
 use soroban_sdk::token::{self, Interface as _};
-use soroban_sdk::{contract, contractimpl, Address, Env, String};
+use soroban_sdk::xdr::{FromXdr, ToXdr};
+use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Bytes, Env, String};
 use soroban_sdk::Vec;
 use soroban_token_sdk::metadata::TokenMetadata;
 use soroban_token_sdk::TokenUtils;
 
+// 10^18 base units per token already uses ~60 of the 127 bits of an i128,
+// leaving headroom for supply * units scaling
+pub(crate) const MAX_DECIMALS: u32 = 18;
+
+// Revision of the soroban_sdk::token::Interface this contract implements, bumped when it changes
+pub(crate) const INTERFACE_VERSION: u32 = 1;
+
 fn check_nonnegative_amount(amount: i128) {
     if amount < 0 {
         panic!("negative amount is not allowed: {}", amount)
     }
 }
 
+fn check_decimals(e: &Env, decimal: u32) {
+    if decimal > MAX_DECIMALS {
+        panic_with_error!(e, Error::InvalidDecimals)
+    }
+}
+
+// The admin is the MinterBurner contract, which can't sign for the manager's role; traps with InvalidRoles
+fn check_roles(e: &Env, admin: &Address, manager: &Address) {
+    if admin == manager {
+        panic_with_error!(e, Error::InvalidRoles)
+    }
+    if *admin == e.current_contract_address() || *manager == e.current_contract_address() {
+        panic_with_error!(e, Error::InvalidRoles)
+    }
+}
+
+// Lengths in bytes, enough for a descriptive name and an exchange ticker
+const MAX_NAME_LENGTH: u32 = 64;
+const MAX_SYMBOL_LENGTH: u32 = 12;
+
+// Traps with InvalidMetadata for an empty or over-long name or symbol
+fn check_metadata(e: &Env, name: &String, symbol: &String) {
+    if name.len() == 0 || name.len() > MAX_NAME_LENGTH {
+        panic_with_error!(e, Error::InvalidMetadata)
+    }
+    if symbol.len() == 0 || symbol.len() > MAX_SYMBOL_LENGTH {
+        panic_with_error!(e, Error::InvalidMetadata)
+    }
+}
+
+// Budget for the configuration initialize writes, in approximate serialized bytes, so every later load
+// and bump of it stays cheap and far from the ledger entry size limit. Fits about 340 components.
+const MAX_CONFIG_SIZE: u32 = 16 * 1024;
+
+fn check_config_size(e: &Env, name: &String, symbol: &String, components: &Vec<Address>) {
+    let size = components
+        .len()
+        .saturating_mul(COMPONENT_ENTRY_SIZE)
+        .saturating_add(name.len())
+        .saturating_add(symbol.len());
+    if size > MAX_CONFIG_SIZE {
+        panic_with_error!(e, Error::ConfigTooLarge)
+    }
+}
+
+// An Address serializes as ScVal::Address (4-byte tag) then ScAddress, whose 4-byte tag is 1 for a contract
+fn is_contract(e: &Env, address: &Address) -> bool {
+    address.clone().to_xdr(e).get(7) == Some(1)
+}
+
+// Every initial component must back the token; components are removed through a rebalance instead
+// Traps with InvalidUnits for a zero unit
+fn check_units(e: &Env, amounts: &Vec<u32>) {
+    for units in amounts.iter() {
+        if units == 0 {
+            panic_with_error!(e, Error::InvalidUnits)
+        }
+    }
+}
+
 #[contract]
 pub struct ConstellationToken;
 
 #[contractimpl]
 impl ConstellationToken {
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize(
         e: Env,
         decimal: u32,
-        components: Vec<String>,
+        components: Vec<Address>,
         amounts: Vec<u32>,
         admin: Address, // Must be instance of ConstellationMinterBurner contract
         manager: Address, // For future use; manager can rebalance and charge fees
         name: String,
-        symbol: String
+        symbol: String,
+        sort_components: bool, // Store components in address order, for comparing baskets
+        require_uniform_decimals: bool, // All components, now and after rebalances, share decimals
     ) {
         if has_administrator(&e) {
             panic!("already initialized")
         }
-        write_administrator(&e, &admin);
+        check_decimals(&e, decimal);
+        check_roles(&e, &admin, &manager);
+        // Before the per-component checks, which call every component
+        check_config_size(&e, &name, &symbol, &components);
+        check_component_addresses(&e, &components);
+        check_units(&e, &amounts);
+        // Stored first so check_component_decimals applies it
+        if require_uniform_decimals {
+            write_uniform_decimals_required(&e);
+        }
+        check_component_decimals(&e, decimal, &components);
+        check_metadata(&e, &name, &symbol);
+        write_manager(&e, &manager);
 
         write_metadata(
             &e,
@@ -50,13 +189,26 @@ impl ConstellationToken {
                 name,
                 symbol,
             },
-        )
+        );
+
+        // Write <Vec> components and <Vec> amounts to instance storage
+        if sort_components {
+            let (components, amounts) = sort_basket(&e, &components, &amounts);
+            write_components(&e, components, amounts);
+        } else {
+            write_components(&e, components, amounts);
+        }
+
+        // Start from the default instance TTL, see ttl.rs
+        write_ttl_config(&e, &read_ttl_config(&e));
+        write_total_supply(&e, 0);
 
-        // Write <Vec> components and <Vec> amounts to persistent storage
-        write_components(&e, components, amounts); // TODO: write_components Implementation
+        // Written last: require_initialized() treats the admin as proof that everything above is in place
+        write_administrator(&e, &admin);
     }
 
     pub fn mint(e: Env, to: Address, amount: i128) {
+        require_initialized(&e);
         check_nonnegative_amount(amount);
         let admin = read_administrator(&e);
         // A user calls the mint() function of the Constellation Minter Burner contract
@@ -64,105 +216,958 @@ impl ConstellationToken {
         // Then the MinterBurner will call ContellationToken.mint() with 'to' as the user address (issuance)
         admin.require_auth();
 
-        e.storage()
-            .instance()
-            .bump(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        bump_instance(&e);
+
+        check_mint_enabled(&e);
+        check_not_winding_down(&e);
+        check_no_rebalance(&e);
+        check_not_denied(&e, &to);
+        check_not_frozen(&e, &to);
+        // The reserves, including the deposit for this mint, must back the supply after it
+        check_circuit_breaker(&e, amount);
 
         receive_balance(&e, to.clone(), amount);
+        increase_total_supply(&e, amount);
         TokenUtils::new(&e).events().mint(admin, to, amount);
+
+        #[cfg(feature = "solvency-check")]
+        assert_solvent(&e);
     }
 
-    pub fn burn(e: Env, from: Address, amount: i128) {
-        // 'from' will be the MinterBurner contract
-        // A user calls the burn() function of the Constellation Minter Burner contract
-        // The MinterBurner will receive the user's Constellation Tokens (redemption)
-        // The MinterBurner will send the user component tokens
-        // Then the MinterBurner will call ContellationToken.burn()
-        from.require_auth();
-        check_nonnegative_amount(amount);
+    // Send component tokens held by this contract to 'to' during redemption
+    // 'amounts' must be in the same order as getComponents()
+    // Only the Constellation Minter Burner may release components, after it has burned the redeemed tokens
+    pub fn release(e: Env, to: Address, amounts: Vec<i128>) {
+        require_initialized(&e);
+        let admin = read_administrator(&e);
+        admin.require_auth();
 
-        e.storage()
-            .instance()
-            .bump(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        bump_instance(&e);
 
-        spend_balance(&e, from.clone(), amount);
-        TokenUtils::new(&e).events().burn(from, amount);
+        let components = read_components(&e);
+        for i in 0..components.len() {
+            token::Client::new(&e, &components.get(i).unwrap()).transfer(
+                &e.current_contract_address(),
+                &to,
+                &amounts.get(i).unwrap(),
+            );
+        }
+
+        #[cfg(feature = "solvency-check")]
+        assert_solvent(&e);
+    }
+
+    // Same as release() for a single component, so the Minter Burner can isolate a component
+    // whose transfers fail (e.g. frozen by its issuer) and queue it for later
+    pub fn release_component(e: Env, to: Address, component: Address, amount: i128) {
+        require_initialized(&e);
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        bump_instance(&e);
+
+        token::Client::new(&e, &component).transfer(&e.current_contract_address(), &to, &amount);
+
+        #[cfg(feature = "solvency-check")]
+        assert_solvent(&e);
+    }
+
+    pub fn total_supply(e: Env) -> i128 {
+        require_initialized(&e);
+        read_total_supply(&e)
+    }
+
+    // allowance() together with the ledger the approval expires at, so wallets can show when it lapses
+    // An expired approval reads as amount 0 with its old expiration_ledger; (0, 0) if none was ever made
+    pub fn allowance_info(e: Env, from: Address, spender: Address) -> (i128, u32) {
+        require_initialized(&e);
+        let allowance = read_allowance(&e, from, spender);
+        (allowance.amount, allowance.expiration_ledger)
+    }
+
+    // allowance() for each of 'spenders' in one call, in the same order; see allowance.rs
+    pub fn allowances_of(e: Env, from: Address, spenders: Vec<Address>) -> Vec<i128> {
+        require_initialized(&e);
+        read_allowances(&e, from, spenders)
+    }
+
+    // Ledger the approval of 'spender' by 'from' expires at, 0 if none was ever made
+    pub fn allowance_expiration(e: Env, from: Address, spender: Address) -> u32 {
+        require_initialized(&e);
+        read_allowance(&e, from, spender).expiration_ledger
+    }
+
+    // Peak total supply since initialize, including fee mints
+    pub fn high_water_supply(e: Env) -> i128 {
+        require_initialized(&e);
+        read_max_supply(&e)
+    }
+
+    // Panics with Error::Insolvent if any component balance held by this contract
+    // is less than total_supply * units for that component
+    pub fn assert_solvent(e: Env) {
+        require_initialized(&e);
+        assert_solvent(&e);
+    }
+
+    // Within the backing epsilon per component, see solvency.rs
+    pub fn is_fully_backed(e: Env) -> bool {
+        require_initialized(&e);
+        is_fully_backed(&e)
+    }
+
+    // (component, held - required) for every component, exact; negative for a deficit
+    pub fn verify_backing(e: Env) -> Vec<(Address, i128)> {
+        require_initialized(&e);
+        verify_backing(&e)
+    }
+
+    // Deficit per component is_fully_backed() tolerates; admin only
+    pub fn set_backing_epsilon(e: Env, epsilon: i128) {
+        require_initialized(&e);
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        bump_instance(&e);
+
+        write_backing_epsilon(&e, epsilon);
+    }
+
+    pub fn backing_epsilon(e: Env) -> i128 {
+        require_initialized(&e);
+        read_backing_epsilon(&e)
+    }
+
+    // Anyone may halt minting once the basket is under-backed; returns whether minting is halted
+    pub fn trip_circuit_breaker(e: Env) -> bool {
+        require_initialized(&e);
+        bump_instance(&e);
+
+        if !is_fully_backed(&e) {
+            write_mint_halted(&e, true);
+        }
+        is_mint_halted(&e)
+    }
+
+    // Resume minting after the manager has restored backing
+    pub fn reset_circuit_breaker(e: Env) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        if !is_fully_backed(&e) {
+            panic_with_error!(&e, Error::Undercollateralized);
+        }
+        write_mint_halted(&e, false);
+    }
+
+    // Turn minting off or back on, independently of burning and of the circuit breaker
+    pub fn set_mint_enabled(e: Env, enabled: bool) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        write_mint_enabled(&e, enabled);
+    }
+
+    // Turn burning, and with it redemption, off or back on
+    // Once a wind-down has been initiated burning can't be turned off, so holders can always exit
+    pub fn set_burn_enabled(e: Env, enabled: bool) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        if !enabled && read_wind_down_ledger(&e).is_some() {
+            panic_with_error!(&e, Error::WindDown);
+        }
+        write_burn_enabled(&e, enabled);
+    }
+
+    pub fn mint_enabled(e: Env) -> bool {
+        require_initialized(&e);
+        is_mint_enabled(&e)
     }
 
-    pub fn getComponents(e: Env) -> Vec<String> {
-        e.storage()
-            .instance()
-            .bump(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    pub fn burn_enabled(e: Env) -> bool {
+        require_initialized(&e);
+        is_burn_enabled(&e)
+    }
+
+    // Announce the irreversible wind-down, see winddown.rs; returns the ledger it takes effect at
+    // Burning is turned back on if it was off, and can't be turned off again, so redemption stays open
+    // Traps with WindDown if one was already initiated
+    pub fn initiate_wind_down(e: Env) -> u32 {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        write_burn_enabled(&e, true);
+        initiate_wind_down(&e)
+    }
 
-        read_components(&e); // TODO: read_components Implementation
+    // True once the notice period has passed and issuance has stopped
+    pub fn is_winding_down(e: Env) -> bool {
+        require_initialized(&e);
+        is_winding_down(&e)
+    }
+
+    // Ledger the wind-down takes effect at, None if none was initiated
+    pub fn wind_down_ledger(e: Env) -> Option<u32> {
+        require_initialized(&e);
+        read_wind_down_ledger(&e)
+    }
+
+    pub fn getComponents(e: Env) -> Vec<Address> {
+        require_initialized(&e);
+        read_components(&e)
     }
 
     // Must return values in the same order as getComponents()
     pub fn getAmounts(e: Env) -> Vec<u32> {
-        e.storage()
-            .instance()
-            .bump(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
-
-        read_amounts(&e); // TODO: read_amounts Implementation
-    }
-
-    // TODO: Implement Dutch Auction contract
-    // Auction params are set by the manager and include:
-    // - An intermediate component in which prices are denominated
-    // - Target components (incl. any components added or removed)
-    // - Target amounts for each component
-    // - A starting price for each target component
-    // - A minimum price for each target component
-    // - A price function that gradually lowers the acceptable price in terms of intermediate component (ex. linear, exponential, etc.) 
-    AuctionClient.start_rebalance_auctions(&e, target_components, target_amounts, auction_params, intermediate_token);
-    
-    fn AuctionClient.start_rebalance_auctions(&e: Env, target_components: Vec<Address>, target_amounts: Vec<u32>, auction_params: Vec<AuctionParam>, intermediate_token: Address) {
-        // Temporarily add intermediate token to components[] vector if not already included
-        for i in 0..target_components.len() {
-            // Start a Dutch auction for component[i]
-            // During an auction users are allowed to swap (target_amount[i] - current_amount[i]) of component tokens for intermediate token in the direction of (target_amount[i] - current_amount[i])
-            // component[i] auction stays open until the component reaches target amount.
-            // If the component[i] auction reaches minimum price, the auction will remain open indefinitely until the manager starts a new rebalance
-            // Note: Mint and Burn of ConstellationToken can still be performed while auctions are open
-            TokenUtils::new(&e).events().start_rebalance_auction(component, target_amount, auction_params);
+        require_initialized(&e);
+        read_amounts(&e)
+    }
+
+    // Every component with its units and whether its issuance is paused
+    pub fn get_basket(e: Env) -> Vec<Component> {
+        require_initialized(&e);
+        read_basket(&e)
+    }
+
+    // For UIs showing large baskets; the MinterBurner reads the whole basket with get_basket()
+    // 'limit' is capped at 50
+    pub fn get_components_page(e: Env, start: u32, limit: u32) -> Vec<Component> {
+        require_initialized(&e);
+        read_basket_page(&e, start, limit)
+    }
+
+    // Balance this contract holds of each component in a page of the basket, for paginated solvency checks
+    // Compare against total_supply() * unit_of(component); 'count' is capped at 50
+    pub fn backing_balances_page(e: Env, start: u32, count: u32) -> Vec<(Address, i128)> {
+        require_initialized(&e);
+        read_backing_page(&e, start, count)
+    }
+
+    pub fn component_count(e: Env) -> u32 {
+        require_initialized(&e);
+        read_components(&e).len()
+    }
+
+    // O(n) in the number of components
+    pub fn is_component(e: Env, token: Address) -> bool {
+        require_initialized(&e);
+        is_component(&e, &token)
+    }
+
+    // Units of 'component' backing one Constellation Token base unit, None if it isn't in the basket
+    pub fn unit_of(e: Env, component: Address) -> Option<u32> {
+        require_initialized(&e);
+        read_unit(&e, &component)
+    }
+
+    // Decimals of each component token, in getComponents() order, for converting units to whole tokens
+    pub fn component_decimals(e: Env) -> Vec<u32> {
+        require_initialized(&e);
+        read_component_decimals(&e)
+    }
+
+    // Halt minting while 'component' has a problem, e.g. a depeg; redemptions stay open
+    pub fn pause_component(e: Env, component: Address) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        write_component_paused(&e, component, true);
+    }
+
+    pub fn unpause_component(e: Env, component: Address) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        write_component_paused(&e, component, false);
+    }
+
+    // Components 'holder' would receive by redeeming their entire balance at the current units
+    // Empty when the holder has no balance
+    pub fn my_redeemable(e: Env, holder: Address) -> Vec<(Address, i128)> {
+        require_initialized(&e);
+        let mut redeemable = Vec::new(&e);
+        let balance = read_balance(&e, holder);
+        if balance == 0 {
+            return redeemable;
+        }
+
+        let components = read_components(&e);
+        let amounts = read_amounts(&e);
+        for i in 0..components.len() {
+            redeemable.push_back((
+                components.get(i).unwrap(),
+                balance * amounts.get(i).unwrap() as i128,
+            ));
+        }
+        redeemable
+    }
+
+    // Queue a rebalance for the manager or operator to start, see auction.rs
+    // target_components must include every current component; list a component with a target amount of 0 to remove it
+    pub fn propose_rebalance(
+        e: Env,
+        target_components: Vec<Address>,
+        target_amounts: Vec<u32>,
+        auction_params: Vec<AuctionParams>,
+        intermediate_token: Address,
+    ) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        write_proposal(
+            &e,
+            &RebalanceProposal {
+                target_components,
+                target_amounts,
+                auction_params,
+                intermediate_token,
+            },
+        );
+    }
+
+    // Dry run of propose_rebalance() and the auctions it would open, without changing anything, see plan.rs
+    pub fn plan_rebalance(
+        e: Env,
+        target_components: Vec<Address>,
+        target_amounts: Vec<u32>,
+        auction_params: Vec<AuctionParams>,
+        intermediate_token: Address,
+        oracle: Address,
+    ) -> RebalancePlan {
+        require_initialized(&e);
+        plan_rebalance(
+            &e,
+            &RebalanceProposal {
+                target_components,
+                target_amounts,
+                auction_params,
+                intermediate_token,
+            },
+            oracle,
+        )
+    }
+
+    pub fn rebalance_proposal(e: Env) -> Option<RebalanceProposal> {
+        require_initialized(&e);
+        read_proposal(&e)
+    }
+
+    // Start a Dutch auction for every component whose amount changes in the proposed rebalance
+    // 'caller' must be the manager or the operator
+    pub fn start_rebalance(e: Env, caller: Address) {
+        require_initialized(&e);
+        require_executor(&e, &caller);
+
+        bump_instance(&e);
+
+        start_proposed_rebalance(&e);
+    }
+
+    // Trade up to 'amount' of 'component' with this contract at the auction's current price
+    // Returns the amount actually filled
+    // No approve step is needed: the bidder's payment is a transfer() sub-call covered by the same auth tree.
+    // For a buy auction that is transfer(bidder, this contract, fill), so sign it for an 'amount' no larger
+    // than auction_status().remaining, or the capped fill won't match the signed transfer.
+    // A buy auction can also be filled by the component's transfer_and_call() to this contract, see
+    // on_token_transfer()
+    pub fn bid(e: Env, bidder: Address, component: Address, amount: i128) -> i128 {
+        require_initialized(&e);
+        bidder.require_auth();
+        check_nonnegative_amount(amount);
+
+        bump_instance(&e);
+
+        bid(&e, bidder, component, amount)
+    }
+
+    pub fn auction_status(e: Env, component: Address) -> AuctionStatus {
+        require_initialized(&e);
+        auction_status(&e, component)
+    }
+
+    // Give one open auction a new price curve from the current ledger, keeping what it has filled
+    pub fn reprice_auction(e: Env, component: Address, params: AuctionParams) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        reprice_auction(&e, component, params);
+    }
+
+    // Flag the auction for 'component' if its price has decayed to min_price
+    // Emits ("rebalance", "min_price") the first time; returns the flag
+    pub fn check_min_price(e: Env, component: Address) -> bool {
+        require_initialized(&e);
+        bump_instance(&e);
+        check_min_price(&e, component)
+    }
+
+    // Value of one Constellation Token base unit in intermediate token, scaled by 10^7,
+    // derived from live auction prices and the contract's reserves without an external oracle
+    pub fn implied_value(e: Env) -> i128 {
+        require_initialized(&e);
+        implied_value(&e)
+    }
+
+    // Components and amounts when the current rebalance started, None when no rebalance is in progress
+    pub fn rebalance_snapshot(e: Env) -> Option<Vec<(Address, u32)>> {
+        require_initialized(&e);
+        read_rebalance_snapshot(&e)
+    }
+
+    // Set 'component's units without an auction, see manual.rs
+    // Raising units requires this contract to already hold total_supply * new_unit of the component beyond
+    // queued claims; lowering them sweeps the freed backing as dust
+    pub fn set_unit(e: Env, component: Address, new_unit: u32) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        set_unit(&e, component, new_unit);
+    }
+
+    // set_unit() with the extra backing deposited by 'manager' in the same call
+    pub fn rebalance_manual(
+        e: Env,
+        manager: Address,
+        component: Address,
+        new_unit: u32,
+        deposit_amount: i128,
+    ) {
+        require_initialized(&e);
+        if manager != read_manager(&e) {
+            panic_with_error!(&e, Error::NotAuthorized);
+        }
+        manager.require_auth();
+        check_nonnegative_amount(deposit_amount);
+
+        bump_instance(&e);
+
+        rebalance_manual(&e, component, new_unit, deposit_amount);
+    }
+
+    // Raise units to what the held reserve backs, see manual.rs
+    pub fn reconcile(e: Env) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        reconcile(&e);
+    }
+
+    // Close all auctions, keeping the amounts reached so far
+    pub fn finalize_rebalance(e: Env) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        finalize_rebalance(&e);
+    }
+
+    // Escape hatch for a misconfigured rebalance: close all auctions and revert to the pre-rebalance amounts
+    pub fn cancel_rebalance(e: Env) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        cancel_rebalance(&e);
+    }
+
+    // Allow 'token' as the intermediate token of future rebalances; admin only, see intermediate.rs
+    pub fn add_intermediate(e: Env, token: Address) {
+        require_initialized(&e);
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        bump_instance(&e);
+
+        add_intermediate(&e, token);
+    }
+
+    // A rebalance already started keeps its intermediate token
+    pub fn remove_intermediate(e: Env, token: Address) {
+        require_initialized(&e);
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        bump_instance(&e);
+
+        remove_intermediate(&e, token);
+    }
+
+    pub fn list_intermediates(e: Env) -> Vec<Address> {
+        require_initialized(&e);
+        read_intermediates(&e)
+    }
+
+    // Sweep component reserves above the backing requirement to the fee recipient, see dust.rs
+    // Returns the amount swept of each component, in getComponents() order
+    pub fn collect_dust(e: Env) -> Vec<i128> {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        collect_dust(&e)
+    }
+
+    pub fn set_dust_epsilon(e: Env, epsilon: i128) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        write_dust_epsilon(&e, epsilon);
+    }
+
+    // Renames the product, e.g. after a methodology change; decimals are fixed at initialize
+    pub fn set_metadata(e: Env, name: String, symbol: String) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        check_metadata(&e, &name, &symbol);
+        let old_name = read_name(&e);
+        let old_symbol = read_symbol(&e);
+        write_metadata(
+            &e,
+            TokenMetadata {
+                decimal: read_decimal(&e),
+                name: name.clone(),
+                symbol: symbol.clone(),
+            },
+        );
+        emit_metadata_updated(&e, old_name, old_symbol, name, symbol);
+    }
+
+    pub fn dust_epsilon(e: Env) -> i128 {
+        require_initialized(&e);
+        read_dust_epsilon(&e)
+    }
+
+    // Epoch of the current or most recent rebalance, 0 before the first one
+    pub fn rebalance_epoch(e: Env) -> u32 {
+        require_initialized(&e);
+        read_epoch(&e)
+    }
+
+    // What each auction of rebalance 'epoch' traded, available once it is finalized or cancelled
+    pub fn get_execution_report(e: Env, epoch: u32) -> Vec<ExecutionLeg> {
+        require_initialized(&e);
+        read_execution_report(&e, epoch)
+    }
+
+    // Price source for nav_per_token; quotes older than 'max_price_age_ledgers' are rejected
+    pub fn set_oracle(e: Env, oracle: Address, max_price_age_ledgers: u32) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        write_oracle_config(
+            &e,
+            &OracleConfig {
+                oracle,
+                max_price_age_ledgers,
+            },
+        );
+    }
+
+    pub fn get_oracle(e: Env) -> Option<OracleConfig> {
+        require_initialized(&e);
+        read_oracle_config(&e)
+    }
+
+    // Value of one Constellation Token base unit in the oracle's quote asset, scaled by 10^7
+    // Traps with StalePrice if any component's quote is too old
+    pub fn nav_per_token(e: Env) -> i128 {
+        require_initialized(&e);
+        nav_per_token(&e)
+    }
+
+    // Performance fee in basis points of NAV gains above the high-water mark, at most 2000
+    pub fn set_performance_fee(e: Env, bps: u32) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+        // Settle gains made under the old rate first
+        accrue_fee(&e);
+        write_performance_fee(&e, bps);
+    }
+
+    pub fn performance_fee(e: Env) -> u32 {
+        require_initialized(&e);
+        read_performance_fee(&e)
+    }
+
+    // Where performance fees are minted and collect_dust() sends its sweeps, the manager until set
+    pub fn set_fee_recipient(e: Env, recipient: Address) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+        // Gains made so far are owed to the old recipient
+        accrue_fee(&e);
+        write_fee_recipient(&e, &recipient);
+    }
+
+    pub fn fee_recipient(e: Env) -> Address {
+        require_initialized(&e);
+        read_fee_recipient(&e)
+    }
+
+    pub fn high_water_mark(e: Env) -> i128 {
+        require_initialized(&e);
+        read_high_water_mark(&e)
+    }
+
+    // Anyone can trigger accrual; returns the ctokens minted to the fee recipient, see fee.rs
+    pub fn accrue_fee(e: Env) -> i128 {
+        require_initialized(&e);
+        bump_instance(&e);
+        accrue_fee(&e)
+    }
+
+    // Intrinsic price of one Constellation Token base unit in 'quote', scaled by 10^7
+    // Sum of units * component price in 'quote' from 'oracle', ignoring held balances
+    // Quotes older than the configured max_price_age_ledgers trap with StalePrice, see oracle.rs
+    pub fn implied_price(e: Env, quote: Address, oracle: Address) -> i128 {
+        require_initialized(&e);
+        implied_price(&e, quote, &oracle)
+    }
+
+    // How far 'market_price' (in 'quote', scaled by 10^7) is above (+) or below (-) implied_price(),
+    // in basis points. Arbitrageurs mint when positive and redeem when negative
+    pub fn premium_discount_bps(e: Env, market_price: i128, quote: Address, oracle: Address) -> i128 {
+        require_initialized(&e);
+        premium_discount_bps(&e, market_price, quote, &oracle)
+    }
+
+    // Each component's share of NAV in basis points, priced by 'oracle'
+    // Rounded down, so the weights sum to at most 10000. Traps with StalePrice like nav_per_token()
+    pub fn get_weights(e: Env, oracle: Address) -> Vec<u32> {
+        require_initialized(&e);
+        weights(&e, read_amounts(&e), &oracle)
+    }
+
+    // Weights the basket will have once the current rebalance completes, at today's prices
+    pub fn get_target_weights(e: Env, oracle: Address) -> Vec<u32> {
+        require_initialized(&e);
+        weights(&e, read_target_amounts(&e), &oracle)
+    }
+
+    // Record the current ledger and total supply for governance votes, returns the snapshot id
+    pub fn snapshot(e: Env) -> u32 {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        take_snapshot(&e)
+    }
+
+    pub fn latest_snapshot(e: Env) -> Option<Snapshot> {
+        require_initialized(&e);
+        read_snapshot(&e)
+    }
+
+    // Balance of 'id' when 'snapshot_id' was taken, for any snapshot taken so far
+    // Traps with SnapshotUnavailable for an id snapshot() hasn't returned
+    pub fn balance_at(e: Env, id: Address, snapshot_id: u32) -> i128 {
+        require_initialized(&e);
+        let balance = read_balance(&e, id.clone());
+        read_balance_at(&e, id, snapshot_id, balance)
+    }
+
+    // balance_at() for callers keeping u64 snapshot ids
+    pub fn balance_at_snapshot(e: Env, account: Address, id: u64) -> i128 {
+        require_initialized(&e);
+        let balance = read_balance(&e, account.clone());
+        read_balance_at(&e, account, snapshot_id(&e, id), balance)
+    }
+
+    // Total supply when 'snapshot_id' was taken, the denominator for pro-rata shares of balance_at()
+    // Traps with SnapshotUnavailable for an id snapshot() hasn't returned
+    pub fn total_supply_at(e: Env, snapshot_id: u32) -> i128 {
+        require_initialized(&e);
+        read_total_supply_at(&e, snapshot_id)
+    }
+
+    // total_supply_at() for callers keeping u64 snapshot ids
+    pub fn total_supply_at_snapshot(e: Env, id: u64) -> i128 {
+        require_initialized(&e);
+        read_total_supply_at(&e, snapshot_id(&e, id))
+    }
+
+    // The operator can only start rebalances the manager proposed
+    pub fn set_operator(e: Env, operator: Address) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+        write_operator(&e, &operator);
+    }
+
+    pub fn get_operator(e: Env) -> Option<Address> {
+        require_initialized(&e);
+        read_operator(&e)
+    }
+
+    // Change how far every call extends the instance TTL
+    pub fn set_ttl_config(e: Env, threshold: u32, bump_amount: u32) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        write_ttl_config(
+            &e,
+            &TtlConfig {
+                threshold,
+                bump_amount,
+            },
+        );
+        bump_instance(&e);
+    }
+
+    pub fn ttl_config(e: Env) -> TtlConfig {
+        require_initialized(&e);
+        read_ttl_config(&e)
+    }
+
+    // Permissionless maintenance: extend the TTL of the instance and of any open auctions
+    pub fn extend_ttl(e: Env) {
+        require_initialized(&e);
+        bump_instance(&e);
+        bump_auctions(&e);
+    }
+
+    // The admin is always the Constellation Minter Burner contract
+    pub fn minter_burner(e: Env) -> Address {
+        require_initialized(&e);
+        read_administrator(&e)
+    }
+
+    // Alias of minter_burner()
+    pub fn get_admin(e: Env) -> Address {
+        require_initialized(&e);
+        read_administrator(&e)
+    }
+
+    // Sequence number of the last Constellation event this token published, see constellation_shared::events
+    pub fn get_event_seq(e: Env) -> u64 {
+        require_initialized(&e);
+        read_event_seq(&e)
+    }
+
+    // Block transfers from 'account' and mints to it until unfrozen; it can still receive and burn, see balance.rs
+    pub fn freeze_account(e: Env, account: Address) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        write_frozen(&e, account, true);
+    }
+
+    pub fn unfreeze_account(e: Env, account: Address) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        write_frozen(&e, account, false);
+    }
+
+    pub fn is_frozen(e: Env, account: Address) -> bool {
+        require_initialized(&e);
+        is_frozen(&e, account)
+    }
+
+    // Bar 'id' from minting and transfers, see denylist.rs; manager only
+    pub fn add_to_denylist(e: Env, id: Address) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        add_to_denylist(&e, id);
+    }
+
+    pub fn remove_from_denylist(e: Env, id: Address) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        remove_from_denylist(&e, id);
+    }
+
+    pub fn denylist(e: Env) -> Vec<Address> {
+        require_initialized(&e);
+        read_denylist(&e)
+    }
+
+    // transfer() that then calls on_token_transfer(from, amount, data) on 'to' if it is a contract,
+    // see constellation_shared::receiver; a trapping callback reverts the transfer
+    pub fn transfer_and_call(e: Env, from: Address, to: Address, amount: i128, data: Bytes) {
+        require_initialized(&e);
+        from.require_auth();
+
+        check_nonnegative_amount(amount);
+
+        bump_instance(&e);
+
+        check_not_denied(&e, &from);
+        check_not_denied(&e, &to);
+        check_not_frozen(&e, &from);
+        check_transfer_hook(&e, &from, &to, amount);
+        spend_balance(&e, from.clone(), amount);
+        receive_balance(&e, to.clone(), amount);
+        TokenUtils::new(&e)
+            .events()
+            .transfer(from.clone(), to.clone(), amount);
+
+        if is_contract(&e, &to) {
+            TokenReceiverClient::new(&e, &to).on_token_transfer(&from, &amount, &data);
         }
     }
 
+    // Contract asked to approve every transfer and transfer_from, see hook.rs; None removes it
+    pub fn set_transfer_hook(e: Env, hook: Option<Address>) {
+        require_initialized(&e);
+        let manager = read_manager(&e);
+        manager.require_auth();
+
+        bump_instance(&e);
+
+        write_transfer_hook(&e, hook);
+    }
+
+    pub fn transfer_hook(e: Env) -> Option<Address> {
+        require_initialized(&e);
+        read_transfer_hook(&e)
+    }
+
+    // Every configuration value of this token, for auditors, see config.rs
+    pub fn dump_config(e: Env) -> ConfigDump {
+        require_initialized(&e);
+        read_config(&e)
+    }
+
+    // See INTERFACE_VERSION; a constant, so it reads no storage and works before initialize
+    pub fn interface_version(_e: Env) -> u32 {
+        INTERFACE_VERSION
+    }
+
+    // Address component transfers for this token must go to, e.g. when reached through a proxy
+    pub fn self_address(e: Env) -> Address {
+        require_initialized(&e);
+        e.current_contract_address()
+    }
+
     // For future use: Allow the Constellation Token manager way to upgrade the associated MinterBurner contract
     // Initially will be disabled
     pub fn set_admin(e: Env, new_admin: Address) {
-        let manager = read_manager(&e); // TODO: read_manager Implementation
+        require_initialized(&e);
+        let manager = read_manager(&e);
         manager.require_auth();
 
-        e.storage()
-            .instance()
-            .bump(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        bump_instance(&e);
         // Validate that the new admin is an instance of the Constellation Minter Burner contract
+        // The new MinterBurner checks that it is this token's admin when the token is registered with add_ctoken()
+        let admin = read_administrator(&e);
         write_administrator(&e, &new_admin);
         TokenUtils::new(&e).events().set_admin(admin, new_admin);
     }
 }
 
+#[contractimpl]
+impl TokenReceiver for ConstellationToken {
+    // Fill a buy auction through the component's transfer_and_call(), see bid_with_transfer()
+    // 'data' is the XDR of a BidData; traps with InvalidBidData if it holds another value.
+    // require_auth() on a contract address succeeds only when that contract is the direct invoker, so only
+    // the component itself can report a transfer of it: a direct call with a made-up amount traps.
+    fn on_token_transfer(e: Env, from: Address, amount: i128, data: Bytes) {
+        require_initialized(&e);
+        let bid_data = match BidData::from_xdr(&e, &data) {
+            Ok(bid_data) => bid_data,
+            Err(_) => panic_with_error!(&e, Error::InvalidBidData),
+        };
+        bid_data.component.require_auth();
+        check_nonnegative_amount(amount);
+
+        bump_instance(&e);
+
+        bid_with_transfer(&e, from, bid_data.component, amount);
+    }
+}
+
 // End of ConstellationToken pseudocode
 // Most of below is unchanged from from "Token" example
 
 #[contractimpl]
 impl token::Interface for ConstellationToken {
     fn allowance(e: Env, from: Address, spender: Address) -> i128 {
-        e.storage()
-            .instance()
-            .bump(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        require_initialized(&e);
         read_allowance(&e, from, spender).amount
     }
 
+    // Approving i128::MAX grants an infinite allowance: transfer_from and burn_from
+    // leave it untouched until it expires or is replaced
+    // A past 'expiration_ledger' traps with ExpiredApproval unless 'amount' is 0, which clears the allowance
     fn approve(e: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        require_initialized(&e);
         from.require_auth();
 
         check_nonnegative_amount(amount);
 
-        e.storage()
-            .instance()
-            .bump(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        bump_instance(&e);
 
         write_allowance(&e, from.clone(), spender.clone(), amount, expiration_ledger);
         TokenUtils::new(&e)
@@ -171,71 +1176,99 @@ impl token::Interface for ConstellationToken {
     }
 
     fn balance(e: Env, id: Address) -> i128 {
-        e.storage()
-            .instance()
-            .bump(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        require_initialized(&e);
         read_balance(&e, id)
     }
 
     fn spendable_balance(e: Env, id: Address) -> i128 {
-        e.storage()
-            .instance()
-            .bump(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        require_initialized(&e);
         read_balance(&e, id)
     }
 
     fn transfer(e: Env, from: Address, to: Address, amount: i128) {
+        require_initialized(&e);
         from.require_auth();
 
         check_nonnegative_amount(amount);
 
-        e.storage()
-            .instance()
-            .bump(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        bump_instance(&e);
 
+        check_not_denied(&e, &from);
+        check_not_denied(&e, &to);
+        check_not_frozen(&e, &from);
+        check_transfer_hook(&e, &from, &to, amount);
         spend_balance(&e, from.clone(), amount);
         receive_balance(&e, to.clone(), amount);
         TokenUtils::new(&e).events().transfer(from, to, amount);
     }
 
     fn transfer_from(e: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        require_initialized(&e);
         spender.require_auth();
 
         check_nonnegative_amount(amount);
 
-        e.storage()
-            .instance()
-            .bump(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        bump_instance(&e);
 
+        check_not_denied(&e, &from);
+        check_not_denied(&e, &to);
+        check_not_frozen(&e, &from);
+        check_transfer_hook(&e, &from, &to, amount);
         spend_allowance(&e, from.clone(), spender, amount);
         spend_balance(&e, from.clone(), amount);
         receive_balance(&e, to.clone(), amount);
         TokenUtils::new(&e).events().transfer(from, to, amount)
     }
 
+    fn burn(e: Env, from: Address, amount: i128) {
+        require_initialized(&e);
+        // Burning directly does not release any components
+        // Redemption goes through the burn() function of the Constellation Minter Burner contract,
+        // which calls burn_from() and then release()
+        from.require_auth();
+        check_nonnegative_amount(amount);
+        check_burn_enabled(&e);
+
+        bump_instance(&e);
+
+        spend_balance(&e, from.clone(), amount);
+        decrease_total_supply(&e, amount);
+        TokenUtils::new(&e).events().burn(from, amount);
+
+        #[cfg(feature = "solvency-check")]
+        assert_solvent(&e);
+    }
+
     fn burn_from(e: Env, spender: Address, from: Address, amount: i128) {
+        require_initialized(&e);
         spender.require_auth();
 
         check_nonnegative_amount(amount);
+        check_burn_enabled(&e);
 
-        e.storage()
-            .instance()
-            .bump(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        bump_instance(&e);
 
         spend_allowance(&e, from.clone(), spender, amount);
         spend_balance(&e, from.clone(), amount);
-        TokenUtils::new(&e).events().burn(from, amount)
+        decrease_total_supply(&e, amount);
+        TokenUtils::new(&e).events().burn(from, amount);
+
+        #[cfg(feature = "solvency-check")]
+        assert_solvent(&e);
     }
 
     fn decimals(e: Env) -> u32 {
+        require_initialized(&e);
         read_decimal(&e)
     }
 
     fn name(e: Env) -> String {
+        require_initialized(&e);
         read_name(&e)
     }
 
     fn symbol(e: Env) -> String {
+        require_initialized(&e);
         read_symbol(&e)
     }
 }
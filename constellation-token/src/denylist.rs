@@ -0,0 +1,46 @@
+// Addresses barred from receiving or moving this token, kept by the manager for basic compliance needs
+// where a full transfer hook (hook.rs) is more than required. Mint, transfer and transfer_from trap with
+// Denied if either party is listed. Burns and redemption stay open, so a listed holder can still exit
+// through the MinterBurner unless the manager also sets a hook.
+// The list is kept in instance storage, so it is capped to stay small.
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+use crate::error::Error;
+use crate::storage_types::DataKey;
+
+pub(crate) const MAX_DENYLIST_SIZE: u32 = 50;
+
+pub fn read_denylist(e: &Env) -> Vec<Address> {
+    let key = DataKey::Denylist;
+    e.storage().instance().get(&key).unwrap_or(Vec::new(e))
+}
+
+fn write_denylist(e: &Env, denylist: &Vec<Address>) {
+    let key = DataKey::Denylist;
+    e.storage().instance().set(&key, denylist);
+}
+
+pub fn add_to_denylist(e: &Env, id: Address) {
+    let mut denylist = read_denylist(e);
+    if !denylist.contains(&id) {
+        if denylist.len() >= MAX_DENYLIST_SIZE {
+            panic!("denylist is full")
+        }
+        denylist.push_back(id);
+        write_denylist(e, &denylist);
+    }
+}
+
+pub fn remove_from_denylist(e: &Env, id: Address) {
+    let mut denylist = read_denylist(e);
+    if let Some(index) = denylist.first_index_of(&id) {
+        denylist.remove(index);
+        write_denylist(e, &denylist);
+    }
+}
+
+pub fn check_not_denied(e: &Env, id: &Address) {
+    if read_denylist(e).contains(id) {
+        panic_with_error!(e, Error::Denied);
+    }
+}
@@ -0,0 +1,67 @@
+// Rounding in redemptions leaves small amounts of components that back no Constellation Token.
+// collect_dust() sends each component's reserve above total_supply * units, less the components the
+// MinterBurner holds here for queued claims and a safety epsilon, to the fee recipient (see fee.rs).
+// Blocked during a rebalance, while units are moving.
+use constellation_shared::claims::ClaimsClient;
+use constellation_shared::events::emit_dust_collected;
+use soroban_sdk::{panic_with_error, token, Address, Env, Vec};
+
+use crate::admin::read_administrator;
+use crate::auction::read_rebalance;
+use crate::component::{read_amounts, read_components};
+use crate::error::Error;
+use crate::fee::read_fee_recipient;
+use crate::storage_types::DataKey;
+use crate::supply::read_total_supply;
+
+// Base units of each component always left above the requirement, 0 by default
+pub fn read_dust_epsilon(e: &Env) -> i128 {
+    let key = DataKey::DustEpsilon;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn write_dust_epsilon(e: &Env, epsilon: i128) {
+    if epsilon < 0 {
+        panic!("negative epsilon is not allowed")
+    }
+    let key = DataKey::DustEpsilon;
+    e.storage().instance().set(&key, &epsilon);
+}
+
+// Returns the amount swept of each component, in getComponents() order
+pub fn collect_dust(e: &Env) -> Vec<i128> {
+    if read_rebalance(e).is_some() {
+        panic_with_error!(e, Error::RebalanceInProgress);
+    }
+    let components = read_components(e);
+    let amounts = read_amounts(e);
+
+    let mut swept = Vec::new(e);
+    for i in 0..components.len() {
+        swept.push_back(sweep_dust(
+            e,
+            components.get(i).unwrap(),
+            amounts.get(i).unwrap(),
+        ));
+    }
+    swept
+}
+
+// Sends 'component's reserve above total_supply * 'units', queued claims and the epsilon to the fee recipient
+// Returns the amount swept
+pub fn sweep_dust(e: &Env, component: Address, units: u32) -> i128 {
+    let contract = e.current_contract_address();
+    let claims = ClaimsClient::new(e, &read_administrator(e));
+    let client = token::Client::new(e, &component);
+    let required = read_total_supply(e) * units as i128
+        + claims.queued_claims(&contract, &component)
+        + read_dust_epsilon(e);
+    let dust = client.balance(&contract) - required;
+    if dust <= 0 {
+        return 0;
+    }
+    let recipient = read_fee_recipient(e);
+    client.transfer(&contract, &recipient, &dust);
+    emit_dust_collected(e, component, recipient, dust);
+    dust
+}
@@ -0,0 +1,41 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    Insolvent = 1,
+    InvalidDecimals = 2,
+    RebalanceInProgress = 3,
+    NoRebalance = 4,
+    NoAuction = 5,
+    AuctionFilled = 6,
+    SnapshotUnavailable = 7,
+    NoProposal = 8,
+    NotAuthorized = 9,
+    NoOracle = 10,
+    StalePrice = 11,
+    Undercollateralized = 12,
+    NotInitialized = 13,
+    NoReport = 14,
+    MintDisabled = 15,
+    BurnDisabled = 16,
+    IntermediateNotAllowed = 17,
+    NoSupply = 18,
+    PriceOutOfRange = 19,
+    BidTooSmall = 20,
+    ExpiredApproval = 21,
+    ConfigTooLarge = 22,
+    TransferRejected = 23,
+    Denied = 24,
+    AccountFrozen = 25,
+    InsufficientAllowance = 26,
+    WindDown = 27,
+    FeeOnTransferNotSupported = 28,
+    InvalidRoles = 29,
+    InvalidComponent = 30,
+    InvalidUnits = 31,
+    InvalidMetadata = 32,
+    BidTooLarge = 33,
+    InvalidBidData = 34,
+}
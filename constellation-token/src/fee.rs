@@ -0,0 +1,131 @@
+// Performance fee charged on NAV gains above the high-water mark.
+// NAV per token comes from the configured oracle (see oracle.rs). When it exceeds the high-water mark,
+// accrue_fee() mints the fee recipient ctokens worth bps of the gain across the whole supply, then raises
+// the mark.
+// The fee is taken by dilution: every component's units are scaled down by supply / (supply + fee),
+// with the fee cut to what whole units can back, see dilute().
+use constellation_shared::events::emit_performance_fee;
+use soroban_sdk::{Address, Env};
+use soroban_token_sdk::TokenUtils;
+
+use crate::auction::read_rebalance;
+use crate::balance::receive_balance;
+use crate::component::{read_amounts, read_components, write_components};
+use crate::manager::read_manager;
+use crate::oracle::{nav_per_token, read_oracle_config, MAX_BPS};
+use crate::storage_types::DataKey;
+use crate::supply::{increase_total_supply, read_total_supply};
+use crate::winddown::is_winding_down;
+
+pub(crate) const MAX_PERFORMANCE_FEE_BPS: u32 = 2_000;
+
+pub fn read_performance_fee(e: &Env) -> u32 {
+    let key = DataKey::PerformanceFee;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn write_performance_fee(e: &Env, bps: u32) {
+    if bps > MAX_PERFORMANCE_FEE_BPS {
+        panic!("performance fee is capped at 20%")
+    }
+    let key = DataKey::PerformanceFee;
+    e.storage().instance().set(&key, &bps);
+}
+
+// Receives the performance fee and collect_dust() sweeps, the manager unless set_fee_recipient() was called
+pub fn read_fee_recipient(e: &Env) -> Address {
+    let key = DataKey::FeeRecipient;
+    match e.storage().instance().get(&key) {
+        Some(recipient) => recipient,
+        None => read_manager(e),
+    }
+}
+
+pub fn write_fee_recipient(e: &Env, recipient: &Address) {
+    let key = DataKey::FeeRecipient;
+    e.storage().instance().set(&key, recipient);
+}
+
+// NAV per token the next fee is measured from, 0 until the first accrual
+pub fn read_high_water_mark(e: &Env) -> i128 {
+    let key = DataKey::HighWaterMark;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+fn write_high_water_mark(e: &Env, nav: i128) {
+    let key = DataKey::HighWaterMark;
+    e.storage().instance().set(&key, &nav);
+}
+
+// Returns the ctokens minted to the fee recipient
+// No-op without an oracle, during a rebalance because units are being moved by the auctions,
+// and for good once the wind-down has taken effect
+pub fn accrue_fee(e: &Env) -> i128 {
+    if read_oracle_config(e).is_none() || read_rebalance(e).is_some() || is_winding_down(e) {
+        return 0;
+    }
+    let nav = nav_per_token(e);
+    let high_water_mark = read_high_water_mark(e);
+    if nav <= high_water_mark {
+        return 0;
+    }
+
+    let supply = read_total_supply(e);
+    let bps = read_performance_fee(e) as i128;
+    // The first accrual only sets the mark
+    if high_water_mark == 0 || supply == 0 || bps == 0 {
+        write_high_water_mark(e, nav);
+        return 0;
+    }
+
+    let fee = (nav - high_water_mark) * supply * bps / MAX_BPS / nav;
+    let fee = dilute(e, supply, fee);
+    if fee == 0 {
+        // Too small to take with whole units; the gain stays above the mark for a later accrual
+        return 0;
+    }
+
+    let recipient = read_fee_recipient(e);
+    receive_balance(e, recipient.clone(), fee);
+    increase_total_supply(e, fee);
+    TokenUtils::new(e)
+        .events()
+        .mint(e.current_contract_address(), recipient.clone(), fee);
+    emit_performance_fee(e, recipient, fee);
+    // Measured after dilution, so the same gain is never charged twice
+    write_high_water_mark(e, nav_per_token(e));
+    fee
+}
+
+// Scales every component's units down for a fee of at most 'fee' on 'supply', and returns the fee the
+// new units back exactly. Units are rounded up first so none reaches 0 and holders are never diluted
+// by more than the fee; the fee is then cut to what the rounded units allow, and the units recomputed
+// for that fee. What remains unbacked is the rounding, under one unit per ctoken, and goes to dust.
+// Returns 0 without touching the units when no whole fee fits, e.g. a component with 1 unit.
+fn dilute(e: &Env, supply: i128, fee: i128) -> i128 {
+    if fee <= 0 {
+        return 0;
+    }
+    let components = read_components(e);
+    let mut amounts = read_amounts(e);
+
+    let mut actual = fee;
+    for units in amounts.iter() {
+        if units == 0 {
+            continue;
+        }
+        let units = units as i128;
+        let diluted = (units * supply + supply + fee - 1) / (supply + fee);
+        actual = actual.min(units * supply / diluted - supply);
+    }
+    if actual <= 0 {
+        return 0;
+    }
+
+    for i in 0..amounts.len() {
+        let units = amounts.get(i).unwrap() as i128;
+        amounts.set(i, (units * supply / (supply + actual)) as u32);
+    }
+    write_components(e, components, amounts);
+    actual
+}
@@ -0,0 +1,29 @@
+// Optional transfer restriction for regulated products, see constellation_shared::hook
+// Unset by default, so transfers are unrestricted. Mint, burn and redemption are not checked.
+use constellation_shared::hook::TransferHookClient;
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::error::Error;
+use crate::storage_types::DataKey;
+
+pub fn read_transfer_hook(e: &Env) -> Option<Address> {
+    let key = DataKey::TransferHook;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_transfer_hook(e: &Env, hook: Option<Address>) {
+    let key = DataKey::TransferHook;
+    match hook {
+        Some(hook) => e.storage().instance().set(&key, &hook),
+        None => e.storage().instance().remove(&key),
+    }
+}
+
+// Traps with TransferRejected if the hook refuses the transfer
+pub fn check_transfer_hook(e: &Env, from: &Address, to: &Address, amount: i128) {
+    if let Some(hook) = read_transfer_hook(e) {
+        if !TransferHookClient::new(e, &hook).check_transfer(from, to, &amount) {
+            panic_with_error!(e, Error::TransferRejected);
+        }
+    }
+}
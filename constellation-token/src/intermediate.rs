@@ -0,0 +1,39 @@
+// Tokens a rebalance may be priced in. The manager picks the intermediate token of each rebalance,
+// but only from this list, which the admin (the MinterBurner, on behalf of the protocol admin) keeps,
+// so a manager can't auction the basket off for a worthless token.
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+use crate::error::Error;
+use crate::storage_types::DataKey;
+
+pub fn read_intermediates(e: &Env) -> Vec<Address> {
+    let key = DataKey::Intermediates;
+    e.storage().instance().get(&key).unwrap_or(Vec::new(e))
+}
+
+fn write_intermediates(e: &Env, intermediates: &Vec<Address>) {
+    let key = DataKey::Intermediates;
+    e.storage().instance().set(&key, intermediates);
+}
+
+pub fn add_intermediate(e: &Env, token: Address) {
+    let mut intermediates = read_intermediates(e);
+    if !intermediates.contains(&token) {
+        intermediates.push_back(token);
+        write_intermediates(e, &intermediates);
+    }
+}
+
+pub fn remove_intermediate(e: &Env, token: Address) {
+    let mut intermediates = read_intermediates(e);
+    if let Some(index) = intermediates.first_index_of(&token) {
+        intermediates.remove(index);
+        write_intermediates(e, &intermediates);
+    }
+}
+
+pub fn check_intermediate(e: &Env, token: &Address) {
+    if !read_intermediates(e).contains(token) {
+        panic_with_error!(e, Error::IntermediateNotAllowed);
+    }
+}
@@ -2,9 +2,31 @@
 
 mod admin;
 mod allowance;
+mod auction;
 mod balance;
+mod component;
+mod config;
 mod contract;
+mod denylist;
+mod dust;
+mod error;
+mod fee;
+mod hook;
+mod intermediate;
+mod manager;
+mod manual;
 mod metadata;
+mod oracle;
+mod plan;
+mod report;
+mod snapshot;
+mod solvency;
 mod storage_types;
+mod supply;
+mod switches;
+mod test;
+mod ttl;
+mod winddown;
 
 pub use crate::contract::ConstellationTokenClient;
+pub use crate::error::Error;
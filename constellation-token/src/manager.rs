@@ -0,0 +1,34 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::error::Error;
+use crate::storage_types::DataKey;
+
+pub fn read_manager(e: &Env) -> Address {
+    let key = DataKey::Manager;
+    e.storage().instance().get(&key).unwrap()
+}
+
+pub fn write_manager(e: &Env, id: &Address) {
+    let key = DataKey::Manager;
+    e.storage().instance().set(&key, id);
+}
+
+// The operator can execute rebalances the manager already proposed, nothing else
+pub fn read_operator(e: &Env) -> Option<Address> {
+    let key = DataKey::Operator;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_operator(e: &Env, id: &Address) {
+    let key = DataKey::Operator;
+    e.storage().instance().set(&key, id);
+}
+
+// Require auth from 'caller', which must be the manager or the operator
+pub fn require_executor(e: &Env, caller: &Address) {
+    let is_operator = read_operator(e).is_some_and(|operator| operator == *caller);
+    if *caller != read_manager(e) && !is_operator {
+        panic_with_error!(e, Error::NotAuthorized);
+    }
+    caller.require_auth();
+}
@@ -0,0 +1,95 @@
+// Manual rebalancing: the manager sets a component's units directly instead of running an auction.
+// Raising units needs the extra backing to be held already (or deposited with rebalance_manual()), on top of
+// the components held for the MinterBurner's queued claims. Lowering them turns the freed backing into dust,
+// which is swept right away the way collect_dust() does it, so queued claims and the dust epsilon stay in
+// the reserve.
+// reconcile() instead raises every component's units to what the reserve actually backs, handing
+// surplus from rounding or donations to holders.
+use constellation_shared::claims::ClaimsClient;
+use constellation_shared::events::{emit_reconcile, emit_unit_updated};
+use soroban_sdk::{panic_with_error, token, Address, Env};
+
+use crate::admin::read_administrator;
+use crate::auction::read_rebalance;
+use crate::component::{read_amounts, read_components, write_components};
+use crate::dust::sweep_dust;
+use crate::error::Error;
+use crate::manager::read_manager;
+use crate::supply::read_total_supply;
+
+pub fn set_unit(e: &Env, component: Address, new_unit: u32) {
+    if read_rebalance(e).is_some() {
+        panic_with_error!(e, Error::RebalanceInProgress);
+    }
+    let components = read_components(e);
+    let mut amounts = read_amounts(e);
+    let index = match components.first_index_of(&component) {
+        Some(index) => index,
+        None => panic!("not a component"),
+    };
+    let old_unit = amounts.get(index).unwrap();
+    amounts.set(index, new_unit);
+    write_components(e, components, amounts);
+
+    if new_unit > old_unit {
+        let contract = e.current_contract_address();
+        let claims = ClaimsClient::new(e, &read_administrator(e));
+        let reserve = token::Client::new(e, &component).balance(&contract)
+            - claims.queued_claims(&contract, &component);
+        if reserve < read_total_supply(e) * new_unit as i128 {
+            panic_with_error!(e, Error::Undercollateralized);
+        }
+    } else if new_unit < old_unit {
+        sweep_dust(e, component.clone(), new_unit);
+    }
+    emit_unit_updated(e, component, old_unit, new_unit);
+}
+
+// Deposit 'deposit_amount' of 'component' from the manager, then set its units
+// Traps with Undercollateralized if the deposit doesn't cover total_supply * new_unit
+pub fn rebalance_manual(e: &Env, component: Address, new_unit: u32, deposit_amount: i128) {
+    if deposit_amount > 0 {
+        token::Client::new(e, &component).transfer(
+            &read_manager(e),
+            &e.current_contract_address(),
+            &deposit_amount,
+        );
+    }
+    set_unit(e, component, new_unit);
+}
+
+// Set every component's units to floor(reserve / total_supply), where the reserve excludes components
+// held for the MinterBurner's queued claims
+// Traps with NoSupply when nothing is issued, and with Undercollateralized rather than lower units
+pub fn reconcile(e: &Env) {
+    if read_rebalance(e).is_some() {
+        panic_with_error!(e, Error::RebalanceInProgress);
+    }
+    let supply = read_total_supply(e);
+    if supply == 0 {
+        panic_with_error!(e, Error::NoSupply);
+    }
+    let contract = e.current_contract_address();
+    let claims = ClaimsClient::new(e, &read_administrator(e));
+    let components = read_components(e);
+    let mut amounts = read_amounts(e);
+    for i in 0..components.len() {
+        let component = components.get(i).unwrap();
+        let reserve = token::Client::new(e, &component).balance(&contract)
+            - claims.queued_claims(&contract, &component);
+        let old_unit = amounts.get(i).unwrap();
+        let backed = reserve / supply;
+        if backed < old_unit as i128 {
+            panic_with_error!(e, Error::Undercollateralized);
+        }
+        let new_unit = match u32::try_from(backed) {
+            Ok(unit) => unit,
+            Err(_) => panic!("units overflow"),
+        };
+        if new_unit != old_unit {
+            amounts.set(i, new_unit);
+            emit_reconcile(e, component, old_unit, new_unit);
+        }
+    }
+    write_components(e, components, amounts);
+}
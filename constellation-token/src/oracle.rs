@@ -0,0 +1,109 @@
+// NAV from an external price oracle, see constellation_shared::oracle
+// The manager configures the oracle and how old a quote may be before price-dependent functions reject it.
+use constellation_shared::oracle::{OracleClient, PRICE_SCALE};
+use soroban_sdk::{contracttype, panic_with_error, Address, Env, Vec};
+
+use crate::component::{read_amounts, read_components};
+use crate::error::Error;
+use crate::storage_types::DataKey;
+
+pub(crate) const MAX_BPS: i128 = 10_000;
+
+#[derive(Clone)]
+#[contracttype]
+pub struct OracleConfig {
+    pub oracle: Address,
+    pub max_price_age_ledgers: u32,
+}
+
+pub fn read_oracle_config(e: &Env) -> Option<OracleConfig> {
+    let key = DataKey::Oracle;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_oracle_config(e: &Env, config: &OracleConfig) {
+    let key = DataKey::Oracle;
+    e.storage().instance().set(&key, config);
+}
+
+// Price of 'asset' scaled by PRICE_SCALE, trapping with StalePrice if the quote is older than allowed
+pub fn read_price(e: &Env, asset: Address) -> i128 {
+    let config = match read_oracle_config(e) {
+        Some(config) => config,
+        None => panic_with_error!(e, Error::NoOracle),
+    };
+    read_price_from(e, &config.oracle, asset)
+}
+
+// read_price() from a caller-chosen 'oracle', for the read-only analytics that take one
+// The age limit is still the configured max_price_age_ledgers, so these trap with NoOracle until set_oracle() is called
+pub fn read_price_from(e: &Env, oracle: &Address, asset: Address) -> i128 {
+    let config = match read_oracle_config(e) {
+        Some(config) => config,
+        None => panic_with_error!(e, Error::NoOracle),
+    };
+    let quote = OracleClient::new(e, oracle).lastprice(&asset);
+    if e.ledger().sequence().saturating_sub(quote.ledger) > config.max_price_age_ledgers {
+        panic_with_error!(e, Error::StalePrice);
+    }
+    quote.price
+}
+
+// Value of one Constellation Token base unit in the oracle's quote asset, scaled by PRICE_SCALE
+pub fn nav_per_token(e: &Env) -> i128 {
+    let components = read_components(e);
+    let amounts = read_amounts(e);
+    let mut nav = 0;
+    for i in 0..components.len() {
+        let price = read_price(e, components.get(i).unwrap());
+        nav += amounts.get(i).unwrap() as i128 * price;
+    }
+    nav
+}
+
+// Intrinsic price of one Constellation Token base unit in 'quote', scaled by PRICE_SCALE
+// Uses units only, not held balances, so it is what the basket should be worth whatever the reserves
+pub fn implied_price(e: &Env, quote: Address, oracle: &Address) -> i128 {
+    let quote_price = read_price_from(e, oracle, quote);
+    if quote_price <= 0 {
+        panic!("quote asset has no price")
+    }
+    let components = read_components(e);
+    let amounts = read_amounts(e);
+    let mut value = 0;
+    for i in 0..components.len() {
+        let price = read_price_from(e, oracle, components.get(i).unwrap());
+        value += amounts.get(i).unwrap() as i128 * price;
+    }
+    value * PRICE_SCALE / quote_price
+}
+
+// Signed deviation of 'market_price' from implied_price() in basis points, positive for a premium
+// 'market_price' uses the same scale as implied_price(). Returns 0 when the intrinsic price is 0.
+pub fn premium_discount_bps(e: &Env, market_price: i128, quote: Address, oracle: &Address) -> i128 {
+    let intrinsic = implied_price(e, quote, oracle);
+    if intrinsic == 0 {
+        return 0;
+    }
+    (market_price - intrinsic) * MAX_BPS / intrinsic
+}
+
+// Each component's share of the value of 'amounts' in basis points, in getComponents() order
+// Shares are rounded down, so they sum to at most 10000. All zero if the basket has no value.
+pub fn weights(e: &Env, amounts: Vec<u32>, oracle: &Address) -> Vec<u32> {
+    let components = read_components(e);
+    let mut values = Vec::new(e);
+    let mut total = 0;
+    for i in 0..components.len() {
+        let value = amounts.get(i).unwrap() as i128 * read_price_from(e, oracle, components.get(i).unwrap());
+        values.push_back(value);
+        total += value;
+    }
+
+    let mut weights = Vec::new(e);
+    for value in values.iter() {
+        let weight = if total == 0 { 0 } else { value * MAX_BPS / total };
+        weights.push_back(weight as u32);
+    }
+    weights
+}
@@ -0,0 +1,137 @@
+// Dry run of a rebalance proposal, so the manager can check it before queueing it.
+// plan_rebalance() mirrors start_rebalance_auctions() and bid() without writing storage or moving tokens:
+// each leg is the auction the proposal would open, with the intermediate token it trades at start_price
+// and at min_price, and the amount an oracle considers fair.
+use constellation_shared::oracle::PRICE_SCALE;
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::auction::{check_proposal, RebalanceProposal};
+use crate::component::{read_amounts, read_components};
+use crate::oracle::read_price_from;
+use crate::report::Direction;
+use crate::supply::read_total_supply;
+
+#[derive(Clone)]
+#[contracttype]
+pub struct PlanLeg {
+    pub component: Address,
+    pub direction: Direction,
+    pub start_amount: u32,
+    pub target_amount: u32,
+    // Quantity of the component the auction trades
+    pub quantity: i128,
+    // Intermediate token paid out (buy) or received (sell) if the whole quantity fills at each price
+    pub intermediate_at_start: i128,
+    pub intermediate_at_min: i128,
+    // The same at the oracle's prices; 0 if the oracle has no price for the intermediate token
+    pub intermediate_at_oracle: i128,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct RebalancePlan {
+    pub intermediate_token: Address,
+    pub supply: i128,
+    pub legs: Vec<PlanLeg>,
+    // Basket once every auction reaches its target and the rebalance is finalized
+    pub components: Vec<Address>,
+    pub amounts: Vec<u32>,
+    // Intermediate token this contract must hold to settle every leg at its least favourable price:
+    // buys paid at their highest price, less sells received at their lowest. Negative if sells cover the buys.
+    pub worst_case_intermediate: i128,
+}
+
+// Payment for 'quantity' at 'price', rounded as bid() rounds a single fill
+fn payment(direction: Direction, quantity: i128, price: i128) -> i128 {
+    match direction {
+        Direction::Buy => quantity * price / PRICE_SCALE,
+        Direction::Sell => (quantity * price + PRICE_SCALE - 1) / PRICE_SCALE,
+    }
+}
+
+// Plan for 'proposal' against the current basket and supply
+// Panics for proposals write_proposal() would reject; quotes from 'oracle' older than the configured
+// max_price_age_ledgers trap with StalePrice, as they would in bid()
+pub fn plan_rebalance(e: &Env, proposal: &RebalanceProposal, oracle: Address) -> RebalancePlan {
+    check_proposal(e, proposal);
+    let mut components = read_components(e);
+    let mut amounts = read_amounts(e);
+    for component in components.iter() {
+        if !proposal.target_components.contains(&component) {
+            panic!("targets must include every current component")
+        }
+    }
+
+    let intermediate_price = read_price_from(e, &oracle, proposal.intermediate_token.clone());
+    let supply = read_total_supply(e);
+    let mut legs = Vec::new(e);
+    let mut worst_case_intermediate = 0;
+    for i in 0..proposal.target_components.len() {
+        let component = proposal.target_components.get(i).unwrap();
+        let target_amount = proposal.target_amounts.get(i).unwrap();
+        let params = proposal.auction_params.get(i).unwrap();
+
+        let index = match components.first_index_of(&component) {
+            Some(index) => index,
+            None => {
+                components.push_back(component.clone());
+                amounts.push_back(0);
+                components.len() - 1
+            }
+        };
+        let start_amount = amounts.get(index).unwrap();
+        amounts.set(index, target_amount);
+        let quantity = (target_amount as i128 - start_amount as i128).abs() * supply;
+        if quantity == 0 {
+            continue;
+        }
+
+        let direction = if target_amount > start_amount {
+            Direction::Buy
+        } else {
+            Direction::Sell
+        };
+        let intermediate_at_start = payment(direction, quantity, params.start_price);
+        let intermediate_at_min = payment(direction, quantity, params.min_price);
+        let intermediate_at_oracle = if intermediate_price <= 0 {
+            0
+        } else {
+            let price = read_price_from(e, &oracle, component.clone()) * PRICE_SCALE / intermediate_price;
+            payment(direction, quantity, price)
+        };
+        worst_case_intermediate += match direction {
+            Direction::Buy => intermediate_at_start.max(intermediate_at_min),
+            Direction::Sell => -intermediate_at_start.min(intermediate_at_min),
+        };
+        legs.push_back(PlanLeg {
+            component,
+            direction,
+            start_amount,
+            target_amount,
+            quantity,
+            intermediate_at_start,
+            intermediate_at_min,
+            intermediate_at_oracle,
+        });
+    }
+
+    // finalize_rebalance() drops components whose amount reached 0
+    let mut kept_components = Vec::new(e);
+    let mut kept_amounts = Vec::new(e);
+    for i in 0..components.len() {
+        let amount = amounts.get(i).unwrap();
+        if amount > 0 {
+            kept_components.push_back(components.get(i).unwrap());
+            kept_amounts.push_back(amount);
+        }
+    }
+
+    RebalancePlan {
+        intermediate_token: proposal.intermediate_token.clone(),
+        supply,
+        legs,
+        components: kept_components,
+        amounts: kept_amounts,
+        worst_case_intermediate,
+    }
+}
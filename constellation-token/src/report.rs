@@ -0,0 +1,78 @@
+// Execution report of each settled rebalance, so the manager can show what every auction traded
+// and at what average price. Rebalances are numbered by epoch, starting at 1.
+// Reports are written when a rebalance is finalized or cancelled and kept in persistent storage.
+use constellation_shared::oracle::PRICE_SCALE;
+use soroban_sdk::{contracttype, panic_with_error, Address, Env, Vec};
+
+use crate::auction::Auction;
+use crate::error::Error;
+use crate::storage_types::{DataKey, AUCTION_BUMP_AMOUNT, AUCTION_LIFETIME_THRESHOLD};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Direction {
+    // This contract bought the component with intermediate token
+    Buy,
+    // This contract sold the component for intermediate token
+    Sell,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct ExecutionLeg {
+    pub component: Address,
+    pub direction: Direction,
+    pub filled: i128,
+    // Intermediate token paid out for a buy, received for a sell
+    pub intermediate_amount: i128,
+    // intermediate_amount per component token, scaled by PRICE_SCALE; 0 if nothing was filled
+    pub avg_price: i128,
+}
+
+// Epoch of the current or most recent rebalance, 0 before the first one
+pub fn read_epoch(e: &Env) -> u32 {
+    let key = DataKey::RebalanceEpoch;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn next_epoch(e: &Env) -> u32 {
+    let epoch = read_epoch(e) + 1;
+    e.storage().instance().set(&DataKey::RebalanceEpoch, &epoch);
+    epoch
+}
+
+pub fn execution_leg(auction: &Auction) -> ExecutionLeg {
+    let avg_price = if auction.filled == 0 {
+        0
+    } else {
+        auction.intermediate_amount * PRICE_SCALE / auction.filled
+    };
+    ExecutionLeg {
+        component: auction.component.clone(),
+        direction: if auction.is_buy() {
+            Direction::Buy
+        } else {
+            Direction::Sell
+        },
+        filled: auction.filled,
+        intermediate_amount: auction.intermediate_amount,
+        avg_price,
+    }
+}
+
+pub fn write_execution_report(e: &Env, epoch: u32, legs: &Vec<ExecutionLeg>) {
+    let key = DataKey::ExecutionReport(epoch);
+    e.storage().persistent().set(&key, legs);
+    e.storage()
+        .persistent()
+        .bump(&key, AUCTION_LIFETIME_THRESHOLD, AUCTION_BUMP_AMOUNT);
+}
+
+// Traps with NoReport if rebalance 'epoch' hasn't settled, or its report has expired
+pub fn read_execution_report(e: &Env, epoch: u32) -> Vec<ExecutionLeg> {
+    let key = DataKey::ExecutionReport(epoch);
+    match e.storage().persistent().get(&key) {
+        Some(legs) => legs,
+        None => panic_with_error!(e, Error::NoReport),
+    }
+}
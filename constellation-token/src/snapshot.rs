@@ -0,0 +1,131 @@
+// Balance snapshots for off-chain governance and distributions, in the style of OpenZeppelin's ERC20Snapshot
+// Every account keeps a list of checkpoints ordered by snapshot id. The first balance change of an account
+// after a snapshot records its balance at that snapshot; later changes leave it alone until the next one.
+// The balance at snapshot 'id' is the amount of the first checkpoint at or after 'id', or the current
+// balance if the account hasn't changed since. An account only gains a checkpoint for snapshots it moved
+// after, so the list grows with the manager's snapshot cadence, not with transfers.
+// The total supply is recorded per snapshot id when the snapshot is taken, so later mints and burns
+// never change it.
+// Snapshot ids are u32, as snapshot() returns them; the u64 ids of balance_at_snapshot() and
+// total_supply_at_snapshot() are narrowed to u32, and one past u32::MAX can't have been taken.
+use soroban_sdk::{contracttype, panic_with_error, Address, Env, Vec};
+
+use crate::error::Error;
+use crate::storage_types::{DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
+use crate::supply::read_total_supply;
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Snapshot {
+    pub id: u32,
+    pub ledger: u32,
+    pub total_supply: i128,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct BalanceCheckpoint {
+    pub snapshot_id: u32,
+    // Balance when the snapshot was taken
+    pub amount: i128,
+}
+
+pub fn read_snapshot(e: &Env) -> Option<Snapshot> {
+    let key = DataKey::Snapshot;
+    e.storage().instance().get(&key)
+}
+
+pub fn take_snapshot(e: &Env) -> u32 {
+    let id = match read_snapshot(e) {
+        Some(snapshot) => snapshot.id + 1,
+        None => 1,
+    };
+    let snapshot = Snapshot {
+        id,
+        ledger: e.ledger().sequence(),
+        total_supply: read_total_supply(e),
+    };
+    e.storage().instance().set(&DataKey::Snapshot, &snapshot);
+    let key = DataKey::SnapshotSupply(id);
+    e.storage().persistent().set(&key, &snapshot.total_supply);
+    e.storage()
+        .persistent()
+        .bump(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    id
+}
+
+fn read_checkpoints(e: &Env, addr: Address) -> Vec<BalanceCheckpoint> {
+    let key = DataKey::BalanceCheckpoint(addr);
+    e.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| Vec::new(e))
+}
+
+// Called with the balance about to be overwritten
+pub fn checkpoint_balance(e: &Env, addr: Address, amount: i128) {
+    let snapshot = match read_snapshot(e) {
+        Some(snapshot) => snapshot,
+        None => return,
+    };
+    let mut checkpoints = read_checkpoints(e, addr.clone());
+    if let Some(last) = checkpoints.last() {
+        if last.snapshot_id == snapshot.id {
+            return;
+        }
+    }
+    checkpoints.push_back(BalanceCheckpoint {
+        snapshot_id: snapshot.id,
+        amount,
+    });
+    let key = DataKey::BalanceCheckpoint(addr);
+    e.storage().persistent().set(&key, &checkpoints);
+    e.storage()
+        .persistent()
+        .bump(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// Traps with SnapshotUnavailable unless 'snapshot_id' has been taken
+fn check_snapshot_id(e: &Env, snapshot_id: u32) {
+    match read_snapshot(e) {
+        Some(snapshot) if snapshot_id >= 1 && snapshot_id <= snapshot.id => {}
+        _ => panic_with_error!(e, Error::SnapshotUnavailable),
+    }
+}
+
+// Narrows a u64 snapshot id, trapping with SnapshotUnavailable for one no snapshot can have
+pub fn snapshot_id(e: &Env, id: u64) -> u32 {
+    u32::try_from(id).unwrap_or_else(|_| panic_with_error!(e, Error::SnapshotUnavailable))
+}
+
+// 'balance' is the current balance of 'addr'
+pub fn read_balance_at(e: &Env, addr: Address, snapshot_id: u32, balance: i128) -> i128 {
+    check_snapshot_id(e, snapshot_id);
+    let checkpoints = read_checkpoints(e, addr);
+    // Binary search for the first checkpoint at or after 'snapshot_id'
+    let (mut index, mut end) = (0, checkpoints.len());
+    while index < end {
+        let mid = (index + end) / 2;
+        if checkpoints.get(mid).unwrap().snapshot_id < snapshot_id {
+            index = mid + 1;
+        } else {
+            end = mid;
+        }
+    }
+    match checkpoints.get(index) {
+        Some(checkpoint) => checkpoint.amount,
+        None => balance,
+    }
+}
+
+// Total supply recorded when 'snapshot_id' was taken; later mints and burns don't change it
+// Doesn't extend the entry's TTL so total_supply_at() stays read-only; traps with SnapshotUnavailable if the
+// entry is missing
+pub fn read_total_supply_at(e: &Env, snapshot_id: u32) -> i128 {
+    check_snapshot_id(e, snapshot_id);
+    let key = DataKey::SnapshotSupply(snapshot_id);
+    match e.storage().persistent().get(&key) {
+        Some(total_supply) => total_supply,
+        None => panic_with_error!(e, Error::SnapshotUnavailable),
+    }
+}
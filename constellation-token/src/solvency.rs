@@ -0,0 +1,122 @@
+// The Constellation Token must always be fully collateralized:
+// total_supply * units[i] <= held[i] for every component i
+// If it isn't, e.g. because a component token was exploited, minting halts until the manager resumes it.
+// Redemptions stay open so holders can exit.
+// is_fully_backed() tolerates a deficit of up to the backing epsilon per component, so dust-level rounding
+// doesn't trip the breaker; verify_backing() always reports exact figures.
+use constellation_shared::events::{emit_backing_epsilon, emit_error};
+use soroban_sdk::{panic_with_error, token, Address, Env, Vec};
+
+use crate::component::{read_amounts, read_components, MAX_PAGE_SIZE};
+use crate::error::Error;
+use crate::storage_types::DataKey;
+use crate::supply::read_total_supply;
+
+// In component base units; enough for rounding dust, too little to hide a real shortfall
+pub(crate) const MAX_BACKING_EPSILON: i128 = 100;
+
+pub fn read_backing_epsilon(e: &Env) -> i128 {
+    let key = DataKey::BackingEpsilon;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn write_backing_epsilon(e: &Env, epsilon: i128) {
+    if !(0..=MAX_BACKING_EPSILON).contains(&epsilon) {
+        panic!("backing epsilon must be between 0 and {}", MAX_BACKING_EPSILON)
+    }
+    let key = DataKey::BackingEpsilon;
+    e.storage().instance().set(&key, &epsilon);
+    emit_backing_epsilon(e, epsilon);
+}
+
+pub fn is_fully_backed(e: &Env) -> bool {
+    is_backed(e, read_total_supply(e))
+}
+
+// Whether the held balances back 'total_supply', within the backing epsilon
+fn is_backed(e: &Env, total_supply: i128) -> bool {
+    find_deficit(e, total_supply).is_none()
+}
+
+// The first component whose held balance falls short of backing 'total_supply' by more than the backing
+// epsilon, with its deficit
+fn find_deficit(e: &Env, total_supply: i128) -> Option<(Address, i128)> {
+    let components = read_components(e);
+    let amounts = read_amounts(e);
+    let epsilon = read_backing_epsilon(e);
+    for i in 0..components.len() {
+        let component = components.get(i).unwrap();
+        let held = token::Client::new(e, &component).balance(&e.current_contract_address());
+        let required = total_supply * amounts.get(i).unwrap() as i128;
+        if required > held + epsilon {
+            return Some((component, required - held));
+        }
+    }
+    None
+}
+
+// Held balance less what the supply requires for every component, in getComponents() order
+// Negative for a deficit; exact, whatever the backing epsilon
+pub fn verify_backing(e: &Env) -> Vec<(Address, i128)> {
+    let total_supply = read_total_supply(e);
+    let components = read_components(e);
+    let amounts = read_amounts(e);
+    let contract = e.current_contract_address();
+    let mut report = Vec::new(e);
+    for i in 0..components.len() {
+        let component = components.get(i).unwrap();
+        let held = token::Client::new(e, &component).balance(&contract);
+        report.push_back((component, held - total_supply * amounts.get(i).unwrap() as i128));
+    }
+    report
+}
+
+// Up to 'count' (component, held balance) pairs starting at index 'start', capped like read_basket_page()
+// Empty when 'start' is past the end
+pub fn read_backing_page(e: &Env, start: u32, count: u32) -> Vec<(Address, i128)> {
+    let components = read_components(e);
+    let end = start.saturating_add(count.min(MAX_PAGE_SIZE)).min(components.len());
+    let contract = e.current_contract_address();
+    let mut page = Vec::new(e);
+    for i in start..end {
+        let component = components.get(i).unwrap();
+        let held = token::Client::new(e, &component).balance(&contract);
+        page.push_back((component, held));
+    }
+    page
+}
+
+pub fn assert_solvent(e: &Env) {
+    if !is_fully_backed(e) {
+        panic_with_error!(e, Error::Insolvent);
+    }
+}
+
+pub fn is_mint_halted(e: &Env) -> bool {
+    let key = DataKey::MintHalted;
+    e.storage().instance().get(&key).unwrap_or(false)
+}
+
+pub fn write_mint_halted(e: &Env, halted: bool) {
+    let key = DataKey::MintHalted;
+    if halted {
+        e.storage().instance().set(&key, &true);
+    } else {
+        e.storage().instance().remove(&key);
+    }
+}
+
+// Traps with Undercollateralized if the circuit breaker has tripped or the reserves don't back the
+// supply after minting 'amount'. The MinterBurner deposits the components before calling mint(), so
+// checking the current supply alone would let the new deposit cover an existing deficit.
+// One balance() call per component, the same order of cost as the deposit transfers themselves.
+// An under-backed component is reported in an ("error", code) event first.
+pub fn check_circuit_breaker(e: &Env, amount: i128) {
+    if is_mint_halted(e) {
+        panic_with_error!(e, Error::Undercollateralized);
+    }
+    if let Some((component, deficit)) = find_deficit(e, read_total_supply(e) + amount) {
+        emit_error(e, Error::Undercollateralized as u32, component, deficit);
+        panic_with_error!(e, Error::Undercollateralized);
+    }
+}
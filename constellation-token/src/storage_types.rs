@@ -1,5 +1,7 @@
-// Unchanged from https://github.com/stellar/soroban-examples/tree/main/token/src
-use soroban_sdk::{contracttype, Address};
+// Based on https://github.com/stellar/soroban-examples/tree/main/token/src
+// The keys are defined in constellation_shared::storage, with the MinterBurner's
+pub use constellation_shared::storage::{AllowanceDataKey, TokenDataKey as DataKey};
+use soroban_sdk::contracttype;
 
 pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
 pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
@@ -8,12 +10,8 @@ pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_I
 pub(crate) const BALANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
 pub(crate) const BALANCE_LIFETIME_THRESHOLD: u32 = BALANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
 
-#[derive(Clone)]
-#[contracttype]
-pub struct AllowanceDataKey {
-    pub from: Address,
-    pub spender: Address,
-}
+pub(crate) const AUCTION_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const AUCTION_LIFETIME_THRESHOLD: u32 = AUCTION_BUMP_AMOUNT - DAY_IN_LEDGERS;
 
 #[contracttype]
 pub struct AllowanceValue {
@@ -21,12 +19,11 @@ pub struct AllowanceValue {
     pub expiration_ledger: u32,
 }
 
-#[derive(Clone)]
 #[contracttype]
-pub enum DataKey {
-    Allowance(AllowanceDataKey),
-    Balance(Address),
-    Nonce(Address),
-    State(Address),
-    Admin,
+pub struct BalanceEntry {
+    pub amount: i128,
+    // Cleared to freeze an account's balance
+    pub authorized: bool,
+    // Ledger sequence before which the balance can't be spent
+    pub locked_until: u32,
 }
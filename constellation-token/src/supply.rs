@@ -0,0 +1,31 @@
+use soroban_sdk::Env;
+
+use crate::storage_types::DataKey;
+
+pub fn read_total_supply(e: &Env) -> i128 {
+    let key = DataKey::TotalSupply;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn write_total_supply(e: &Env, amount: i128) {
+    let key = DataKey::TotalSupply;
+    e.storage().instance().set(&key, &amount);
+}
+
+// Highest total supply ever reached; burns never lower it
+pub fn read_max_supply(e: &Env) -> i128 {
+    let key = DataKey::MaxSupply;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn increase_total_supply(e: &Env, amount: i128) {
+    let supply = read_total_supply(e) + amount;
+    write_total_supply(e, supply);
+    if supply > read_max_supply(e) {
+        e.storage().instance().set(&DataKey::MaxSupply, &supply);
+    }
+}
+
+pub fn decrease_total_supply(e: &Env, amount: i128) {
+    write_total_supply(e, read_total_supply(e) - amount);
+}
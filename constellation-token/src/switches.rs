@@ -0,0 +1,45 @@
+// Manager switches to turn off minting or burning on their own, e.g. once a product reaches its cap,
+// without pausing the MinterBurner for every ctoken. Both default to enabled; only 'disabled' is stored.
+// Burning covers burn() and burn_from(), so every redemption through the MinterBurner stops too.
+use soroban_sdk::{panic_with_error, Env};
+
+use crate::error::Error;
+use crate::storage_types::DataKey;
+
+pub fn is_mint_enabled(e: &Env) -> bool {
+    !e.storage().instance().has(&DataKey::MintDisabled)
+}
+
+pub fn write_mint_enabled(e: &Env, enabled: bool) {
+    let key = DataKey::MintDisabled;
+    if enabled {
+        e.storage().instance().remove(&key);
+    } else {
+        e.storage().instance().set(&key, &true);
+    }
+}
+
+pub fn is_burn_enabled(e: &Env) -> bool {
+    !e.storage().instance().has(&DataKey::BurnDisabled)
+}
+
+pub fn write_burn_enabled(e: &Env, enabled: bool) {
+    let key = DataKey::BurnDisabled;
+    if enabled {
+        e.storage().instance().remove(&key);
+    } else {
+        e.storage().instance().set(&key, &true);
+    }
+}
+
+pub fn check_mint_enabled(e: &Env) {
+    if !is_mint_enabled(e) {
+        panic_with_error!(e, Error::MintDisabled);
+    }
+}
+
+pub fn check_burn_enabled(e: &Env) {
+    if !is_burn_enabled(e) {
+        panic_with_error!(e, Error::BurnDisabled);
+    }
+}
@@ -0,0 +1,2849 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::auction::{AuctionParams, BidData};
+use crate::contract::{ConstellationToken, ConstellationTokenClient};
+use crate::error::Error;
+use crate::report::Direction;
+use crate::storage_types::{BalanceEntry, DataKey, DAY_IN_LEDGERS};
+use constellation_mocks::{
+    MockOracle, MockOracleClient, MockReceiver, MockReceiverClient, MockToken, MockTokenClient,
+    MockTransferHook, MockTransferHookClient,
+};
+use constellation_shared::events::{
+    AllowanceErrorEvent, AmountEvent, ErrorEvent, MetadataUpdatedEvent, RebalanceEndEvent,
+    RebalanceProgressEvent, RebalanceStartEvent, UnitUpdatedEvent, WindDownEvent, ALLOWANCE,
+    CANCEL, DUST, EPSILON, ERROR, ESCROW, EVENT_VERSION, FEE, FINALIZE, ISSUANCE, METADATA,
+    MIN_PRICE, PERFORMANCE, PROGRESS, REBALANCE, RECONCILE, REPRICED, SOLVENCY, SPENT, START, UNIT,
+    UPDATED, WIND_DOWN,
+};
+use constellation_shared::oracle::PRICE_SCALE;
+use soroban_sdk::testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke};
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{Address, Bytes, Env, IntoVal, String, Symbol, TryFromVal, Val, Vec};
+
+const DECIMALS: u32 = 7;
+
+struct Setup<'a> {
+    env: Env,
+    // Stands in for the MinterBurner; all auths are mocked
+    admin: Address,
+    manager: Address,
+    token: ConstellationTokenClient<'a>,
+    components: std::vec::Vec<MockTokenClient<'a>>,
+}
+
+fn create_component<'a>(e: &Env, decimals: u32) -> MockTokenClient<'a> {
+    let component = MockTokenClient::new(e, &e.register_contract(None, MockToken));
+    component.initialize(
+        &decimals,
+        &String::from_str(e, "Component"),
+        &String::from_str(e, "CMP"),
+    );
+    component
+}
+
+fn create_oracle<'a>(e: &Env) -> MockOracleClient<'a> {
+    MockOracleClient::new(e, &e.register_contract(None, MockOracle))
+}
+
+// A token with one 7-decimal mock component per entry of 'units'
+fn setup<'a>(units: &[u32]) -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+
+    let admin = Address::random(&env);
+    let manager = Address::random(&env);
+    let mut components = std::vec::Vec::new();
+    let mut addresses = Vec::new(&env);
+    let mut amounts = Vec::new(&env);
+    for u in units {
+        let component = create_component(&env, DECIMALS);
+        addresses.push_back(component.address.clone());
+        amounts.push_back(*u);
+        components.push(component);
+    }
+
+    let token =
+        ConstellationTokenClient::new(&env, &env.register_contract(None, ConstellationToken));
+    token.initialize(
+        &DECIMALS,
+        &addresses,
+        &amounts,
+        &admin,
+        &manager,
+        &String::from_str(&env, "Constellation"),
+        &String::from_str(&env, "CTKN"),
+        &false,
+        &false,
+    );
+    Setup {
+        env,
+        admin,
+        manager,
+        token,
+        components,
+    }
+}
+
+impl<'a> Setup<'a> {
+    // Deposit the backing for 'amount' ctokens and mint them, as the MinterBurner does
+    fn mint(&self, to: &Address, amount: i128) {
+        let units = self.token.getAmounts();
+        for (i, component) in self.components.iter().enumerate() {
+            component.mint(
+                &self.token.address,
+                &(amount * units.get(i as u32).unwrap() as i128),
+            );
+        }
+        self.token.mint(to, &amount);
+    }
+
+    // An oracle pricing every component at 'price', published at the current ledger
+    fn set_oracle(&self, price: i128) -> MockOracleClient<'a> {
+        let oracle = create_oracle(&self.env);
+        for component in self.components.iter() {
+            oracle.set_price(&component.address, &price, &self.env.ledger().sequence());
+        }
+        self.token.set_oracle(&oracle.address, &10);
+        oracle
+    }
+
+    // Propose and start a rebalance to 'amounts' of 'components', with auctions from 1 to 0.5 intermediate
+    // token per component over 100 ledgers. Returns the intermediate token.
+    fn start_rebalance(&self, components: &[&Address], amounts: &[u32]) -> MockTokenClient<'a> {
+        let intermediate = create_component(&self.env, DECIMALS);
+        self.token.add_intermediate(&intermediate.address);
+        let mut targets = Vec::new(&self.env);
+        let mut params = Vec::new(&self.env);
+        for component in components {
+            targets.push_back((*component).clone());
+            params.push_back(AuctionParams {
+                start_price: PRICE_SCALE,
+                min_price: PRICE_SCALE / 2,
+                duration_ledgers: 100,
+                min_fill: 1,
+            });
+        }
+        self.token.propose_rebalance(
+            &targets,
+            &Vec::from_slice(&self.env, amounts),
+            &params,
+            &intermediate.address,
+        );
+        self.token.start_rebalance(&self.manager);
+        intermediate
+    }
+
+    // Topics and payload of each ('topic', 'action', ...) event the token published, oldest first
+    fn events(&self, topic: Symbol, action: Symbol) -> std::vec::Vec<(Vec<Val>, Val)> {
+        let mut events = std::vec::Vec::new();
+        for (contract, topics, data) in self.env.events().all().iter() {
+            if contract == self.token.address
+                && Symbol::try_from_val(&self.env, &topics.get(0).unwrap()) == Ok(topic.clone())
+                && Symbol::try_from_val(&self.env, &topics.get(1).unwrap()) == Ok(action.clone())
+            {
+                events.push((topics, data));
+            }
+        }
+        events
+    }
+
+    // Code and payload of the last ("error", code) event the token published
+    fn last_error(&self) -> (u32, ErrorEvent) {
+        let (_, topics, data) = self
+            .env
+            .events()
+            .all()
+            .iter()
+            .filter(|(contract, topics, _)| {
+                *contract == self.token.address
+                    && Symbol::try_from_val(&self.env, &topics.get(0).unwrap()) == Ok(ERROR)
+            })
+            .last()
+            .unwrap();
+        (
+            u32::try_from_val(&self.env, &topics.get(1).unwrap()).unwrap(),
+            ErrorEvent::try_from_val(&self.env, &data).unwrap(),
+        )
+    }
+
+    fn advance(&self, ledgers: u32) {
+        self.env
+            .ledger()
+            .with_mut(|li| li.sequence_number += ledgers);
+    }
+}
+
+#[test]
+fn test_nav_rejects_stale_oracle_quote() {
+    let s = setup(&[2, 3]);
+    let oracle = s.set_oracle(PRICE_SCALE);
+    assert_eq!(s.token.nav_per_token(), 5 * PRICE_SCALE);
+
+    // Within max_price_age_ledgers
+    s.advance(10);
+    assert_eq!(s.token.nav_per_token(), 5 * PRICE_SCALE);
+
+    s.advance(1);
+    assert_eq!(
+        s.token.try_nav_per_token(),
+        Err(Ok(Error::StalePrice.into()))
+    );
+
+    // A fresh quote for every component makes it usable again
+    for component in s.components.iter() {
+        oracle.set_price(&component.address, &PRICE_SCALE, &s.env.ledger().sequence());
+    }
+    assert_eq!(s.token.nav_per_token(), 5 * PRICE_SCALE);
+}
+
+#[test]
+fn test_nav_without_oracle() {
+    let s = setup(&[1]);
+    assert_eq!(s.token.try_nav_per_token(), Err(Ok(Error::NoOracle.into())));
+}
+
+#[test]
+fn test_accrue_fee_rounds_units_up() {
+    let s = setup(&[700, 300]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 1000);
+    let oracle = s.set_oracle(PRICE_SCALE);
+    // Settles nothing and sets the mark
+    s.token.set_performance_fee(&2000);
+    assert_eq!(s.token.high_water_mark(), 1000 * PRICE_SCALE);
+
+    // NAV doubles: 20% of the gain is a fee of 100 on 1000, but 700 * 1000 / 1100 and
+    // 300 * 1000 / 1100 truncate, so only 98 fits whole units
+    for component in s.components.iter() {
+        oracle.set_price(
+            &component.address,
+            &(2 * PRICE_SCALE),
+            &s.env.ledger().sequence(),
+        );
+    }
+    assert_eq!(s.token.accrue_fee(), 98);
+    assert_eq!(s.token.getAmounts(), Vec::from_array(&s.env, [637, 273]));
+    assert_eq!(s.token.balance(&s.manager), 98);
+    assert_eq!(s.token.total_supply(), 1098);
+    assert_eq!(s.token.high_water_mark(), 1820 * PRICE_SCALE);
+
+    // Holders keep at least supply / (supply + fee) of every component, and the reserves back the new supply
+    for (i, component) in s.components.iter().enumerate() {
+        let units = s.token.getAmounts().get(i as u32).unwrap() as i128;
+        let before = [700, 300][i];
+        assert!(units * 1100 >= before * 1000);
+        assert!(component.balance(&s.token.address) >= 1098 * units);
+    }
+}
+
+#[test]
+fn test_accrue_fee_never_zeroes_a_unit() {
+    let s = setup(&[1]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 1000);
+    let oracle = s.set_oracle(PRICE_SCALE);
+    s.token.set_performance_fee(&2000);
+
+    oracle.set_price(
+        &s.components[0].address,
+        &(2 * PRICE_SCALE),
+        &s.env.ledger().sequence(),
+    );
+    // 1 * 1000 / 1100 would truncate to 0; no whole fee fits, so nothing is taken
+    assert_eq!(s.token.accrue_fee(), 0);
+    assert_eq!(s.token.getAmounts(), Vec::from_array(&s.env, [1]));
+    assert_eq!(s.token.balance(&s.manager), 0);
+    assert_eq!(s.token.total_supply(), 1000);
+    // The gain stays above the mark
+    assert_eq!(s.token.high_water_mark(), PRICE_SCALE);
+}
+
+#[test]
+fn test_performance_fee_goes_to_fee_recipient() {
+    let s = setup(&[700, 300]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 1000);
+    let oracle = s.set_oracle(PRICE_SCALE);
+    s.token.set_performance_fee(&2000);
+    let recipient = Address::random(&s.env);
+    s.token.set_fee_recipient(&recipient);
+    assert_eq!(s.token.fee_recipient(), recipient);
+
+    for component in s.components.iter() {
+        oracle.set_price(
+            &component.address,
+            &(2 * PRICE_SCALE),
+            &s.env.ledger().sequence(),
+        );
+    }
+    assert_eq!(s.token.accrue_fee(), 98);
+    assert_eq!(s.token.balance(&recipient), 98);
+    assert_eq!(s.token.balance(&s.manager), 0);
+}
+
+#[test]
+fn test_set_fee_recipient_settles_the_old_recipient_first() {
+    let s = setup(&[700, 300]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 1000);
+    let oracle = s.set_oracle(PRICE_SCALE);
+    s.token.set_performance_fee(&2000);
+    assert_eq!(s.token.fee_recipient(), s.manager);
+
+    for component in s.components.iter() {
+        oracle.set_price(
+            &component.address,
+            &(2 * PRICE_SCALE),
+            &s.env.ledger().sequence(),
+        );
+    }
+    // The gain so far is owed to the manager
+    let recipient = Address::random(&s.env);
+    s.token.set_fee_recipient(&recipient);
+    assert_eq!(s.token.balance(&s.manager), 98);
+    assert_eq!(s.token.accrue_fee(), 0);
+    assert_eq!(s.token.balance(&recipient), 0);
+}
+
+#[test]
+fn test_assert_solvent() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.token.assert_solvent();
+    s.mint(&holder, 100);
+    // The ctoken's own burn leaves the components for the MinterBurner to release, a surplus of 40 * 3
+    s.token.burn(&holder, &40);
+    s.token.assert_solvent();
+
+    // Move the surplus and one base unit more out of the reserves behind the contract's back
+    let thief = Address::random(&s.env);
+    s.components[1].transfer(&s.token.address, &thief, &120);
+    s.token.assert_solvent();
+    s.components[1].transfer(&s.token.address, &thief, &1);
+    assert_eq!(
+        s.token.try_assert_solvent(),
+        Err(Ok(Error::Insolvent.into()))
+    );
+}
+
+#[test]
+fn test_circuit_breaker_checks_supply_after_mint() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+
+    // A deposit for 50 doesn't back a mint of 60, even though it covers the existing supply
+    for (i, component) in s.components.iter().enumerate() {
+        component.mint(&s.token.address, &(50 * [2, 3][i]));
+    }
+    assert_eq!(
+        s.token.try_mint(&holder, &60),
+        Err(Ok(Error::Undercollateralized.into()))
+    );
+    // 160 * 2 of the first component are needed and 300 are held
+    let (code, error) = s.last_error();
+    assert_eq!(code, Error::Undercollateralized as u32);
+    assert_eq!(error.component, s.components[0].address);
+    assert_eq!(error.shortfall, 20);
+
+    s.token.mint(&holder, &50);
+    assert_eq!(s.token.total_supply(), 150);
+    assert!(s.token.is_fully_backed());
+}
+
+#[test]
+fn test_initialize_smoke() {
+    let env = Env::default();
+    let component = create_component(&env, DECIMALS);
+    let token =
+        ConstellationTokenClient::new(&env, &env.register_contract(None, ConstellationToken));
+    let components = Vec::from_array(&env, [component.address.clone()]);
+    let amounts = Vec::from_array(&env, [1u32]);
+    let admin = Address::random(&env);
+    let manager = Address::random(&env);
+    let name = String::from_str(&env, "Constellation");
+    let symbol = String::from_str(&env, "CTKN");
+    token.initialize(
+        &DECIMALS,
+        &components,
+        &amounts,
+        &admin,
+        &manager,
+        &name,
+        &symbol,
+        &false,
+        &false,
+    );
+
+    // A second initialize traps
+    assert!(token
+        .try_initialize(
+            &DECIMALS,
+            &components,
+            &amounts,
+            &admin,
+            &manager,
+            &name,
+            &symbol,
+            &false,
+            &false,
+        )
+        .is_err());
+}
+
+#[test]
+fn test_get_components_and_amounts() {
+    let s = setup(&[2, 3, 5]);
+    let mut components = Vec::new(&s.env);
+    for component in s.components.iter() {
+        components.push_back(component.address.clone());
+    }
+    assert_eq!(s.token.getComponents(), components);
+    assert_eq!(s.token.getAmounts(), Vec::from_array(&s.env, [2, 3, 5]));
+}
+
+#[test]
+fn test_initialize_rejects_mismatched_amounts() {
+    let env = Env::default();
+    let component = create_component(&env, DECIMALS);
+    let token =
+        ConstellationTokenClient::new(&env, &env.register_contract(None, ConstellationToken));
+    assert!(token
+        .try_initialize(
+            &DECIMALS,
+            &Vec::from_array(&env, [component.address.clone()]),
+            &Vec::from_array(&env, [1u32, 2]),
+            &Address::random(&env),
+            &Address::random(&env),
+            &String::from_str(&env, "Constellation"),
+            &String::from_str(&env, "CTKN"),
+            &false,
+            &false,
+        )
+        .is_err());
+}
+
+#[test]
+fn test_initialize_writes_metadata_and_components() {
+    let s = setup(&[2, 3]);
+    // Both writes of initialize() are readable afterwards
+    assert_eq!(s.token.name(), String::from_str(&s.env, "Constellation"));
+    assert_eq!(s.token.getComponents().len(), 2);
+    assert_eq!(s.token.getAmounts().len(), 2);
+}
+
+#[test]
+fn test_balance_at_snapshot() {
+    let s = setup(&[1]);
+    let alice = Address::random(&s.env);
+    let bob = Address::random(&s.env);
+    s.mint(&alice, 100);
+
+    let first = s.token.snapshot();
+    s.token.transfer(&alice, &bob, &30);
+    s.token.transfer(&alice, &bob, &10);
+    let second = s.token.snapshot();
+    s.token.transfer(&bob, &alice, &5);
+    // No change between the third snapshot and now
+    let third = s.token.snapshot();
+
+    assert_eq!(s.token.balance_at(&alice, &first), 100);
+    assert_eq!(s.token.balance_at(&bob, &first), 0);
+    assert_eq!(s.token.balance_at(&alice, &second), 60);
+    assert_eq!(s.token.balance_at(&bob, &second), 40);
+    assert_eq!(s.token.balance_at(&alice, &third), 65);
+    assert_eq!(s.token.balance_at(&bob, &third), 35);
+    assert_eq!(s.token.balance(&alice), 65);
+
+    // balance_at_snapshot() answers the same for u64 ids
+    assert_eq!(s.token.balance_at_snapshot(&alice, &(first as u64)), 100);
+    assert_eq!(s.token.balance_at_snapshot(&bob, &(second as u64)), 40);
+}
+
+#[test]
+fn test_balance_at_snapshot_not_taken() {
+    let s = setup(&[1]);
+    let alice = Address::random(&s.env);
+    assert_eq!(
+        s.token.try_balance_at(&alice, &1),
+        Err(Ok(Error::SnapshotUnavailable.into()))
+    );
+    let id = s.token.snapshot();
+    assert_eq!(s.token.balance_at(&alice, &id), 0);
+    assert_eq!(
+        s.token.try_balance_at(&alice, &(id + 1)),
+        Err(Ok(Error::SnapshotUnavailable.into()))
+    );
+    assert_eq!(
+        s.token.try_balance_at(&alice, &0),
+        Err(Ok(Error::SnapshotUnavailable.into()))
+    );
+    // A u64 id past u32::MAX can't have been taken
+    assert_eq!(
+        s.token
+            .try_balance_at_snapshot(&alice, &(u32::MAX as u64 + id as u64)),
+        Err(Ok(Error::SnapshotUnavailable.into()))
+    );
+}
+
+#[test]
+fn test_total_supply_at() {
+    let s = setup(&[1]);
+    let alice = Address::random(&s.env);
+    s.mint(&alice, 100);
+    let first = s.token.snapshot();
+    s.mint(&alice, 50);
+    let second = s.token.snapshot();
+    s.token.burn(&alice, &30);
+
+    // Later mints and burns leave every snapshot's supply alone
+    assert_eq!(s.token.total_supply_at(&first), 100);
+    assert_eq!(s.token.total_supply_at(&second), 150);
+    assert_eq!(s.token.total_supply_at_snapshot(&(first as u64)), 100);
+    assert_eq!(s.token.total_supply(), 120);
+    assert_eq!(
+        s.token.try_total_supply_at(&(second + 1)),
+        Err(Ok(Error::SnapshotUnavailable.into()))
+    );
+    assert_eq!(
+        s.token.try_total_supply_at_snapshot(&(u32::MAX as u64 + 1)),
+        Err(Ok(Error::SnapshotUnavailable.into()))
+    );
+
+    // Far enough that a bump would extend the entry; reading it doesn't
+    s.advance(2 * DAY_IN_LEDGERS);
+    let before = s.env.to_ledger_snapshot();
+    s.token.total_supply_at(&first);
+    assert_eq!(
+        s.env.to_ledger_snapshot().ledger_entries,
+        before.ledger_entries
+    );
+
+    // A missing entry traps with SnapshotUnavailable
+    s.env.as_contract(&s.token.address, || {
+        s.env
+            .storage()
+            .persistent()
+            .remove(&DataKey::SnapshotSupply(first));
+    });
+    assert_eq!(
+        s.token.try_total_supply_at(&first),
+        Err(Ok(Error::SnapshotUnavailable.into()))
+    );
+}
+
+#[test]
+fn test_freeze_account() {
+    let s = setup(&[1]);
+    let alice = Address::random(&s.env);
+    let bob = Address::random(&s.env);
+    s.mint(&alice, 100);
+    s.mint(&bob, 100);
+
+    s.token.freeze_account(&alice);
+    assert!(s.token.is_frozen(&alice));
+    assert_eq!(
+        s.token.try_transfer(&alice, &bob, &10),
+        Err(Ok(Error::AccountFrozen.into()))
+    );
+    s.components[0].mint(&s.token.address, &10);
+    assert_eq!(
+        s.token.try_mint(&alice, &10),
+        Err(Ok(Error::AccountFrozen.into()))
+    );
+
+    // Others transfer as usual, including to the frozen account, and it can still burn
+    s.token.transfer(&bob, &alice, &10);
+    s.token.burn(&alice, &5);
+    assert_eq!(s.token.balance(&alice), 105);
+
+    s.token.unfreeze_account(&alice);
+    s.token.transfer(&alice, &bob, &10);
+    assert_eq!(s.token.balance(&bob), 100);
+}
+
+#[test]
+fn test_wind_down() {
+    let s = setup(&[1]);
+    let alice = Address::random(&s.env);
+    s.mint(&alice, 100);
+    s.token.set_burn_enabled(&false);
+
+    let effective_ledger = s.token.initiate_wind_down();
+    assert_eq!(s.token.wind_down_ledger(), Some(effective_ledger));
+    // Irreversible: it can't be initiated again and burning can't be turned off
+    assert_eq!(
+        s.token.try_initiate_wind_down(),
+        Err(Ok(Error::WindDown.into()))
+    );
+    assert_eq!(
+        s.token.try_set_burn_enabled(&false),
+        Err(Ok(Error::WindDown.into()))
+    );
+    assert!(s.token.burn_enabled());
+
+    // Issuance continues through the notice period
+    assert!(!s.token.is_winding_down());
+    s.mint(&alice, 10);
+    s.advance(effective_ledger - s.env.ledger().sequence());
+    assert!(s.token.is_winding_down());
+    s.components[0].mint(&s.token.address, &10);
+    assert_eq!(
+        s.token.try_mint(&alice, &10),
+        Err(Ok(Error::WindDown.into()))
+    );
+
+    // Holders can still exit
+    s.token.burn(&alice, &110);
+    assert_eq!(s.token.total_supply(), 0);
+}
+
+#[test]
+fn test_wind_down_stops_fee_accrual() {
+    let s = setup(&[1000]);
+    let alice = Address::random(&s.env);
+    s.mint(&alice, 1000);
+    s.token.initiate_wind_down();
+    let effective_ledger = s.token.wind_down_ledger().unwrap();
+    s.advance(effective_ledger - s.env.ledger().sequence());
+
+    let oracle = s.set_oracle(PRICE_SCALE);
+    s.token.set_performance_fee(&2000);
+    oracle.set_price(
+        &s.components[0].address,
+        &(2 * PRICE_SCALE),
+        &s.env.ledger().sequence(),
+    );
+    assert_eq!(s.token.accrue_fee(), 0);
+    assert_eq!(s.token.high_water_mark(), 0);
+    assert_eq!(s.token.getAmounts(), Vec::from_array(&s.env, [1000]));
+}
+
+#[test]
+fn test_initialize_and_metadata() {
+    let s = setup(&[2, 3]);
+    assert_eq!(s.token.decimals(), DECIMALS);
+    assert_eq!(s.token.name(), String::from_str(&s.env, "Constellation"));
+    assert_eq!(s.token.symbol(), String::from_str(&s.env, "CTKN"));
+    assert_eq!(s.token.get_admin(), s.admin);
+    assert_eq!(s.token.total_supply(), 0);
+}
+
+#[test]
+fn test_getters_before_initialize() {
+    let env = Env::default();
+    let token =
+        ConstellationTokenClient::new(&env, &env.register_contract(None, ConstellationToken));
+    assert_eq!(token.try_decimals(), Err(Ok(Error::NotInitialized.into())));
+    assert_eq!(
+        token.try_getComponents(),
+        Err(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_bids_emit_rebalance_progress() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    // Sells 200 of the second component
+    let intermediate = s.start_rebalance(&[c0, c1], &[2, 1]);
+
+    let bidder = Address::random(&s.env);
+    intermediate.mint(&bidder, &1000);
+    assert_eq!(s.token.bid(&bidder, c1, &50), 50);
+    assert_eq!(s.token.bid(&bidder, c1, &70), 70);
+    assert_eq!(s.components[1].balance(&bidder), 120);
+    assert_eq!(intermediate.balance(&bidder), 880);
+
+    let mut remaining = std::vec::Vec::new();
+    for (topics, data) in s.events(REBALANCE, PROGRESS) {
+        assert_eq!(
+            Address::try_from_val(&s.env, &topics.get(2).unwrap()).unwrap(),
+            *c1
+        );
+        remaining.push(
+            RebalanceProgressEvent::try_from_val(&s.env, &data)
+                .unwrap()
+                .remaining,
+        );
+    }
+    assert_eq!(remaining, [150, 80]);
+
+    // A failed bid emits no progress
+    assert_eq!(
+        s.token.try_bid(&bidder, c0, &10),
+        Err(Ok(Error::NoAuction.into()))
+    );
+}
+
+#[test]
+fn test_mint_blocked_and_redeem_open_during_rebalance() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    s.start_rebalance(&[c0, c1], &[2, 1]);
+    // Nobody bids: the auction reaches min_price and stays open
+    s.advance(200);
+
+    s.components[0].mint(&s.token.address, &20);
+    s.components[1].mint(&s.token.address, &30);
+    assert_eq!(
+        s.token.try_mint(&holder, &10),
+        Err(Ok(Error::RebalanceInProgress.into()))
+    );
+
+    // Redemption, as the MinterBurner runs it, still goes through
+    s.token.approve(&holder, &s.admin, &10, &1000);
+    s.token.burn_from(&s.admin, &holder, &10);
+    s.token.release(&holder, &Vec::from_array(&s.env, [20, 30]));
+    // As does burning directly
+    s.token.burn(&holder, &5);
+    assert_eq!(s.token.total_supply(), 85);
+    assert_eq!(s.components[0].balance(&holder), 20);
+    assert_eq!(s.components[1].balance(&holder), 30);
+
+    s.token.cancel_rebalance();
+    s.token.mint(&holder, &10);
+    assert_eq!(s.token.total_supply(), 95);
+}
+
+#[test]
+fn test_cancel_partially_filled_rebalance() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let added = create_component(&s.env, DECIMALS);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    // Buy 200 of the first component, sell 200 of the second, and add a third
+    let intermediate = s.start_rebalance(&[c0, c1, &added.address], &[4, 1, 1]);
+
+    // Only the sell auction trades: 100 sold takes the second component's amount to 2
+    let bidder = Address::random(&s.env);
+    intermediate.mint(&bidder, &1000);
+    s.token.bid(&bidder, c1, &100);
+    assert_eq!(s.token.getAmounts(), Vec::from_array(&s.env, [2, 2, 0]));
+
+    // Every amount reverts to the snapshot, the added component is dropped
+    s.token.cancel_rebalance();
+    assert_eq!(
+        s.token.getComponents(),
+        Vec::from_array(&s.env, [c0.clone(), c1.clone()])
+    );
+    assert_eq!(s.token.getAmounts(), Vec::from_array(&s.env, [2, 3]));
+    assert_eq!(
+        s.token.try_bid(&bidder, c1, &10),
+        Err(Ok(Error::NoRebalance.into()))
+    );
+
+    // The swap itself isn't undone: the reserves keep what was traded
+    assert_eq!(s.components[1].balance(&s.token.address), 200);
+    assert_eq!(intermediate.balance(&s.token.address), 100);
+    assert!(!s.token.is_fully_backed());
+}
+
+#[test]
+fn test_bid_rejects_fee_on_transfer() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    // Buys 200 of the first component, paying out intermediate token held by the ctoken
+    let intermediate = s.start_rebalance(&[c0, c1], &[4, 3]);
+    intermediate.mint(&s.token.address, &1000);
+
+    let bidder = Address::random(&s.env);
+    s.components[0].mint(&bidder, &200);
+    s.components[0].set_fee_bps(&100);
+    assert_eq!(
+        s.token.try_bid(&bidder, c0, &100),
+        Err(Ok(Error::FeeOnTransferNotSupported.into()))
+    );
+    let (code, error) = s.last_error();
+    assert_eq!(code, Error::FeeOnTransferNotSupported as u32);
+    assert_eq!((error.component, error.shortfall), (c0.clone(), 1));
+
+    s.components[0].set_fee_bps(&0);
+    assert_eq!(s.token.bid(&bidder, c0, &100), 100);
+    assert_eq!(s.components[0].balance(&s.token.address), 300);
+}
+
+#[test]
+fn test_weights_from_oracle() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let oracle = s.set_oracle(PRICE_SCALE);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    let ledger = s.env.ledger().sequence();
+    oracle.set_price(c0, &(3 * PRICE_SCALE), &ledger);
+    // 2 * 3 against 3 * 1, rounded down
+    let weights = s.token.get_weights(&oracle.address);
+    assert_eq!(weights, Vec::from_array(&s.env, [6666, 3333]));
+    assert!(weights.iter().sum::<u32>() <= 10_000);
+
+    // Priced by the oracle passed in, not the configured one
+    let other = create_oracle(&s.env);
+    other.set_price(c0, &PRICE_SCALE, &ledger);
+    other.set_price(c1, &PRICE_SCALE, &ledger);
+    assert_eq!(
+        s.token.get_weights(&other.address),
+        Vec::from_array(&s.env, [4000, 6000])
+    );
+
+    s.start_rebalance(&[c0, c1], &[3, 1]);
+    assert_eq!(
+        s.token.get_target_weights(&oracle.address),
+        Vec::from_array(&s.env, [9000, 1000])
+    );
+}
+
+#[test]
+fn test_weights_reject_stale_quotes() {
+    let s = setup(&[2, 3]);
+    let oracle = s.set_oracle(PRICE_SCALE);
+    s.advance(11);
+    assert_eq!(
+        s.token.try_get_weights(&oracle.address),
+        Err(Ok(Error::StalePrice.into()))
+    );
+}
+
+#[test]
+fn test_implied_price() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let oracle = s.set_oracle(PRICE_SCALE);
+    let ledger = s.env.ledger().sequence();
+    oracle.set_price(&s.components[0].address, &(3 * PRICE_SCALE), &ledger);
+    let quote = Address::random(&s.env);
+    oracle.set_price(&quote, &(2 * PRICE_SCALE), &ledger);
+    // (2 * 3 + 3 * 1) / 2
+    assert_eq!(
+        s.token.implied_price(&quote, &oracle.address),
+        9 * PRICE_SCALE / 2
+    );
+
+    // Units only: a donation to the reserves doesn't move it
+    s.components[0].mint(&s.token.address, &1000);
+    assert_eq!(
+        s.token.implied_price(&quote, &oracle.address),
+        9 * PRICE_SCALE / 2
+    );
+}
+
+#[test]
+fn test_implied_price_traps() {
+    let s = setup(&[2, 3]);
+    let oracle = s.set_oracle(PRICE_SCALE);
+    let quote = Address::random(&s.env);
+    oracle.set_price(&quote, &0, &s.env.ledger().sequence());
+    assert!(s.token.try_implied_price(&quote, &oracle.address).is_err());
+
+    oracle.set_price(&quote, &PRICE_SCALE, &s.env.ledger().sequence());
+    s.advance(11);
+    assert_eq!(
+        s.token.try_implied_price(&quote, &oracle.address),
+        Err(Ok(Error::StalePrice.into()))
+    );
+}
+
+#[test]
+fn test_premium_discount_bps() {
+    let s = setup(&[2, 3]);
+    let oracle = s.set_oracle(PRICE_SCALE);
+    let quote = Address::random(&s.env);
+    oracle.set_price(&quote, &PRICE_SCALE, &s.env.ledger().sequence());
+    // Intrinsic price is 5
+    let bps = |market_price: i128| {
+        s.token
+            .premium_discount_bps(&market_price, &quote, &oracle.address)
+    };
+    assert_eq!(bps(55 * PRICE_SCALE / 10), 1000);
+    assert_eq!(bps(5 * PRICE_SCALE), 0);
+    assert_eq!(bps(45 * PRICE_SCALE / 10), -1000);
+
+    // Worthless components give no intrinsic price to deviate from
+    for component in s.components.iter() {
+        oracle.set_price(&component.address, &0, &s.env.ledger().sequence());
+    }
+    assert_eq!(bps(5 * PRICE_SCALE), 0);
+}
+
+#[test]
+fn test_premium_discount_bps_rejects_stale_quotes() {
+    let s = setup(&[2, 3]);
+    let oracle = s.set_oracle(PRICE_SCALE);
+    let quote = Address::random(&s.env);
+    oracle.set_price(&quote, &PRICE_SCALE, &s.env.ledger().sequence());
+    s.advance(11);
+    assert_eq!(
+        s.token
+            .try_premium_discount_bps(&PRICE_SCALE, &quote, &oracle.address),
+        Err(Ok(Error::StalePrice.into()))
+    );
+}
+
+// plan_rebalance() to 'amounts' through a new intermediate token, priced by 'oracle' at 1
+fn plan<'a>(
+    s: &Setup<'a>,
+    amounts: [u32; 2],
+    oracle: &MockOracleClient<'a>,
+) -> crate::plan::RebalancePlan {
+    let intermediate = create_component(&s.env, DECIMALS);
+    s.token.add_intermediate(&intermediate.address);
+    oracle.set_price(
+        &intermediate.address,
+        &PRICE_SCALE,
+        &s.env.ledger().sequence(),
+    );
+    let params = AuctionParams {
+        start_price: PRICE_SCALE,
+        min_price: PRICE_SCALE / 2,
+        duration_ledgers: 100,
+        min_fill: 1,
+    };
+    s.token.plan_rebalance(
+        &Vec::from_array(
+            &s.env,
+            [
+                s.components[0].address.clone(),
+                s.components[1].address.clone(),
+            ],
+        ),
+        &Vec::from_array(&s.env, amounts),
+        &Vec::from_array(&s.env, [params.clone(), params]),
+        &intermediate.address,
+        &oracle.address,
+    )
+}
+
+#[test]
+fn test_plan_rebalance() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let oracle = s.set_oracle(PRICE_SCALE);
+    let plan = plan(&s, [2, 1], &oracle);
+    assert_eq!(plan.legs.len(), 1);
+    let leg = plan.legs.get(0).unwrap();
+    assert_eq!(leg.component, s.components[1].address);
+    assert_eq!(leg.quantity, 200);
+    assert_eq!(leg.intermediate_at_start, 200);
+    assert_eq!(leg.intermediate_at_min, 100);
+    assert_eq!(leg.intermediate_at_oracle, 200);
+    assert_eq!(plan.worst_case_intermediate, -100);
+    assert_eq!(plan.amounts, Vec::from_array(&s.env, [2, 1]));
+}
+
+#[test]
+fn test_plan_rebalance_rejects_stale_quotes() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let oracle = s.set_oracle(PRICE_SCALE);
+    s.advance(11);
+    let intermediate = create_component(&s.env, DECIMALS);
+    s.token.add_intermediate(&intermediate.address);
+    oracle.set_price(
+        &intermediate.address,
+        &PRICE_SCALE,
+        &s.env.ledger().sequence(),
+    );
+    let params = AuctionParams {
+        start_price: PRICE_SCALE,
+        min_price: PRICE_SCALE / 2,
+        duration_ledgers: 100,
+        min_fill: 1,
+    };
+    // The intermediate quote is fresh, the component quotes are not
+    assert_eq!(
+        s.token
+            .try_plan_rebalance(
+                &Vec::from_array(
+                    &s.env,
+                    [
+                        s.components[0].address.clone(),
+                        s.components[1].address.clone(),
+                    ],
+                ),
+                &Vec::from_array(&s.env, [2, 1]),
+                &Vec::from_array(&s.env, [params.clone(), params]),
+                &intermediate.address,
+                &oracle.address,
+            )
+            .err(),
+        Some(Ok(Error::StalePrice.into()))
+    );
+}
+
+#[test]
+fn test_spend_allowance_emits_remaining() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    let spender = Address::random(&s.env);
+    let to = Address::random(&s.env);
+    s.mint(&holder, 100);
+    s.token
+        .approve(&holder, &spender, &100, &(s.env.ledger().sequence() + 10));
+
+    let remaining = |s: &Setup| {
+        let events = s.events(ALLOWANCE, SPENT);
+        let (topics, data) = events.last().unwrap();
+        assert_eq!(
+            Address::try_from_val(&s.env, &topics.get(2).unwrap()).unwrap(),
+            holder
+        );
+        assert_eq!(
+            Address::try_from_val(&s.env, &topics.get(3).unwrap()).unwrap(),
+            spender
+        );
+        AmountEvent::try_from_val(&s.env, data).unwrap().amount
+    };
+    s.token.transfer_from(&spender, &holder, &to, &30);
+    assert_eq!(remaining(&s), 70);
+    s.token.transfer_from(&spender, &holder, &to, &70);
+    assert_eq!(remaining(&s), 0);
+    assert_eq!(
+        s.token.try_transfer_from(&spender, &holder, &to, &1),
+        Err(Ok(Error::InsufficientAllowance.into()))
+    );
+}
+
+#[test]
+fn test_expired_allowance_cannot_be_spent() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    let spender = Address::random(&s.env);
+    s.mint(&holder, 100);
+    s.token
+        .approve(&holder, &spender, &100, &(s.env.ledger().sequence() + 10));
+    s.advance(11);
+    assert_eq!(s.token.allowance(&holder, &spender), 0);
+    assert_eq!(
+        s.token.try_transfer_from(&spender, &holder, &spender, &1),
+        Err(Ok(Error::InsufficientAllowance.into()))
+    );
+}
+
+#[test]
+fn test_max_allowance_never_decrements() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    let spender = Address::random(&s.env);
+    let to = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let expiration = s.env.ledger().sequence() + 10;
+    s.token.approve(&holder, &spender, &i128::MAX, &expiration);
+
+    s.token.transfer_from(&spender, &holder, &to, &30);
+    s.token.transfer_from(&spender, &holder, &to, &20);
+    assert_eq!(s.token.allowance(&holder, &spender), i128::MAX);
+    assert!(s.events(ALLOWANCE, SPENT).is_empty());
+
+    // A finite allowance still decrements
+    s.token.approve(&holder, &spender, &40, &expiration);
+    s.token.transfer_from(&spender, &holder, &to, &30);
+    assert_eq!(s.token.allowance(&holder, &spender), 10);
+
+    // Infinite, but not past its expiration
+    s.token.approve(&holder, &spender, &i128::MAX, &expiration);
+    s.advance(11);
+    assert_eq!(
+        s.token.try_transfer_from(&spender, &holder, &to, &1),
+        Err(Ok(Error::InsufficientAllowance.into()))
+    );
+}
+
+#[test]
+fn test_my_redeemable() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    assert!(s.token.my_redeemable(&holder).is_empty());
+    s.mint(&holder, 100);
+    assert_eq!(
+        s.token.my_redeemable(&holder),
+        Vec::from_array(
+            &s.env,
+            [
+                (s.components[0].address.clone(), 200),
+                (s.components[1].address.clone(), 300)
+            ]
+        )
+    );
+}
+
+// Initialize a fresh token with 'decimal' decimals over one component with 'component_decimals'
+fn try_initialize_decimals(
+    decimal: u32,
+    component_decimals: u32,
+) -> Result<
+    Result<(), soroban_sdk::ConversionError>,
+    Result<soroban_sdk::Error, soroban_sdk::InvokeError>,
+> {
+    let env = Env::default();
+    let component = create_component(&env, component_decimals);
+    let token =
+        ConstellationTokenClient::new(&env, &env.register_contract(None, ConstellationToken));
+    token.try_initialize(
+        &decimal,
+        &Vec::from_array(&env, [component.address.clone()]),
+        &Vec::from_array(&env, [1u32]),
+        &Address::random(&env),
+        &Address::random(&env),
+        &String::from_str(&env, "Constellation"),
+        &String::from_str(&env, "CTKN"),
+        &false,
+        &false,
+    )
+}
+
+#[test]
+fn test_initialize_decimals_bounds() {
+    assert_eq!(try_initialize_decimals(0, 7), Ok(Ok(())));
+    assert_eq!(try_initialize_decimals(18, 18), Ok(Ok(())));
+    assert_eq!(
+        try_initialize_decimals(19, 19),
+        Err(Ok(Error::InvalidDecimals.into()))
+    );
+    assert_eq!(
+        try_initialize_decimals(u32::MAX, 7),
+        Err(Ok(Error::InvalidDecimals.into()))
+    );
+}
+
+// The BalanceEntry stored for 'addr', or None if the entry isn't one
+fn stored_balance_entry(s: &Setup, addr: &Address) -> Option<BalanceEntry> {
+    s.env.as_contract(&s.token.address, || {
+        let raw: Val = s
+            .env
+            .storage()
+            .persistent()
+            .get(&DataKey::Balance(addr.clone()))
+            .unwrap();
+        BalanceEntry::try_from_val(&s.env, &raw).ok()
+    })
+}
+
+#[test]
+fn test_legacy_balance_entry_is_upgraded_on_write() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    let to = Address::random(&s.env);
+    s.mint(&holder, 100);
+    // A bare i128, as balances were stored before BalanceEntry
+    let legacy = Address::random(&s.env);
+    s.env.as_contract(&s.token.address, || {
+        s.env
+            .storage()
+            .persistent()
+            .set(&DataKey::Balance(legacy.clone()), &50_i128);
+    });
+    assert!(stored_balance_entry(&s, &legacy).is_none());
+    assert_eq!(s.token.balance(&legacy), 50);
+    assert!(!s.token.is_frozen(&legacy));
+
+    s.token.transfer(&legacy, &to, &20);
+    let entry = stored_balance_entry(&s, &legacy).unwrap();
+    assert_eq!(entry.amount, 30);
+    assert!(entry.authorized);
+    assert_eq!(entry.locked_until, 0);
+    assert_eq!(stored_balance_entry(&s, &to).unwrap().amount, 20);
+
+    // Freezing clears the authorized flag
+    s.token.freeze_account(&holder);
+    assert!(!stored_balance_entry(&s, &holder).unwrap().authorized);
+    assert_eq!(stored_balance_entry(&s, &holder).unwrap().amount, 100);
+}
+
+#[test]
+fn test_latest_snapshot() {
+    let s = setup(&[1]);
+    let alice = Address::random(&s.env);
+    assert!(s.token.latest_snapshot().is_none());
+    s.mint(&alice, 100);
+    let first = s.token.snapshot();
+    s.advance(5);
+    s.mint(&alice, 20);
+    let second = s.token.snapshot();
+    assert_eq!(second, first + 1);
+
+    let snapshot = s.token.latest_snapshot().unwrap();
+    assert_eq!(snapshot.id, second);
+    assert_eq!(snapshot.ledger, 105);
+    assert_eq!(snapshot.total_supply, 120);
+}
+
+#[test]
+fn test_cancel_rebalance_reverts_unfilled_amounts() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    let intermediate = s.start_rebalance(&[c0, c1], &[4, 1]);
+
+    s.token.cancel_rebalance();
+    assert_eq!(s.token.getAmounts(), Vec::from_array(&s.env, [2, 3]));
+    assert_eq!(s.events(REBALANCE, CANCEL).len(), 1);
+
+    // The auctions are closed and mint is open again
+    let bidder = Address::random(&s.env);
+    intermediate.mint(&bidder, &1000);
+    assert_eq!(
+        s.token.try_bid(&bidder, c1, &10),
+        Err(Ok(Error::NoRebalance.into()))
+    );
+    s.mint(&holder, 10);
+    assert_eq!(
+        s.token.try_cancel_rebalance(),
+        Err(Ok(Error::NoRebalance.into()))
+    );
+}
+
+#[test]
+fn test_rebalance_snapshot() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    assert_eq!(s.token.rebalance_snapshot(), None);
+
+    s.start_rebalance(&[c0, c1], &[2, 1]);
+    let expected = Vec::from_array(&s.env, [(c0.clone(), 2), (c1.clone(), 3)]);
+    assert_eq!(s.token.rebalance_snapshot(), Some(expected.clone()));
+    s.token.finalize_rebalance();
+    assert_eq!(s.token.rebalance_snapshot(), None);
+
+    s.start_rebalance(&[c0, c1], &[2, 1]);
+    assert_eq!(s.token.rebalance_snapshot(), Some(expected));
+    s.token.cancel_rebalance();
+    assert_eq!(s.token.rebalance_snapshot(), None);
+}
+
+#[test]
+fn test_auction_reaches_min_price() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    s.start_rebalance(&[c0, c1], &[2, 1]);
+
+    // Halfway down the price curve
+    s.advance(50);
+    assert!(!s.token.check_min_price(c1));
+    assert!(!s.token.auction_status(c1).reached_min);
+    assert!(s.events(REBALANCE, MIN_PRICE).is_empty());
+
+    s.advance(60);
+    assert!(s.token.check_min_price(c1));
+    let status = s.token.auction_status(c1);
+    assert!(status.reached_min);
+    assert_eq!(status.current_price, PRICE_SCALE / 2);
+    let events = s.events(REBALANCE, MIN_PRICE);
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        AmountEvent::try_from_val(&s.env, &events[0].1)
+            .unwrap()
+            .amount,
+        PRICE_SCALE / 2
+    );
+
+    // Flagged once
+    assert!(s.token.check_min_price(c1));
+    assert_eq!(s.events(REBALANCE, MIN_PRICE).len(), 1);
+    assert_eq!(
+        s.token.try_check_min_price(c0),
+        Err(Ok(Error::NoAuction.into()))
+    );
+}
+
+#[test]
+fn test_auction_filled_by_several_bidders() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    // Sells 200 of the second component
+    let intermediate = s.start_rebalance(&[c0, c1], &[2, 1]);
+
+    let mut bidders = std::vec::Vec::new();
+    for _ in 0..3 {
+        let bidder = Address::random(&s.env);
+        intermediate.mint(&bidder, &1000);
+        bidders.push(bidder);
+    }
+    // Sold amounts are rounded up to whole units, so the backing left never falls short
+    assert_eq!(s.token.bid(&bidders[0], c1, &50), 50);
+    assert_eq!(s.token.auction_status(c1).current_amount, 2);
+    assert_eq!(s.token.bid(&bidders[1], c1, &100), 100);
+    assert_eq!(s.token.auction_status(c1).current_amount, 1);
+    // Only the 50 left are filled
+    assert_eq!(s.token.bid(&bidders[2], c1, &80), 50);
+
+    let status = s.token.auction_status(c1);
+    assert_eq!(status.filled, 200);
+    assert_eq!(status.remaining, 0);
+    assert_eq!(status.current_amount, 1);
+    assert_eq!(s.components[1].balance(&s.token.address), 100);
+    assert_eq!(s.components[1].balance(&bidders[2]), 50);
+    assert_eq!(
+        s.token.try_bid(&bidders[0], c1, &1),
+        Err(Ok(Error::AuctionFilled.into()))
+    );
+}
+
+#[test]
+fn test_implied_value_follows_auction_prices() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    // Nothing has traded yet
+    assert_eq!(s.token.implied_value(), 0);
+
+    // Sells 200 of the second component; the first is never auctioned, so it counts as 0
+    let intermediate = s.start_rebalance(&[c0, c1], &[2, 1]);
+    assert_eq!(s.token.implied_value(), 3 * PRICE_SCALE);
+    s.advance(50);
+    assert_eq!(s.token.implied_value(), 3 * PRICE_SCALE * 3 / 4);
+
+    let bidder = Address::random(&s.env);
+    intermediate.mint(&bidder, &1000);
+    s.token.bid(&bidder, c1, &100);
+    assert_eq!(s.token.implied_value(), 2 * PRICE_SCALE * 3 / 4);
+    s.advance(10);
+    assert_eq!(s.token.implied_value(), 2 * PRICE_SCALE * 7 / 10);
+
+    // Once the auction closes, the last bid's price is used
+    s.token.finalize_rebalance();
+    assert_eq!(s.token.implied_value(), 2 * PRICE_SCALE * 3 / 4);
+
+    let uninitialized =
+        ConstellationTokenClient::new(&s.env, &s.env.register_contract(None, ConstellationToken));
+    assert_eq!(
+        uninitialized.try_implied_value(),
+        Err(Ok(Error::NotInitialized.into()))
+    );
+}
+
+// Decode one of the token's Constellation events into its payload, returning its action, version and seq
+fn decode_event(s: &Setup, topics: &Vec<Val>, data: &Val) -> (Symbol, u32, u64) {
+    let env = &s.env;
+    let area = Symbol::try_from_val(env, &topics.get(0).unwrap()).unwrap();
+    if area == ERROR {
+        let code = u32::try_from_val(env, &topics.get(1).unwrap()).unwrap();
+        let (version, seq) = if code == Error::InsufficientAllowance as u32 {
+            let event = AllowanceErrorEvent::try_from_val(env, data).unwrap();
+            (event.version, event.seq)
+        } else {
+            let event = ErrorEvent::try_from_val(env, data).unwrap();
+            (event.version, event.seq)
+        };
+        return (ERROR, version, seq);
+    }
+    let action = Symbol::try_from_val(env, &topics.get(1).unwrap()).unwrap();
+    let (version, seq) = if area == ALLOWANCE && action == SPENT
+        || area == REBALANCE && action == MIN_PRICE
+        || area == FEE && (action == PERFORMANCE || action == DUST)
+        || area == SOLVENCY && action == EPSILON
+    {
+        let event = AmountEvent::try_from_val(env, data).unwrap();
+        (event.version, event.seq)
+    } else if area == REBALANCE && (action == START || action == REPRICED) {
+        let event = RebalanceStartEvent::try_from_val(env, data).unwrap();
+        (event.version, event.seq)
+    } else if area == REBALANCE && action == PROGRESS {
+        let event = RebalanceProgressEvent::try_from_val(env, data).unwrap();
+        (event.version, event.seq)
+    } else if area == REBALANCE && (action == FINALIZE || action == CANCEL) {
+        let event = RebalanceEndEvent::try_from_val(env, data).unwrap();
+        (event.version, event.seq)
+    } else if area == REBALANCE && (action == UNIT || action == RECONCILE) {
+        let event = UnitUpdatedEvent::try_from_val(env, data).unwrap();
+        (event.version, event.seq)
+    } else if area == METADATA && action == UPDATED {
+        let event = MetadataUpdatedEvent::try_from_val(env, data).unwrap();
+        (event.version, event.seq)
+    } else if area == ISSUANCE && action == WIND_DOWN {
+        let event = WindDownEvent::try_from_val(env, data).unwrap();
+        (event.version, event.seq)
+    } else {
+        panic!("no payload type for this event")
+    };
+    (action, version, seq)
+}
+
+// Decode the events published since the last call, checking they continue the sequence
+fn decode_new_events(s: &Setup, decoded: &mut std::vec::Vec<Symbol>) {
+    let areas = [
+        ISSUANCE, ESCROW, ALLOWANCE, REBALANCE, ERROR, FEE, METADATA, SOLVENCY,
+    ];
+    for (contract, topics, data) in s.env.events().all().iter() {
+        if contract != s.token.address {
+            continue;
+        }
+        match Symbol::try_from_val(&s.env, &topics.get(0).unwrap()) {
+            Ok(area) if areas.contains(&area) => {}
+            // Standard token events
+            _ => continue,
+        }
+        let (action, version, seq) = decode_event(s, &topics, &data);
+        if seq > decoded.len() as u64 {
+            assert_eq!(seq, decoded.len() as u64 + 1);
+            assert_eq!(version, EVENT_VERSION);
+            decoded.push(action);
+        }
+    }
+}
+
+#[test]
+fn test_events_decode_into_their_payloads() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    let spender = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    let mut decoded = std::vec::Vec::new();
+
+    s.token.approve(&holder, &spender, &50, &200);
+    s.token.transfer_from(&spender, &holder, &spender, &10);
+    decode_new_events(&s, &mut decoded);
+
+    let intermediate = s.start_rebalance(&[c0, c1], &[2, 1]);
+    decode_new_events(&s, &mut decoded);
+    s.token.reprice_auction(
+        c1,
+        &AuctionParams {
+            start_price: PRICE_SCALE,
+            min_price: PRICE_SCALE / 2,
+            duration_ledgers: 100,
+            min_fill: 1,
+        },
+    );
+    decode_new_events(&s, &mut decoded);
+    s.advance(110);
+    s.token.check_min_price(c1);
+    decode_new_events(&s, &mut decoded);
+    let bidder = Address::random(&s.env);
+    intermediate.mint(&bidder, &1000);
+    s.token.bid(&bidder, c1, &200);
+    decode_new_events(&s, &mut decoded);
+    s.token.finalize_rebalance();
+    decode_new_events(&s, &mut decoded);
+
+    s.token.set_metadata(
+        &String::from_str(&s.env, "Renamed"),
+        &String::from_str(&s.env, "RNM"),
+    );
+    decode_new_events(&s, &mut decoded);
+
+    assert_eq!(
+        decoded,
+        [SPENT, START, REPRICED, MIN_PRICE, PROGRESS, FINALIZE, UPDATED]
+    );
+
+    // The error event published before a trap carries its own payload
+    assert!(s
+        .token
+        .try_transfer_from(&spender, &holder, &spender, &100)
+        .is_err());
+    decode_new_events(&s, &mut decoded);
+    assert_eq!(decoded.last(), Some(&ERROR));
+}
+
+#[test]
+fn test_operator_can_only_start_rebalances() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    let operator = Address::random(&s.env);
+    let stranger = Address::random(&s.env);
+    assert_eq!(s.token.get_operator(), None);
+    s.token.set_operator(&operator);
+    assert_eq!(s.token.get_operator(), Some(operator.clone()));
+
+    // The manager proposes, the operator starts
+    let intermediate = create_component(&s.env, DECIMALS);
+    s.token.add_intermediate(&intermediate.address);
+    let params = AuctionParams {
+        start_price: PRICE_SCALE,
+        min_price: PRICE_SCALE / 2,
+        duration_ledgers: 100,
+        min_fill: 1,
+    };
+    s.token.propose_rebalance(
+        &Vec::from_array(&s.env, [c0.clone(), c1.clone()]),
+        &Vec::from_array(&s.env, [2, 1]),
+        &Vec::from_array(&s.env, [params.clone(), params]),
+        &intermediate.address,
+    );
+    assert_eq!(
+        s.token.try_start_rebalance(&stranger),
+        Err(Ok(Error::NotAuthorized.into()))
+    );
+    s.token.start_rebalance(&operator);
+    assert_eq!(s.env.auths()[0].0, operator);
+    assert!(s.token.rebalance_snapshot().is_some());
+    s.token.cancel_rebalance();
+
+    // The operator's signature doesn't satisfy manager-only functions
+    s.env.mock_auths(&[MockAuth {
+        address: &operator,
+        invoke: &MockAuthInvoke {
+            contract: &s.token.address,
+            fn_name: "set_operator",
+            args: (&stranger,).into_val(&s.env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(s.token.try_set_operator(&stranger).is_err());
+    s.env.mock_auths(&[MockAuth {
+        address: &operator,
+        invoke: &MockAuthInvoke {
+            contract: &s.token.address,
+            fn_name: "set_performance_fee",
+            args: (100_u32,).into_val(&s.env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(s.token.try_set_performance_fee(&100).is_err());
+    assert_eq!(s.token.get_operator(), Some(operator));
+
+    // The manager can still start rebalances itself
+    s.env.mock_all_auths();
+    s.start_rebalance(&[c0, c1], &[2, 1]);
+    assert_eq!(s.env.auths()[0].0, s.manager);
+}
+
+#[test]
+fn test_pause_component() {
+    let s = setup(&[2, 3, 4]);
+    let c1 = &s.components[1].address;
+    assert!(s.token.get_basket().iter().all(|c| !c.paused));
+
+    s.token.pause_component(c1);
+    let paused: std::vec::Vec<bool> = s.token.get_basket().iter().map(|c| c.paused).collect();
+    assert_eq!(paused, [false, true, false]);
+
+    s.token.unpause_component(c1);
+    assert!(s.token.get_basket().iter().all(|c| !c.paused));
+    assert!(s
+        .token
+        .try_pause_component(&Address::random(&s.env))
+        .is_err());
+}
+
+#[test]
+fn test_views_write_nothing() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    let spender = Address::random(&s.env);
+    s.mint(&holder, 100);
+    s.token.approve(
+        &holder,
+        &spender,
+        &50,
+        &(s.env.ledger().sequence() + 3 * DAY_IN_LEDGERS),
+    );
+    // Far enough that a bump would extend the instance and balance entries
+    s.advance(2 * DAY_IN_LEDGERS);
+
+    let before = s.env.to_ledger_snapshot();
+    s.token.balance(&holder);
+    s.token.allowance(&holder, &spender);
+    s.token.getComponents();
+    s.token.getAmounts();
+    s.token.decimals();
+    s.token.name();
+    s.token.symbol();
+    s.token.total_supply();
+    assert_eq!(
+        s.env.to_ledger_snapshot().ledger_entries,
+        before.ledger_entries
+    );
+
+    // Maintenance still extends the instance
+    s.token.extend_ttl();
+    assert_ne!(
+        s.env.to_ledger_snapshot().ledger_entries,
+        before.ledger_entries
+    );
+
+    let uninitialized =
+        ConstellationTokenClient::new(&s.env, &s.env.register_contract(None, ConstellationToken));
+    assert_eq!(
+        uninitialized.try_extend_ttl(),
+        Err(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_minter_burner_is_the_admin() {
+    let s = setup(&[1]);
+    assert_eq!(s.token.minter_burner(), s.admin);
+    assert_eq!(s.token.get_admin(), s.admin);
+
+    let minter_burner = Address::random(&s.env);
+    s.token.set_admin(&minter_burner);
+    assert_eq!(s.token.minter_burner(), minter_burner);
+    assert_eq!(s.token.get_admin(), minter_burner);
+
+    let uninitialized =
+        ConstellationTokenClient::new(&s.env, &s.env.register_contract(None, ConstellationToken));
+    assert_eq!(
+        uninitialized.try_minter_burner(),
+        Err(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_components_page() {
+    let s = setup(&[1, 2, 3]);
+    let page = |start: u32, limit: u32| -> std::vec::Vec<u32> {
+        s.token
+            .get_components_page(&start, &limit)
+            .iter()
+            .map(|c| c.units)
+            .collect()
+    };
+    assert_eq!(s.token.component_count(), 3);
+    assert_eq!(page(0, 2), [1, 2]);
+    assert_eq!(page(2, 2), [3]);
+    assert_eq!(page(1, 2), [2, 3]);
+    assert_eq!(page(0, 1000), [1, 2, 3]);
+    // Empty tail pages rather than traps
+    assert!(page(3, 2).is_empty());
+    assert!(page(u32::MAX, u32::MAX).is_empty());
+    assert!(page(0, 0).is_empty());
+    assert_eq!(
+        s.token.get_components_page(&0, &1).get(0).unwrap().address,
+        s.components[0].address
+    );
+
+    let uninitialized =
+        ConstellationTokenClient::new(&s.env, &s.env.register_contract(None, ConstellationToken));
+    assert_eq!(
+        uninitialized.try_component_count(),
+        Err(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_bid_guards_publish_error_events() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    let intermediate = s.start_rebalance(&[c0, c1], &[2, 1]);
+    let bidder = Address::random(&s.env);
+    intermediate.mint(&bidder, &1000);
+
+    // The first component isn't auctioned
+    assert_eq!(
+        s.token.try_bid(&bidder, c0, &30),
+        Err(Ok(Error::NoAuction.into()))
+    );
+    s.token.bid(&bidder, c1, &200);
+    assert_eq!(
+        s.token.try_bid(&bidder, c1, &5),
+        Err(Ok(Error::AuctionFilled.into()))
+    );
+
+    let mut errors = std::vec::Vec::new();
+    for (contract, topics, data) in s.env.events().all().iter() {
+        if contract == s.token.address
+            && Symbol::try_from_val(&s.env, &topics.get(0).unwrap()) == Ok(ERROR)
+        {
+            let code = u32::try_from_val(&s.env, &topics.get(1).unwrap()).unwrap();
+            let event = ErrorEvent::try_from_val(&s.env, &data).unwrap();
+            errors.push((code, event.component, event.shortfall));
+        }
+    }
+    assert_eq!(
+        errors,
+        [
+            (Error::NoAuction as u32, c0.clone(), 30),
+            (Error::AuctionFilled as u32, c1.clone(), 5)
+        ]
+    );
+}
+
+#[test]
+fn test_is_component() {
+    let s = setup(&[2, 3]);
+    assert!(s.token.is_component(&s.components[0].address));
+    assert!(s.token.is_component(&s.components[1].address));
+    assert!(!s.token.is_component(&Address::random(&s.env)));
+    assert!(!s.token.is_component(&s.token.address));
+
+    let uninitialized =
+        ConstellationTokenClient::new(&s.env, &s.env.register_contract(None, ConstellationToken));
+    assert_eq!(
+        uninitialized.try_is_component(&s.components[0].address),
+        Err(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_unit_of() {
+    let s = setup(&[2, 3]);
+    assert_eq!(s.token.unit_of(&s.components[0].address), Some(2));
+    assert_eq!(s.token.unit_of(&s.components[1].address), Some(3));
+    assert_eq!(s.token.unit_of(&Address::random(&s.env)), None);
+
+    let uninitialized =
+        ConstellationTokenClient::new(&s.env, &s.env.register_contract(None, ConstellationToken));
+    assert_eq!(
+        uninitialized.try_unit_of(&s.components[0].address),
+        Err(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_entry_points_before_initialize() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let token =
+        ConstellationTokenClient::new(&env, &env.register_contract(None, ConstellationToken));
+    let (a, b) = (Address::random(&env), Address::random(&env));
+    let expected = Some(Ok(Error::NotInitialized.into()));
+
+    assert_eq!(token.try_mint(&a, &1).err(), expected);
+    assert_eq!(token.try_burn(&a, &1).err(), expected);
+    assert_eq!(token.try_transfer(&a, &b, &1).err(), expected);
+    assert_eq!(token.try_transfer_from(&a, &b, &a, &1).err(), expected);
+    assert_eq!(token.try_approve(&a, &b, &1, &200).err(), expected);
+    assert_eq!(token.try_allowance(&a, &b).err(), expected);
+    assert_eq!(token.try_balance(&a).err(), expected);
+    assert_eq!(token.try_total_supply().err(), expected);
+    assert_eq!(token.try_name().err(), expected);
+    assert_eq!(token.try_getAmounts().err(), expected);
+    assert_eq!(token.try_set_admin(&a).err(), expected);
+    assert_eq!(token.try_snapshot().err(), expected);
+    assert_eq!(token.try_bid(&a, &b, &1).err(), expected);
+
+    // A rejected initialize leaves nothing behind, so it can be retried
+    let component = create_component(&env, DECIMALS);
+    let name = String::from_str(&env, "Constellation");
+    let symbol = String::from_str(&env, "CTKN");
+    assert!(token
+        .try_initialize(
+            &DECIMALS,
+            &Vec::from_array(&env, [component.address.clone()]),
+            &Vec::from_array(&env, [1, 2]),
+            &a,
+            &b,
+            &name,
+            &symbol,
+            &false,
+            &false,
+        )
+        .is_err());
+    assert_eq!(token.try_decimals().err(), expected);
+    token.initialize(
+        &DECIMALS,
+        &Vec::from_array(&env, [component.address.clone()]),
+        &Vec::from_array(&env, [1]),
+        &a,
+        &b,
+        &name,
+        &symbol,
+        &false,
+        &false,
+    );
+    assert_eq!(token.total_supply(), 0);
+}
+
+#[test]
+fn test_self_address() {
+    let s = setup(&[1]);
+    assert_eq!(s.token.self_address(), s.token.address);
+
+    let uninitialized =
+        ConstellationTokenClient::new(&s.env, &s.env.register_contract(None, ConstellationToken));
+    assert_eq!(
+        uninitialized.try_self_address(),
+        Err(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_execution_report() {
+    let s = setup(&[2, 3]);
+    let holder = Address::random(&s.env);
+    s.mint(&holder, 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    // Buys 100 of the first component and sells 100 of the second
+    let intermediate = s.start_rebalance(&[c0, c1], &[3, 2]);
+    let bidders: std::vec::Vec<Address> = (0..4).map(|_| Address::random(&s.env)).collect();
+    for bidder in &bidders[..2] {
+        intermediate.mint(bidder, &1000);
+    }
+    for bidder in &bidders[2..] {
+        s.components[0].mint(bidder, &1000);
+    }
+    assert_eq!(
+        s.token.try_get_execution_report(&1).err(),
+        Some(Ok(Error::NoReport.into()))
+    );
+
+    // Sold for 40 at 1 and 45 at 0.75, bought for 30 at 0.75 and 30 at 0.5
+    s.token.bid(&bidders[0], c1, &40);
+    s.advance(50);
+    s.token.bid(&bidders[1], c1, &60);
+    s.token.bid(&bidders[2], c0, &40);
+    s.advance(50);
+    s.token.bid(&bidders[3], c0, &60);
+    s.token.finalize_rebalance();
+
+    let report = s.token.get_execution_report(&1);
+    assert_eq!(report.len(), 2);
+    for leg in report.iter() {
+        if leg.component == *c0 {
+            assert_eq!(leg.direction, Direction::Buy);
+            assert_eq!(leg.filled, 100);
+            assert_eq!(leg.intermediate_amount, 60);
+            assert_eq!(leg.avg_price, PRICE_SCALE * 6 / 10);
+        } else {
+            assert_eq!(leg.component, *c1);
+            assert_eq!(leg.direction, Direction::Sell);
+            assert_eq!(leg.filled, 100);
+            assert_eq!(leg.intermediate_amount, 85);
+            assert_eq!(leg.avg_price, PRICE_SCALE * 85 / 100);
+        }
+    }
+    assert_eq!(intermediate.balance(&s.token.address), 25);
+    assert_eq!(
+        s.token.try_get_execution_report(&2).err(),
+        Some(Ok(Error::NoReport.into()))
+    );
+}
+
+#[test]
+fn test_initialize_validates_roles_and_components() {
+    let env = Env::default();
+    let token =
+        ConstellationTokenClient::new(&env, &env.register_contract(None, ConstellationToken));
+    let (c0, c1) = (
+        create_component(&env, DECIMALS).address,
+        create_component(&env, DECIMALS).address,
+    );
+    let (admin, manager) = (Address::random(&env), Address::random(&env));
+    let initialize =
+        |components: &[&Address], units: &[u32], admin: &Address, manager: &Address| {
+            let mut addresses = Vec::new(&env);
+            for component in components {
+                addresses.push_back((*component).clone());
+            }
+            token.try_initialize(
+                &DECIMALS,
+                &addresses,
+                &Vec::from_slice(&env, units),
+                admin,
+                manager,
+                &String::from_str(&env, "Constellation"),
+                &String::from_str(&env, "CTKN"),
+                &false,
+                &false,
+            )
+        };
+
+    let invalid_roles = Err(Ok(Error::InvalidRoles.into()));
+    let invalid_component = Err(Ok(Error::InvalidComponent.into()));
+    assert_eq!(
+        initialize(&[&c0, &c1], &[1, 2], &admin, &admin),
+        invalid_roles
+    );
+    assert_eq!(
+        initialize(&[&c0, &c1], &[1, 2], &token.address, &manager),
+        invalid_roles
+    );
+    assert_eq!(
+        initialize(&[&c0, &c1], &[1, 2], &admin, &token.address),
+        invalid_roles
+    );
+    assert_eq!(
+        initialize(&[&c0, &token.address], &[1, 2], &admin, &manager),
+        invalid_component
+    );
+    assert_eq!(
+        initialize(&[&c0, &c0], &[1, 2], &admin, &manager),
+        invalid_component
+    );
+    assert_eq!(
+        initialize(&[&c0, &c1], &[1, 0], &admin, &manager),
+        Err(Ok(Error::InvalidUnits.into()))
+    );
+    assert!(initialize(&[&c0, &c1], &[1, 2], &admin, &manager).is_ok());
+    assert_eq!(token.getComponents(), Vec::from_array(&env, [c0, c1]));
+}
+
+#[test]
+fn test_propose_rebalance_validates_targets() {
+    let s = setup(&[1, 2]);
+    let intermediate = create_component(&s.env, DECIMALS);
+    s.token.add_intermediate(&intermediate.address);
+    let c0 = &s.components[0].address;
+    let params = AuctionParams {
+        start_price: PRICE_SCALE,
+        min_price: PRICE_SCALE / 2,
+        duration_ledgers: 100,
+        min_fill: 1,
+    };
+    let propose = |targets: [&Address; 2]| {
+        s.token.try_propose_rebalance(
+            &Vec::from_array(&s.env, [targets[0].clone(), targets[1].clone()]),
+            &Vec::from_array(&s.env, [1, 1]),
+            &Vec::from_array(&s.env, [params.clone(), params.clone()]),
+            &intermediate.address,
+        )
+    };
+
+    assert_eq!(propose([c0, c0]), Err(Ok(Error::InvalidComponent.into())));
+    assert_eq!(
+        propose([c0, &s.token.address]),
+        Err(Ok(Error::InvalidComponent.into()))
+    );
+    assert!(propose([c0, &s.components[1].address]).is_ok());
+}
+
+#[test]
+fn test_mint_and_burn_enabled_flags() {
+    let s = setup(&[1]);
+    let alice = Address::random(&s.env);
+    s.mint(&alice, 100);
+    assert!(s.token.mint_enabled());
+    assert!(s.token.burn_enabled());
+
+    // Minting off keeps redemption open
+    s.token.set_mint_enabled(&false);
+    assert!(!s.token.mint_enabled());
+    s.components[0].mint(&s.token.address, &10);
+    assert_eq!(
+        s.token.try_mint(&alice, &10),
+        Err(Ok(Error::MintDisabled.into()))
+    );
+    s.token.burn(&alice, &10);
+    s.token.set_mint_enabled(&true);
+    s.token.mint(&alice, &10);
+
+    // Burning off keeps issuance open
+    s.token.set_burn_enabled(&false);
+    assert!(!s.token.burn_enabled());
+    assert!(s.token.mint_enabled());
+    assert_eq!(
+        s.token.try_burn(&alice, &10),
+        Err(Ok(Error::BurnDisabled.into()))
+    );
+    s.mint(&alice, 10);
+    s.token.set_burn_enabled(&true);
+    s.token.burn(&alice, &10);
+    assert_eq!(s.token.balance(&alice), 100);
+}
+
+#[test]
+fn test_intermediate_allowlist() {
+    let s = setup(&[1, 2]);
+    let intermediate = create_component(&s.env, DECIMALS);
+    assert_eq!(s.token.list_intermediates(), Vec::new(&s.env));
+    let params = AuctionParams {
+        start_price: PRICE_SCALE,
+        min_price: PRICE_SCALE / 2,
+        duration_ledgers: 100,
+        min_fill: 1,
+    };
+    let propose = || {
+        s.token.try_propose_rebalance(
+            &Vec::from_array(
+                &s.env,
+                [
+                    s.components[0].address.clone(),
+                    s.components[1].address.clone(),
+                ],
+            ),
+            &Vec::from_array(&s.env, [2, 1]),
+            &Vec::from_array(&s.env, [params.clone(), params.clone()]),
+            &intermediate.address,
+        )
+    };
+
+    assert_eq!(
+        propose().err(),
+        Some(Ok(Error::IntermediateNotAllowed.into()))
+    );
+    s.token.add_intermediate(&intermediate.address);
+    assert_eq!(
+        s.token.list_intermediates(),
+        Vec::from_array(&s.env, [intermediate.address.clone()])
+    );
+    assert!(propose().is_ok());
+
+    // Delisting after the proposal stops it from starting
+    s.token.remove_intermediate(&intermediate.address);
+    assert_eq!(s.token.list_intermediates(), Vec::new(&s.env));
+    assert_eq!(
+        s.token.try_start_rebalance(&s.manager).err(),
+        Some(Ok(Error::IntermediateNotAllowed.into()))
+    );
+    s.token.add_intermediate(&intermediate.address);
+    s.token.start_rebalance(&s.manager);
+    assert!(s.token.rebalance_snapshot().is_some());
+}
+
+#[test]
+fn test_backing_balances_page() {
+    let s = setup(&[1, 2, 3]);
+    s.mint(&Address::random(&s.env), 10);
+    let page = |start: u32, count: u32| -> std::vec::Vec<i128> {
+        s.token
+            .backing_balances_page(&start, &count)
+            .iter()
+            .map(|(_, held)| held)
+            .collect()
+    };
+    assert_eq!(page(0, 3), [10, 20, 30]);
+    assert_eq!(page(0, 2), [10, 20]);
+    assert_eq!(page(2, 2), [30]);
+    assert!(page(3, 1).is_empty());
+    assert!(page(u32::MAX, u32::MAX).is_empty());
+    assert_eq!(
+        s.token.backing_balances_page(&1, &1).get(0).unwrap().0,
+        s.components[1].address
+    );
+
+    let uninitialized =
+        ConstellationTokenClient::new(&s.env, &s.env.register_contract(None, ConstellationToken));
+    assert_eq!(
+        uninitialized.try_backing_balances_page(&0, &1).err(),
+        Some(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_initialize_sorts_components() {
+    let env = Env::default();
+    let mut components: std::vec::Vec<Address> = (0..3)
+        .map(|_| create_component(&env, DECIMALS).address)
+        .collect();
+    // Descending, so sorting has to move every pair
+    components.sort_by(|a, b| b.cmp(a));
+    let units = [1u32, 2, 3];
+    let initialize = |units: &[u32], sort: bool| {
+        let token =
+            ConstellationTokenClient::new(&env, &env.register_contract(None, ConstellationToken));
+        let result = token.try_initialize(
+            &DECIMALS,
+            &Vec::from_slice(&env, &components),
+            &Vec::from_slice(&env, units),
+            &Address::random(&env),
+            &Address::random(&env),
+            &String::from_str(&env, "Constellation"),
+            &String::from_str(&env, "CTKN"),
+            &sort,
+            &false,
+        );
+        (token, result)
+    };
+
+    let (sorted, _) = initialize(&units, true);
+    let mut ascending = components.clone();
+    ascending.reverse();
+    assert_eq!(sorted.getComponents(), Vec::from_slice(&env, &ascending));
+    assert_eq!(sorted.getAmounts(), Vec::from_array(&env, [3, 2, 1]));
+    for (component, unit) in components.iter().zip(units) {
+        assert_eq!(sorted.unit_of(component), Some(unit));
+    }
+
+    // Insertion order stays the default
+    let (unsorted, _) = initialize(&units, false);
+    assert_eq!(unsorted.getComponents(), Vec::from_slice(&env, &components));
+    assert_eq!(unsorted.getAmounts(), Vec::from_array(&env, units));
+
+    let (_, result) = initialize(&[1, 2], true);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_set_metadata() {
+    let s = setup(&[1]);
+    s.token.set_metadata(
+        &String::from_str(&s.env, "Constellation v2"),
+        &String::from_str(&s.env, "CTKN2"),
+    );
+    assert_eq!(s.token.name(), String::from_str(&s.env, "Constellation v2"));
+    assert_eq!(s.token.symbol(), String::from_str(&s.env, "CTKN2"));
+    assert_eq!(s.token.decimals(), DECIMALS);
+    let events = s.events(METADATA, UPDATED);
+    assert_eq!(events.len(), 1);
+    let event = MetadataUpdatedEvent::try_from_val(&s.env, &events[0].1).unwrap();
+    assert_eq!(event.old_symbol, String::from_str(&s.env, "CTKN"));
+    assert_eq!(event.new_symbol, String::from_str(&s.env, "CTKN2"));
+
+    assert_eq!(
+        s.token.try_set_metadata(
+            &String::from_str(&s.env, "Constellation v3"),
+            &String::from_str(&s.env, "")
+        ),
+        Err(Ok(Error::InvalidMetadata.into()))
+    );
+    assert_eq!(
+        s.token.try_set_metadata(
+            &String::from_str(&s.env, ""),
+            &String::from_str(&s.env, "CTKN3")
+        ),
+        Err(Ok(Error::InvalidMetadata.into()))
+    );
+    assert_eq!(s.token.symbol(), String::from_str(&s.env, "CTKN2"));
+    assert_eq!(s.token.decimals(), DECIMALS);
+}
+
+#[test]
+fn test_reprice_floored_auction() {
+    let s = setup(&[2, 3]);
+    s.mint(&Address::random(&s.env), 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    // Sells 200 of the second component from 1 down to 0.5
+    let intermediate = s.start_rebalance(&[c0, c1], &[2, 1]);
+    let bidder = Address::random(&s.env);
+    intermediate.mint(&bidder, &1000);
+    s.token.bid(&bidder, c1, &50);
+    s.advance(110);
+    assert!(s.token.check_min_price(c1));
+
+    let params = |start: i128, min: i128| AuctionParams {
+        start_price: PRICE_SCALE * start / 10,
+        min_price: PRICE_SCALE * min / 10,
+        duration_ledgers: 100,
+        min_fill: 1,
+    };
+    s.token.reprice_auction(c1, &params(9, 6));
+    let status = s.token.auction_status(c1);
+    assert_eq!(status.current_price, PRICE_SCALE * 9 / 10);
+    assert!(!status.reached_min);
+    assert_eq!(status.filled, 50);
+    assert_eq!(status.remaining, 150);
+
+    // Bids follow the new curve and add to the fills so far
+    s.token.bid(&bidder, c1, &50);
+    assert_eq!(intermediate.balance(&bidder), 1000 - 50 - 45);
+    let status = s.token.auction_status(c1);
+    assert_eq!(status.filled, 100);
+    assert_eq!(status.remaining, 100);
+    assert_eq!(s.token.rebalance_epoch(), 1);
+
+    assert_eq!(
+        s.token.try_reprice_auction(c0, &params(9, 6)),
+        Err(Ok(Error::NoAuction.into()))
+    );
+    // With an oracle, the new curve must span its price of 1
+    let oracle = s.set_oracle(PRICE_SCALE);
+    oracle.set_price(
+        &intermediate.address,
+        &PRICE_SCALE,
+        &s.env.ledger().sequence(),
+    );
+    assert_eq!(
+        s.token.try_reprice_auction(c1, &params(8, 5)),
+        Err(Ok(Error::PriceOutOfRange.into()))
+    );
+    s.token.reprice_auction(c1, &params(11, 7));
+    assert_eq!(s.token.auction_status(c1).filled, 100);
+
+    s.token.finalize_rebalance();
+    assert_eq!(
+        s.token.try_reprice_auction(c1, &params(11, 7)),
+        Err(Ok(Error::NoRebalance.into()))
+    );
+}
+
+#[test]
+fn test_high_water_supply() {
+    let s = setup(&[1]);
+    let alice = Address::random(&s.env);
+    assert_eq!(s.token.high_water_supply(), 0);
+
+    s.mint(&alice, 100);
+    s.token.burn(&alice, &60);
+    s.mint(&alice, 20);
+    assert_eq!(s.token.total_supply(), 60);
+    assert_eq!(s.token.high_water_supply(), 100);
+    s.mint(&alice, 50);
+    assert_eq!(s.token.high_water_supply(), 110);
+
+    let uninitialized =
+        ConstellationTokenClient::new(&s.env, &s.env.register_contract(None, ConstellationToken));
+    assert_eq!(
+        uninitialized.try_high_water_supply(),
+        Err(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_min_fill_defaults_from_decimals() {
+    let s = setup(&[2, 3]);
+    s.mint(&Address::random(&s.env), 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    let c18 = create_component(&s.env, 18).address;
+    let intermediate = create_component(&s.env, DECIMALS);
+    s.token.add_intermediate(&intermediate.address);
+    let params = |min_fill: i128| AuctionParams {
+        start_price: PRICE_SCALE,
+        min_price: PRICE_SCALE / 2,
+        duration_ledgers: 100,
+        min_fill,
+    };
+    s.token.propose_rebalance(
+        &Vec::from_array(&s.env, [c0.clone(), c1.clone(), c18.clone()]),
+        &Vec::from_array(&s.env, [3, 1, 1]),
+        &Vec::from_array(&s.env, [params(5), params(0), params(0)]),
+        &intermediate.address,
+    );
+    s.token.start_rebalance(&s.manager);
+
+    // An explicit min_fill overrides the 10^(decimals - 6) default
+    assert_eq!(s.token.auction_status(c0).min_fill, 5);
+    assert_eq!(s.token.auction_status(c1).min_fill, 10);
+    assert_eq!(s.token.auction_status(&c18).min_fill, 10i128.pow(12));
+
+    let bidder = Address::random(&s.env);
+    intermediate.mint(&bidder, &1000);
+    assert_eq!(
+        s.token.try_bid(&bidder, c1, &9),
+        Err(Ok(Error::BidTooSmall.into()))
+    );
+    assert_eq!(s.token.bid(&bidder, c1, &10), 10);
+    // A bid completing the auction may be smaller: buying all 100 of the 18-decimal component
+    MockTokenClient::new(&s.env, &c18).mint(&bidder, &100);
+    intermediate.mint(&s.token.address, &100);
+    assert_eq!(s.token.bid(&bidder, &c18, &100), 100);
+    assert_eq!(s.token.auction_status(&c18).remaining, 0);
+}
+
+#[test]
+fn test_allowance_info() {
+    let s = setup(&[1]);
+    let holder = Address::random(&s.env);
+    let spender = Address::random(&s.env);
+    s.mint(&holder, 100);
+    assert_eq!(s.token.allowance_info(&holder, &spender), (0, 0));
+
+    let expiration = s.env.ledger().sequence() + 10;
+    s.token.approve(&holder, &spender, &100, &expiration);
+    assert_eq!(s.token.allowance_info(&holder, &spender), (100, expiration));
+    s.token.transfer_from(&spender, &holder, &spender, &30);
+    assert_eq!(s.token.allowance_info(&holder, &spender), (70, expiration));
+    // A lapsed allowance keeps its expiration so wallets can show when it lapsed
+    s.advance(11);
+    assert_eq!(s.token.allowance_info(&holder, &spender), (0, expiration));
+
+    let uninitialized =
+        ConstellationTokenClient::new(&s.env, &s.env.register_contract(None, ConstellationToken));
+    assert_eq!(
+        uninitialized.try_allowance_info(&holder, &spender).err(),
+        Some(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_allowance_expiration() {
+    let s = setup(&[1]);
+    let holder = Address::random(&s.env);
+    let spender = Address::random(&s.env);
+    assert_eq!(s.token.allowance_expiration(&holder, &spender), 0);
+
+    let expiration = s.env.ledger().sequence() + 500;
+    s.token.approve(&holder, &spender, &100, &expiration);
+    assert_eq!(s.token.allowance_expiration(&holder, &spender), expiration);
+    s.token.approve(&holder, &spender, &100, &(expiration + 1));
+    assert_eq!(
+        s.token.allowance_expiration(&holder, &spender),
+        expiration + 1
+    );
+
+    let uninitialized =
+        ConstellationTokenClient::new(&s.env, &s.env.register_contract(None, ConstellationToken));
+    assert_eq!(
+        uninitialized.try_allowance_expiration(&holder, &spender),
+        Err(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_backing_epsilon() {
+    let s = setup(&[2, 3]);
+    s.mint(&Address::random(&s.env), 100);
+    // A 1-stroop deficit in the second component
+    s.components[1].transfer(&s.token.address, &Address::random(&s.env), &1);
+    let deficits = |s: &Setup| -> std::vec::Vec<i128> {
+        s.token.verify_backing().iter().map(|(_, d)| d).collect()
+    };
+    assert!(!s.token.is_fully_backed());
+    assert_eq!(deficits(&s), [0, -1]);
+
+    s.token.set_backing_epsilon(&1);
+    assert_eq!(s.token.backing_epsilon(), 1);
+    assert_eq!(s.events(SOLVENCY, EPSILON).len(), 1);
+    assert!(s.token.is_fully_backed());
+    // verify_backing still reports the exact deficit
+    assert_eq!(deficits(&s), [0, -1]);
+
+    s.components[1].transfer(&s.token.address, &Address::random(&s.env), &1);
+    assert!(!s.token.is_fully_backed());
+    assert_eq!(deficits(&s), [0, -2]);
+
+    assert!(s.token.try_set_backing_epsilon(&101).is_err());
+    assert!(s.token.try_set_backing_epsilon(&-1).is_err());
+    assert_eq!(s.token.backing_epsilon(), 1);
+}
+
+#[test]
+fn test_approve_rejects_past_expiration() {
+    let s = setup(&[1]);
+    let holder = Address::random(&s.env);
+    let spender = Address::random(&s.env);
+    let ledger = s.env.ledger().sequence();
+    assert_eq!(
+        s.token.try_approve(&holder, &spender, &100, &(ledger - 1)),
+        Err(Ok(Error::ExpiredApproval.into()))
+    );
+    // Expiring at the current ledger is still usable for this ledger
+    s.token.approve(&holder, &spender, &100, &ledger);
+    assert_eq!(s.token.allowance(&holder, &spender), 100);
+
+    // Clearing needs no meaningful expiration
+    s.token.approve(&holder, &spender, &0, &0);
+    assert_eq!(s.token.allowance(&holder, &spender), 0);
+}
+
+#[test]
+fn test_interface_version() {
+    let s = setup(&[1]);
+    assert_eq!(s.token.interface_version(), 1);
+    // Storage-free, so it answers before initialize too
+    let uninitialized =
+        ConstellationTokenClient::new(&s.env, &s.env.register_contract(None, ConstellationToken));
+    assert_eq!(uninitialized.interface_version(), 1);
+}
+
+#[test]
+fn test_initialize_config_size_cap() {
+    let env = Env::default();
+    env.budget().reset_unlimited();
+    // 341 components take 16368 of the 16384-byte budget, leaving 16 bytes for name and symbol
+    let mut components = Vec::new(&env);
+    let mut amounts = Vec::new(&env);
+    for _ in 0..341 {
+        components.push_back(create_component(&env, DECIMALS).address);
+        amounts.push_back(1);
+    }
+    let token =
+        ConstellationTokenClient::new(&env, &env.register_contract(None, ConstellationToken));
+    let initialize = |name: &str, symbol: &str| {
+        token.try_initialize(
+            &DECIMALS,
+            &components,
+            &amounts,
+            &Address::random(&env),
+            &Address::random(&env),
+            &String::from_str(&env, name),
+            &String::from_str(&env, symbol),
+            &false,
+            &false,
+        )
+    };
+
+    assert_eq!(
+        initialize("Constellation", "CTKN").err(),
+        Some(Ok(Error::ConfigTooLarge.into()))
+    );
+    assert!(initialize("Constellation", "CTK").is_ok());
+    assert_eq!(token.component_count(), 341);
+}
+
+#[test]
+fn test_transfer_hook() {
+    let s = setup(&[1]);
+    let alice = Address::random(&s.env);
+    let bob = Address::random(&s.env);
+    s.mint(&alice, 100);
+    assert_eq!(s.token.transfer_hook(), None);
+
+    let hook =
+        MockTransferHookClient::new(&s.env, &s.env.register_contract(None, MockTransferHook));
+    s.token.set_transfer_hook(&Some(hook.address.clone()));
+    assert_eq!(s.token.transfer_hook(), Some(hook.address.clone()));
+    s.token.transfer(&alice, &bob, &10);
+    s.token
+        .approve(&alice, &bob, &50, &(s.env.ledger().sequence() + 10));
+    s.token.transfer_from(&bob, &alice, &bob, &10);
+    assert_eq!(s.token.balance(&bob), 20);
+
+    hook.set_transfers_allowed(&false);
+    assert_eq!(
+        s.token.try_transfer(&alice, &bob, &10),
+        Err(Ok(Error::TransferRejected.into()))
+    );
+    assert_eq!(
+        s.token.try_transfer_from(&bob, &alice, &bob, &10),
+        Err(Ok(Error::TransferRejected.into()))
+    );
+    assert_eq!(s.token.balance(&bob), 20);
+
+    // Removing the hook lifts the restriction
+    s.token.set_transfer_hook(&None);
+    s.token.transfer(&alice, &bob, &10);
+    assert_eq!(s.token.balance(&bob), 30);
+}
+
+#[test]
+fn test_transfer_and_call() {
+    let s = setup(&[1]);
+    let alice = Address::random(&s.env);
+    s.mint(&alice, 100);
+    let receiver = MockReceiverClient::new(&s.env, &s.env.register_contract(None, MockReceiver));
+    let data = Bytes::from_slice(&s.env, b"deposit");
+
+    s.token
+        .transfer_and_call(&alice, &receiver.address, &30, &data);
+    assert_eq!(s.token.balance(&receiver.address), 30);
+    assert_eq!(
+        receiver.last_transfer(),
+        Some((alice.clone(), 30, data.clone()))
+    );
+
+    // A rejecting callback reverts the transfer
+    receiver.set_rejecting(&true);
+    assert!(s
+        .token
+        .try_transfer_and_call(&alice, &receiver.address, &30, &data)
+        .is_err());
+    assert_eq!(s.token.balance(&receiver.address), 30);
+    assert_eq!(s.token.balance(&alice), 70);
+
+    // An account recipient gets a plain transfer; Address::random() makes contract addresses
+    let bob = Address::from_string(&String::from_str(
+        &s.env,
+        "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
+    ));
+    s.token.transfer_and_call(&alice, &bob, &20, &data);
+    assert_eq!(s.token.balance(&bob), 20);
+    assert_eq!(s.token.balance(&alice), 50);
+}
+
+#[test]
+fn test_denylist() {
+    let s = setup(&[1]);
+    let alice = Address::random(&s.env);
+    let bob = Address::random(&s.env);
+    s.mint(&alice, 100);
+    s.mint(&bob, 100);
+    s.token
+        .approve(&alice, &bob, &100, &(s.env.ledger().sequence() + 10));
+
+    s.token.add_to_denylist(&alice);
+    assert_eq!(s.token.denylist(), Vec::from_array(&s.env, [alice.clone()]));
+    // Denied sender
+    assert_eq!(
+        s.token.try_transfer(&alice, &bob, &10),
+        Err(Ok(Error::Denied.into()))
+    );
+    assert_eq!(
+        s.token.try_transfer_from(&bob, &alice, &bob, &10),
+        Err(Ok(Error::Denied.into()))
+    );
+    // Denied recipient
+    assert_eq!(
+        s.token.try_transfer(&bob, &alice, &10),
+        Err(Ok(Error::Denied.into()))
+    );
+    s.components[0].mint(&s.token.address, &10);
+    assert_eq!(s.token.try_mint(&alice, &10), Err(Ok(Error::Denied.into())));
+    // A listed holder can still exit
+    s.token.burn(&alice, &10);
+
+    s.token.remove_from_denylist(&alice);
+    assert!(s.token.denylist().is_empty());
+    s.token.transfer(&alice, &bob, &10);
+    s.token.transfer(&bob, &alice, &10);
+    s.token.mint(&alice, &10);
+    assert_eq!(s.token.balance(&alice), 100);
+}
+
+#[test]
+fn test_dump_config() {
+    let s = setup(&[2, 3]);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    let operator = Address::random(&s.env);
+    let recipient = Address::random(&s.env);
+    let denied = Address::random(&s.env);
+    let hook = s.env.register_contract(None, MockTransferHook);
+    s.token.set_operator(&operator);
+    let oracle = s.set_oracle(PRICE_SCALE);
+    s.token.set_performance_fee(&100);
+    s.token.set_fee_recipient(&recipient);
+    s.token.set_dust_epsilon(&3);
+    s.token.set_backing_epsilon(&2);
+    s.token.set_transfer_hook(&Some(hook.clone()));
+    s.token.add_to_denylist(&denied);
+    s.token.pause_component(c1);
+    s.token.set_mint_enabled(&false);
+    let intermediate = create_component(&s.env, DECIMALS);
+    oracle.set_price(
+        &intermediate.address,
+        &PRICE_SCALE,
+        &s.env.ledger().sequence(),
+    );
+    s.token.add_intermediate(&intermediate.address);
+    let params = AuctionParams {
+        start_price: PRICE_SCALE * 2,
+        min_price: PRICE_SCALE / 2,
+        duration_ledgers: 100,
+        min_fill: 1,
+    };
+    s.token.propose_rebalance(
+        &Vec::from_array(&s.env, [c0.clone(), c1.clone()]),
+        &Vec::from_array(&s.env, [2, 1]),
+        &Vec::from_array(&s.env, [params.clone(), params]),
+        &intermediate.address,
+    );
+
+    let dump = s.token.dump_config();
+    assert_eq!(dump.storage_version, 2);
+    assert_eq!(dump.admin, s.token.get_admin());
+    assert_eq!(dump.manager, s.manager);
+    assert_eq!(dump.operator, Vec::from_array(&s.env, [operator]));
+    assert_eq!(s.token.get_operator(), dump.operator.first());
+    assert_eq!(dump.decimals, s.token.decimals());
+    assert_eq!(dump.name, s.token.name());
+    assert_eq!(dump.symbol, s.token.symbol());
+    assert_eq!(dump.components, s.token.getComponents());
+    assert_eq!(dump.amounts, s.token.getAmounts());
+    assert_eq!(
+        dump.paused_components,
+        Vec::from_array(&s.env, [c1.clone()])
+    );
+    assert!(!dump.uniform_decimals);
+    assert_eq!(dump.ttl.threshold, s.token.ttl_config().threshold);
+    assert_eq!(dump.ttl.bump_amount, s.token.ttl_config().bump_amount);
+    assert_eq!(dump.oracle.len(), 1);
+    assert_eq!(dump.oracle.get(0).unwrap().oracle, oracle.address);
+    assert_eq!(
+        dump.oracle.get(0).unwrap().max_price_age_ledgers,
+        s.token.get_oracle().unwrap().max_price_age_ledgers
+    );
+    assert_eq!(dump.performance_fee_bps, s.token.performance_fee());
+    assert_eq!(dump.fee_recipient, s.token.fee_recipient());
+    assert_eq!(dump.dust_epsilon, s.token.dust_epsilon());
+    assert_eq!(dump.backing_epsilon, s.token.backing_epsilon());
+    assert_eq!(dump.transfer_hook, Vec::from_array(&s.env, [hook]));
+    assert_eq!(dump.denylist, s.token.denylist());
+    assert_eq!(dump.intermediates, s.token.list_intermediates());
+    assert_eq!(dump.mint_enabled, s.token.mint_enabled());
+    assert!(!dump.mint_enabled);
+    assert_eq!(dump.burn_enabled, s.token.burn_enabled());
+    assert!(!dump.mint_halted);
+    assert_eq!(dump.wind_down_ledger, s.token.wind_down_ledger());
+    assert_eq!(dump.rebalance_proposal.len(), 1);
+    assert_eq!(
+        dump.rebalance_proposal.get(0).unwrap().target_amounts,
+        s.token.rebalance_proposal().unwrap().target_amounts
+    );
+    assert!(dump.rebalance.is_empty());
+    assert_eq!(dump.rebalance_epoch, s.token.rebalance_epoch());
+
+    let uninitialized =
+        ConstellationTokenClient::new(&s.env, &s.env.register_contract(None, ConstellationToken));
+    assert_eq!(
+        uninitialized.try_dump_config().err(),
+        Some(Ok(Error::NotInitialized.into()))
+    );
+}
+
+#[test]
+fn test_allowances_of() {
+    let s = setup(&[1]);
+    let holder = Address::random(&s.env);
+    let spenders: std::vec::Vec<Address> = (0..4).map(|_| Address::random(&s.env)).collect();
+    s.mint(&holder, 100);
+    let ledger = s.env.ledger().sequence();
+    s.token.approve(&holder, &spenders[0], &50, &(ledger + 5));
+    s.token.approve(&holder, &spenders[1], &50, &(ledger + 100));
+    s.token.approve(&holder, &spenders[2], &40, &(ledger + 100));
+    s.token
+        .transfer_from(&spenders[1], &holder, &spenders[1], &30);
+    s.advance(6);
+
+    // Expired, partially consumed, untouched and never approved
+    assert_eq!(
+        s.token
+            .allowances_of(&holder, &Vec::from_slice(&s.env, &spenders)),
+        Vec::from_array(&s.env, [0, 20, 40, 0])
+    );
+
+    // The failure reports what was available
+    assert_eq!(
+        s.token
+            .try_transfer_from(&spenders[1], &holder, &spenders[1], &25),
+        Err(Ok(Error::InsufficientAllowance.into()))
+    );
+    let (_, _, data) = s
+        .env
+        .events()
+        .all()
+        .iter()
+        .filter(|(contract, topics, _)| {
+            *contract == s.token.address
+                && Symbol::try_from_val(&s.env, &topics.get(0).unwrap()) == Ok(ERROR)
+                && u32::try_from_val(&s.env, &topics.get(1).unwrap())
+                    == Ok(Error::InsufficientAllowance as u32)
+        })
+        .last()
+        .unwrap();
+    let event = AllowanceErrorEvent::try_from_val(&s.env, &data).unwrap();
+    assert_eq!(event.spender, spenders[1]);
+    assert_eq!(event.requested, 25);
+    assert_eq!(event.available, 20);
+}
+
+#[test]
+fn test_bid_with_a_single_signature() {
+    let s = setup(&[2, 3]);
+    s.mint(&Address::random(&s.env), 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    // Buys 100 of the first component
+    let intermediate = s.start_rebalance(&[c0, c1], &[3, 3]);
+    intermediate.mint(&s.token.address, &1000);
+    let bidder = Address::random(&s.env);
+    s.components[0].mint(&bidder, &150);
+
+    // One signed bid whose auth tree covers the payment; no approve beforehand
+    let sign = |amount: i128| {
+        s.env.mock_auths(&[MockAuth {
+            address: &bidder,
+            invoke: &MockAuthInvoke {
+                contract: &s.token.address,
+                fn_name: "bid",
+                args: (&bidder, c0, amount).into_val(&s.env),
+                sub_invokes: &[MockAuthInvoke {
+                    contract: c0,
+                    fn_name: "transfer",
+                    args: (&bidder, &s.token.address, amount).into_val(&s.env),
+                    sub_invokes: &[],
+                }],
+            },
+        }]);
+    };
+
+    // Signed for more than remains, the capped fill doesn't match the signed transfer
+    sign(150);
+    assert!(s.token.try_bid(&bidder, c0, &150).is_err());
+    assert_eq!(s.components[0].balance(&bidder), 150);
+
+    sign(60);
+    assert_eq!(s.token.bid(&bidder, c0, &60), 60);
+    assert_eq!(s.env.auths().len(), 1);
+    assert_eq!(s.env.auths()[0].0, bidder);
+    sign(40);
+    assert_eq!(s.token.bid(&bidder, c0, &40), 40);
+    assert_eq!(s.components[0].balance(&bidder), 50);
+    assert_eq!(intermediate.balance(&bidder), 100);
+    assert_eq!(s.token.auction_status(c0).remaining, 0);
+}
+
+#[test]
+fn test_require_uniform_decimals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (c7, c7b, c9) = (
+        create_component(&env, 7).address,
+        create_component(&env, 7).address,
+        create_component(&env, 9).address,
+    );
+    let initialize = |components: [&Address; 2], uniform: bool| {
+        let token =
+            ConstellationTokenClient::new(&env, &env.register_contract(None, ConstellationToken));
+        let result = token.try_initialize(
+            &DECIMALS,
+            &Vec::from_array(&env, [components[0].clone(), components[1].clone()]),
+            &Vec::from_array(&env, [1, 1]),
+            &Address::random(&env),
+            &Address::random(&env),
+            &String::from_str(&env, "Constellation"),
+            &String::from_str(&env, "CTKN"),
+            &false,
+            &uniform,
+        );
+        (token, result)
+    };
+
+    // Mixed decimals are only rejected when the flag is on
+    assert_eq!(
+        initialize([&c7, &c9], true).1.err(),
+        Some(Ok(Error::InvalidDecimals.into()))
+    );
+    assert!(initialize([&c7, &c9], false).1.is_ok());
+    let (token, result) = initialize([&c7, &c7b], true);
+    assert!(result.is_ok());
+    assert!(token.dump_config().uniform_decimals);
+
+    // Rebalance targets are held to the same rule
+    let intermediate = create_component(&env, DECIMALS);
+    token.add_intermediate(&intermediate.address);
+    let params = AuctionParams {
+        start_price: PRICE_SCALE,
+        min_price: PRICE_SCALE / 2,
+        duration_ledgers: 100,
+        min_fill: 1,
+    };
+    let propose = |added: &Address| {
+        token.try_propose_rebalance(
+            &Vec::from_array(&env, [c7.clone(), c7b.clone(), added.clone()]),
+            &Vec::from_array(&env, [1, 1, 1]),
+            &Vec::from_array(&env, [params.clone(), params.clone(), params.clone()]),
+            &intermediate.address,
+        )
+    };
+    assert_eq!(propose(&c9).err(), Some(Ok(Error::InvalidDecimals.into())));
+    assert!(propose(&create_component(&env, 7).address).is_ok());
+}
+
+// Start a rebalance buying 100 of the first component, fill 60 of it with 'fill'(setup, bidder, data) after
+// 10 ledgers, and return the auction, amounts and balances after the fill
+fn state_after_fill(
+    fill: fn(&Setup, &Address, Bytes),
+) -> (i128, i128, std::vec::Vec<u32>, i128, i128, i128, i128) {
+    let s = setup(&[2, 3]);
+    s.mint(&Address::random(&s.env), 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    let intermediate = s.start_rebalance(&[c0, c1], &[3, 3]);
+    intermediate.mint(&s.token.address, &1000);
+    let bidder = Address::random(&s.env);
+    s.components[0].mint(&bidder, &150);
+    s.advance(10);
+
+    let data = BidData {
+        component: c0.clone(),
+    }
+    .to_xdr(&s.env);
+    fill(&s, &bidder, data);
+    assert_eq!(s.env.auths().len(), 1);
+    assert_eq!(s.env.auths()[0].0, bidder);
+
+    let status = s.token.auction_status(c0);
+    (
+        status.filled,
+        status.remaining,
+        s.token.getAmounts().iter().collect(),
+        s.components[0].balance(&bidder),
+        intermediate.balance(&bidder),
+        s.components[0].balance(&s.token.address),
+        intermediate.balance(&s.token.address),
+    )
+}
+
+#[test]
+fn test_bid_with_transfer_and_call_matches_bid() {
+    // One signed bid whose auth tree covers the payment
+    let with_bid = state_after_fill(|s, bidder, _| {
+        let c0 = &s.components[0].address;
+        s.env.mock_auths(&[MockAuth {
+            address: bidder,
+            invoke: &MockAuthInvoke {
+                contract: &s.token.address,
+                fn_name: "bid",
+                args: (bidder, c0, 60_i128).into_val(&s.env),
+                sub_invokes: &[MockAuthInvoke {
+                    contract: c0,
+                    fn_name: "transfer",
+                    args: (bidder, &s.token.address, 60_i128).into_val(&s.env),
+                    sub_invokes: &[],
+                }],
+            },
+        }]);
+        assert_eq!(s.token.bid(bidder, c0, &60), 60);
+    });
+    // One signed transfer_and_call() on the component; the token's callback needs no signature of its own
+    let with_transfer = state_after_fill(|s, bidder, data| {
+        let c0 = &s.components[0];
+        s.env.mock_auths(&[MockAuth {
+            address: bidder,
+            invoke: &MockAuthInvoke {
+                contract: &c0.address,
+                fn_name: "transfer_and_call",
+                args: (bidder, &s.token.address, 60_i128, data.clone()).into_val(&s.env),
+                sub_invokes: &[],
+            },
+        }]);
+        c0.transfer_and_call(bidder, &s.token.address, &60, &data);
+    });
+
+    assert_eq!(with_bid, with_transfer);
+    // 60 filled at 0.95 intermediate token each
+    assert_eq!(with_bid, (60, 40, std::vec![2, 3], 90, 57, 260, 943));
+}
+
+#[test]
+fn test_bid_with_transfer_and_call_traps() {
+    let s = setup(&[2, 3]);
+    s.mint(&Address::random(&s.env), 100);
+    let (c0, c1) = (&s.components[0].address, &s.components[1].address);
+    let intermediate = s.start_rebalance(&[c0, c1], &[3, 3]);
+    intermediate.mint(&s.token.address, &1000);
+    let bidder = Address::random(&s.env);
+    s.components[0].mint(&bidder, &150);
+    s.components[1].mint(&bidder, &150);
+    let bid_data = |component: &Address| {
+        BidData {
+            component: component.clone(),
+        }
+        .to_xdr(&s.env)
+    };
+
+    // Only the component can report a transfer of it: a direct call, or a callback from another
+    // contract naming it, fails its require_auth()
+    s.env.mock_auths(&[]);
+    assert!(s
+        .token
+        .try_on_token_transfer(&bidder, &60, &bid_data(c0))
+        .is_err());
+    s.env.mock_auths(&[MockAuth {
+        address: &bidder,
+        invoke: &MockAuthInvoke {
+            contract: c1,
+            fn_name: "transfer_and_call",
+            args: (&bidder, &s.token.address, 60_i128, bid_data(c0)).into_val(&s.env),
+            sub_invokes: &[],
+        },
+    }]);
+    assert!(s.components[1]
+        .try_transfer_and_call(&bidder, &s.token.address, &60, &bid_data(c0))
+        .is_err());
+    assert_eq!(intermediate.balance(&bidder), 0);
+
+    s.env.mock_all_auths();
+    // More than remains can't be refunded from inside the callback
+    assert_eq!(
+        s.components[0].try_transfer_and_call(&bidder, &s.token.address, &150, &bid_data(c0)),
+        Err(Ok(Error::BidTooLarge.into()))
+    );
+    // The second component isn't auctioned
+    assert_eq!(
+        s.components[1].try_transfer_and_call(&bidder, &s.token.address, &60, &bid_data(c1)),
+        Err(Ok(Error::NoAuction.into()))
+    );
+    assert_eq!(
+        s.components[0].try_transfer_and_call(&bidder, &s.token.address, &60, &c0.to_xdr(&s.env)),
+        Err(Ok(Error::InvalidBidData.into()))
+    );
+    // Every failed callback reverted its transfer
+    assert_eq!(s.components[0].balance(&bidder), 150);
+    assert_eq!(s.components[1].balance(&bidder), 150);
+    assert_eq!(s.token.auction_status(c0).filled, 0);
+}
@@ -0,0 +1,46 @@
+// How long each call extends the instance for. Starts at the INSTANCE_* defaults and can be tuned by the
+// manager as archival costs change, without redeploying.
+use soroban_sdk::{contracttype, Env};
+
+use crate::component::bump_components;
+use crate::storage_types::{
+    DataKey, DAY_IN_LEDGERS, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+
+// Upper bound on the bump amount, below the network's maximum entry lifetime
+pub(crate) const MAX_BUMP_AMOUNT: u32 = 180 * DAY_IN_LEDGERS;
+
+#[derive(Clone)]
+#[contracttype]
+pub struct TtlConfig {
+    // Extend once the remaining TTL drops below this many ledgers
+    pub threshold: u32,
+    pub bump_amount: u32,
+}
+
+pub fn read_ttl_config(e: &Env) -> TtlConfig {
+    let key = DataKey::TtlConfig;
+    e.storage().instance().get(&key).unwrap_or(TtlConfig {
+        threshold: INSTANCE_LIFETIME_THRESHOLD,
+        bump_amount: INSTANCE_BUMP_AMOUNT,
+    })
+}
+
+pub fn write_ttl_config(e: &Env, config: &TtlConfig) {
+    if config.threshold < DAY_IN_LEDGERS
+        || config.threshold >= config.bump_amount
+        || config.bump_amount > MAX_BUMP_AMOUNT
+    {
+        panic!("ttl config must satisfy 1 day <= threshold < bump_amount <= 180 days")
+    }
+    let key = DataKey::TtlConfig;
+    e.storage().instance().set(&key, config);
+}
+
+pub fn bump_instance(e: &Env) {
+    let config = read_ttl_config(e);
+    e.storage()
+        .instance()
+        .bump(config.threshold, config.bump_amount);
+    bump_components(e, &config);
+}
@@ -0,0 +1,41 @@
+// Irreversible wind-down of the product. The manager announces it and, WIND_DOWN_NOTICE_LEDGERS later,
+// issuance stops for good and the performance fee stops accruing, while burning and pro-rata redemption
+// stay available. The notice gives holders time to react before the product closes to new money.
+// Nothing removes the stored ledger, so a wind-down can't be called off once initiated.
+use constellation_shared::events::emit_wind_down;
+use soroban_sdk::{panic_with_error, Env};
+
+use crate::error::Error;
+use crate::storage_types::{DataKey, DAY_IN_LEDGERS};
+
+pub(crate) const WIND_DOWN_NOTICE_LEDGERS: u32 = 7 * DAY_IN_LEDGERS;
+
+// Ledger the wind-down takes effect at, None if it was never initiated
+pub fn read_wind_down_ledger(e: &Env) -> Option<u32> {
+    let key = DataKey::WindDown;
+    e.storage().instance().get(&key)
+}
+
+// Returns the ledger the wind-down takes effect at; traps with WindDown if it was already initiated
+pub fn initiate_wind_down(e: &Env) -> u32 {
+    if read_wind_down_ledger(e).is_some() {
+        panic_with_error!(e, Error::WindDown);
+    }
+    let effective_ledger = e.ledger().sequence() + WIND_DOWN_NOTICE_LEDGERS;
+    e.storage().instance().set(&DataKey::WindDown, &effective_ledger);
+    emit_wind_down(e, effective_ledger);
+    effective_ledger
+}
+
+pub fn is_winding_down(e: &Env) -> bool {
+    match read_wind_down_ledger(e) {
+        Some(effective_ledger) => e.ledger().sequence() >= effective_ledger,
+        None => false,
+    }
+}
+
+pub fn check_not_winding_down(e: &Env) {
+    if is_winding_down(e) {
+        panic_with_error!(e, Error::WindDown);
+    }
+}